@@ -0,0 +1,438 @@
+//! Command-line argument definitions
+
+use std::path::PathBuf;
+
+use clap::builder::FalseyValueParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::mcp::{PathRequest, ResponseFormat, SymbolRequest};
+use crate::types::NodeKind;
+
+const EXIT_CODES: &str = "\
+EXIT CODES:
+    0  Success
+    1  Error
+    2  Invalid arguments
+    3  No index found, or the requested symbol or file is not indexed
+    4  The symbol name is ambiguous; the candidates are listed";
+
+/// Semantic code intelligence MCP server
+#[derive(Debug, Parser)]
+#[command(name = "codemap", version, after_help = EXIT_CODES)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Options shared by every command
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Project root directory
+    #[arg(long, global = true, env = "CODEMAP_ROOT", default_value = ".")]
+    pub root: String,
+
+    /// Index database file (default: <root>/.codemap/index.db)
+    #[arg(long, global = true, env = "CODEMAP_DB")]
+    pub db: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Shorthand for --format json
+    #[arg(long, global = true, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Load the grammar libraries listed in the project's
+    /// .codemap/config.json, running their code
+    #[arg(
+        long,
+        global = true,
+        env = "CODEMAP_ALLOW_GRAMMARS",
+        value_parser = FalseyValueParser::new()
+    )]
+    pub allow_grammars: bool,
+}
+
+impl GlobalArgs {
+    /// The output format after applying --json
+    pub fn output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.format
+        }
+    }
+}
+
+/// How command output is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Plain text for terminals and scripts
+    Text,
+    /// Markdown, as returned by the MCP tools
+    Markdown,
+    /// Versioned JSON, as in the MCP tools' structured content
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the MCP server (stdio transport unless --port is given)
+    Serve {
+        /// Serve over HTTP on this port
+        #[arg(long)]
+        port: Option<u16>,
+        /// Reindex files as they change while serving
+        #[arg(long)]
+        watch: bool,
+    },
+    /// Index a codebase
+    Index {
+        /// Directory to index (default: --root)
+        path: Option<String>,
+        /// Number of parser threads (default: one per CPU)
+        #[arg(long, default_value_t = 0)]
+        workers: usize,
+    },
+    /// Index a codebase, then reindex files as they change
+    Watch {
+        /// Directory to watch (default: --root)
+        path: Option<String>,
+    },
+    /// Show index statistics
+    Status {
+        /// Project directory (default: --root)
+        path: Option<String>,
+    },
+    /// Search for symbols by name, with typo tolerance
+    Search {
+        /// Symbol name, part of it, or words from its signature or docs; may
+        /// be omitted with --param-type or --returns
+        #[arg(default_value = "")]
+        query: String,
+        #[command(flatten)]
+        filter: ListArgs,
+        /// Only show symbols in this language (e.g. rust, python)
+        #[arg(long)]
+        language: Option<String>,
+        /// Only show symbols in files matching this glob (e.g. 'src/**/*.rs')
+        #[arg(long)]
+        path: Option<String>,
+        /// Only show functions taking a parameter of this type (e.g. '&mut Connection')
+        #[arg(long)]
+        param_type: Option<String>,
+        /// Only show functions returning this type; `_` matches any type (e.g. 'Result<_, AuthError>')
+        #[arg(long)]
+        returns: Option<String>,
+    },
+    /// Build context for a task description
+    Context {
+        /// Description of the task, bug or feature
+        #[arg(required = true, num_args = 1..)]
+        task: Vec<String>,
+        /// Maximum number of symbols to include
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// Show detailed information about a symbol
+    Node(SymbolArgs),
+    /// Find functions and methods that call a symbol
+    Callers(SymbolArgs),
+    /// Find functions and methods that a symbol calls
+    Callees(SymbolArgs),
+    /// Analyze the impact of changing a symbol
+    Impact(SymbolArgs),
+    /// Find call paths from one symbol to another
+    Path(PathArgs),
+    /// Find symbols with no incoming references
+    Unused(ListArgs),
+    /// Find classes and structs that implement or extend a symbol
+    Implementations(SymbolArgs),
+    /// Find symbols carrying a decorator, annotation or attribute
+    Decorated {
+        /// Decorator name, e.g. Transactional, app.route or tokio::main
+        decorator: String,
+        #[command(flatten)]
+        filter: ListArgs,
+    },
+    /// Show what contains a symbol and what it contains
+    Hierarchy(SymbolArgs),
+    /// List the symbols defined in a file
+    File {
+        /// File path relative to the project root
+        path: String,
+        #[command(flatten)]
+        filter: ListArgs,
+    },
+    /// Print the source code of a symbol
+    Definition {
+        #[command(flatten)]
+        target: SymbolTarget,
+        /// Lines of context before and after the definition
+        #[arg(long)]
+        context_lines: Option<u32>,
+    },
+    /// Find all references to a symbol
+    References(SymbolArgs),
+    /// Show which files or directories import which
+    Dependencies {
+        /// File (or directory, with --level module) to show the dependencies of
+        path: Option<String>,
+        /// Group dependencies by file or by directory
+        #[arg(long, value_parser = ["file", "module"], default_value = "file")]
+        level: String,
+        /// With a path, show only what it imports or what imports it
+        #[arg(long, value_parser = ["imports", "imported_by", "both"], default_value = "both")]
+        direction: String,
+        /// Maximum number of dependencies to show
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// List parse errors and the symbols they affect
+    Diagnostics {
+        /// Only show the errors of this file, relative to the project root
+        path: Option<String>,
+        /// Maximum number of files to show
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+    /// Analyze the impact of changing a range of lines
+    DiffImpact {
+        /// File path relative to the project root
+        file: String,
+        /// First changed line (1-indexed)
+        start_line: u32,
+        /// Last changed line (1-indexed)
+        end_line: u32,
+        /// Maximum number of affected symbols to show
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+}
+
+/// The symbol a command is about
+#[derive(Debug, Args)]
+pub struct SymbolTarget {
+    /// Function, method or type name, optionally qualified (e.g. Parser::parse)
+    #[arg(required_unless_present = "id")]
+    pub symbol: Option<String>,
+    /// Kind of the symbol when several share the name
+    #[arg(long, value_parser = parse_kind)]
+    pub kind: Option<String>,
+    /// File that defines the symbol, when several share the name
+    #[arg(long = "file", value_name = "PATH")]
+    pub file_path: Option<String>,
+    /// Id of the symbol, as listed for ambiguous names
+    #[arg(long)]
+    pub id: Option<i64>,
+}
+
+/// A symbol argument with its lookup and result options
+#[derive(Debug, Args)]
+pub struct SymbolArgs {
+    #[command(flatten)]
+    pub target: SymbolTarget,
+    /// Maximum number of results to show
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+/// The two ends of a call path, each selected like a [`SymbolTarget`]
+///
+/// With one end given by id, a single name selects the other end:
+/// `codemap path --from-id 12 save`.
+#[derive(Debug, Args)]
+pub struct PathArgs {
+    /// Starting symbol, optionally qualified (e.g. Server::run)
+    #[arg(required_unless_present = "from_id")]
+    pub from: Option<String>,
+    /// Target symbol, optionally qualified
+    #[arg(required_unless_present_any = ["to_id", "from_id"])]
+    pub to: Option<String>,
+    /// Kind of the starting symbol when several share the name
+    #[arg(long, value_parser = parse_kind)]
+    pub from_kind: Option<String>,
+    /// File that defines the starting symbol
+    #[arg(long, value_name = "PATH")]
+    pub from_file: Option<String>,
+    /// Id of the starting symbol
+    #[arg(long)]
+    pub from_id: Option<i64>,
+    /// Kind of the target symbol when several share the name
+    #[arg(long, value_parser = parse_kind)]
+    pub to_kind: Option<String>,
+    /// File that defines the target symbol
+    #[arg(long, value_name = "PATH")]
+    pub to_file: Option<String>,
+    /// Id of the target symbol
+    #[arg(long)]
+    pub to_id: Option<i64>,
+    /// Maximum number of paths to show
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+/// Filters for commands that list symbols
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only list symbols of this kind (e.g. function, struct)
+    #[arg(long, value_parser = parse_kind)]
+    pub kind: Option<String>,
+    /// Maximum number of results to show
+    #[arg(long)]
+    pub limit: Option<u32>,
+}
+
+fn parse_kind(s: &str) -> Result<String, String> {
+    let kind = s.to_lowercase();
+    match NodeKind::parse(&kind) {
+        Some(_) => Ok(kind),
+        None => Err(format!("unknown symbol kind '{}'", s)),
+    }
+}
+
+impl SymbolArgs {
+    /// The equivalent MCP tool request
+    pub fn request(&self) -> SymbolRequest {
+        let target = &self.target;
+        SymbolRequest {
+            symbol: target.symbol.clone().unwrap_or_default(),
+            kind: target.kind.clone(),
+            file_path: target.file_path.clone(),
+            id: target.id,
+            limit: self.limit,
+            format: ResponseFormat::default(),
+        }
+    }
+}
+
+impl PathArgs {
+    /// The equivalent MCP tool request
+    pub fn request(&self) -> PathRequest {
+        let (from, to) = match (&self.from, &self.to) {
+            (Some(name), None) if self.from_id.is_some() => (None, Some(name)),
+            (from, to) => (from.as_ref(), to.as_ref()),
+        };
+        PathRequest {
+            from: from.cloned().unwrap_or_default(),
+            from_kind: self.from_kind.clone(),
+            from_file_path: self.from_file.clone(),
+            from_id: self.from_id,
+            to: to.cloned().unwrap_or_default(),
+            to_kind: self.to_kind.clone(),
+            to_file_path: self.to_file.clone(),
+            to_id: self.to_id,
+            limit: self.limit,
+            format: ResponseFormat::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_tool_command() {
+        let cli = Cli::try_parse_from([
+            "codemap", "callers", "handle", "--kind", "Method", "--limit", "5", "--root", "/tmp",
+        ])
+        .unwrap();
+        assert_eq!(cli.global.root, "/tmp");
+        match cli.command {
+            Command::Callers(args) => {
+                let req = args.request();
+                assert_eq!(req.symbol, "handle");
+                assert_eq!(req.kind.as_deref(), Some("method"));
+                assert_eq!(req.limit, Some(5));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_symbol_selectors() {
+        let cli = Cli::try_parse_from(["codemap", "node", "--id", "42"]).unwrap();
+        match cli.command {
+            Command::Node(args) => assert_eq!(args.request().id, Some(42)),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        let cli = Cli::try_parse_from(["codemap", "definition", "Db::new", "--file", "src/db.rs"])
+            .unwrap();
+        match cli.command {
+            Command::Definition { target, .. } => {
+                assert_eq!(target.symbol.as_deref(), Some("Db::new"));
+                assert_eq!(target.file_path.as_deref(), Some("src/db.rs"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(Cli::try_parse_from(["codemap", "callers"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_path_selectors() {
+        let path = |args: &[&str]| match Cli::try_parse_from(args) {
+            Ok(Cli {
+                command: Command::Path(args),
+                ..
+            }) => args.request(),
+            other => panic!("unexpected parse: {:?}", other),
+        };
+        let req = path(&["codemap", "path", "main", "save", "--to-file", "src/db.rs"]);
+        assert_eq!((req.from.as_str(), req.to.as_str()), ("main", "save"));
+        assert_eq!(req.to_file_path.as_deref(), Some("src/db.rs"));
+        assert_eq!(req.from_file_path, None);
+
+        // A single name is the end that has no id
+        let req = path(&[
+            "codemap",
+            "path",
+            "--from-id",
+            "12",
+            "save",
+            "--to-kind",
+            "Method",
+        ]);
+        assert_eq!((req.from_id, req.from.as_str()), (Some(12), ""));
+        assert_eq!(req.to, "save");
+        assert_eq!(req.to_kind.as_deref(), Some("method"));
+        let req = path(&["codemap", "path", "main", "--to-id", "7"]);
+        assert_eq!((req.from.as_str(), req.to_id), ("main", Some(7)));
+
+        assert!(Cli::try_parse_from(["codemap", "path", "main"]).is_err());
+        assert!(Cli::try_parse_from(["codemap", "path", "--to-id", "7"]).is_err());
+    }
+
+    #[test]
+    fn test_grammars_load_only_when_allowed() {
+        let cli = Cli::try_parse_from(["codemap", "index"]).unwrap();
+        assert!(!cli.global.allow_grammars);
+        let cli = Cli::try_parse_from(["codemap", "index", "--allow-grammars"]).unwrap();
+        assert!(cli.global.allow_grammars);
+    }
+
+    #[test]
+    fn test_json_flag_selects_json_format() {
+        let cli = Cli::try_parse_from(["codemap", "status", "--json"]).unwrap();
+        assert_eq!(cli.global.output_format(), OutputFormat::Json);
+        let cli = Cli::try_parse_from(["codemap", "status", "--format", "markdown"]).unwrap();
+        assert_eq!(cli.global.output_format(), OutputFormat::Markdown);
+        assert!(Cli::try_parse_from(["codemap", "status", "--json", "--format", "text"]).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_kind_and_bad_port() {
+        assert!(Cli::try_parse_from(["codemap", "unused", "--kind", "widget"]).is_err());
+        assert!(Cli::try_parse_from(["codemap", "serve", "--port", "eighty"]).is_err());
+    }
+}
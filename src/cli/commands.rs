@@ -1,51 +1,141 @@
 //! Command implementations for CLI operations
 
-use anyhow::{Context, Result};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, Result};
 use tracing::info;
 
-use crate::context::{format_context_markdown, ContextBuilder, ContextOptions};
 use crate::db::Database;
+use crate::extraction::grammars;
+use crate::mcp::handlers;
+use crate::mcp::response::{ErrorCode, ToolError, ToolResponse, ToolResult};
+use crate::mcp::{
+    ContextRequest, DecoratedRequest, DefinitionRequest, DependenciesRequest, DiagnosticsRequest,
+    DiffImpactRequest, FileRequest, ResponseFormat, SearchRequest, UnusedRequest,
+};
+use crate::watch::{watch, Watcher, DEFAULT_DEBOUNCE};
 use crate::{index_codebase, IndexConfig};
 
-use super::db_utils::{canonicalize_path, database_path, open_project_database};
+use super::args::{Command, GlobalArgs, OutputFormat};
+use super::db_utils::{canonicalize_path, open_database, resolve_database_path};
+use super::output::render;
+
+/// Exit status when the request is invalid, e.g. it names an unknown symbol kind
+pub const EXIT_INVALID: u8 = 2;
+
+/// Exit status when there is no index, or the requested symbol or file is not in it
+pub const EXIT_NOT_FOUND: u8 = 3;
+
+/// Exit status when a symbol name matches several symbols
+pub const EXIT_AMBIGUOUS: u8 = 4;
 
 /// Index a codebase at the given path
-pub fn index_command(path: &str) -> Result<()> {
+pub fn index_command(
+    path: &str,
+    db_path: Option<&Path>,
+    workers: usize,
+    allow_grammars: bool,
+    format: OutputFormat,
+) -> Result<ExitCode> {
     let project_root = canonicalize_path(path)?;
-    let mut db = open_project_database(&project_root)?;
+    let mut db = open_database(&project_root, db_path)?;
 
     let config = IndexConfig {
         root: project_root.clone(),
+        workers,
+        allow_grammars,
         ..Default::default()
     };
 
     let stats = index_codebase(&mut db, &config)?;
 
+    if format == OutputFormat::Json {
+        let result = Ok(ToolResponse::new(String::new(), &stats));
+        return print_result("codemap-index", &result, format);
+    }
+
     println!("\nIndexing complete!");
     println!("  Files indexed: {}", stats.files);
     println!("  Symbols found: {}", stats.nodes);
     println!("  Relationships: {}", stats.edges);
     println!("  Files skipped: {}", stats.skipped);
+    if stats.removed > 0 || stats.renamed > 0 {
+        println!("  Files removed: {}", stats.removed);
+        println!("  Files renamed: {}", stats.renamed);
+    }
     println!("  Refs resolved: {}", stats.resolved_refs);
+    println!("  Imports resolved: {}", stats.resolved_imports);
+    println!("  Overrides found: {}", stats.overrides);
     if stats.errors > 0 {
         println!("  Errors: {}", stats.errors);
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Index a codebase and keep the index up to date as files change
+pub fn watch_command(path: &str, db_path: Option<&Path>, allow_grammars: bool) -> Result<ExitCode> {
+    let project_root = canonicalize_path(path)?;
+    let db = Arc::new(Mutex::new(open_database(&project_root, db_path)?));
+
+    let watcher = start_watching(&project_root, db, allow_grammars)?;
+    println!("Watching {} for changes (Ctrl-C to stop)", project_root);
+    watcher.wait();
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Catch the index up with the working tree, then watch it for changes
+pub fn start_watching(
+    project_root: &str,
+    db: Arc<Mutex<Database>>,
+    allow_grammars: bool,
+) -> Result<Watcher> {
+    let config = IndexConfig {
+        root: project_root.to_string(),
+        allow_grammars,
+        ..Default::default()
+    };
+
+    {
+        let mut db = db
+            .lock()
+            .map_err(|e| anyhow!("Database lock poisoned: {}", e))?;
+        let stats = index_codebase(&mut db, &config)?;
+        info!(
+            "Index up to date: {} files reindexed, {} skipped, {} removed",
+            stats.files, stats.skipped, stats.removed
+        );
+    }
+
+    watch(db, config, DEFAULT_DEBOUNCE)
 }
 
 /// Show index statistics for a project
-pub fn status_command(path: &str) -> Result<()> {
+pub fn status_command(
+    path: &str,
+    db_path: Option<&Path>,
+    allow_grammars: bool,
+    format: OutputFormat,
+) -> Result<ExitCode> {
     let project_root = canonicalize_path(path)?;
-    let db_path = database_path(&project_root);
+    let db_path = resolve_database_path(&project_root, db_path);
 
     if !db_path.exists() {
-        println!("No index found at {}", db_path.display());
-        println!("Run 'codemap index {}' first.", path);
-        return Ok(());
+        let hint = format!("codemap index {}", path);
+        return missing_index("codemap-status", &db_path, &hint, format);
     }
 
+    load_grammars(&project_root, allow_grammars);
     let db = Database::open(&db_path)?;
+    if format != OutputFormat::Text {
+        let result = handlers::status::handle_status(&db);
+        return print_result("codemap-status", &result, format);
+    }
+
     let stats = db.get_stats()?;
 
     println!("codemap Index Status");
@@ -55,6 +145,12 @@ pub fn status_command(path: &str) -> Result<()> {
     println!("Symbols: {}", stats.total_nodes);
     println!("Relationships: {}", stats.total_edges);
     println!("Size: {:.2} KB", stats.db_size_bytes as f64 / 1024.0);
+    if stats.parse_errors > 0 {
+        println!(
+            "Parse errors: {} in {} files (run `codemap diagnostics`)",
+            stats.parse_errors, stats.files_with_errors
+        );
+    }
 
     if !stats.languages.is_empty() {
         println!("\nLanguages:");
@@ -70,89 +166,259 @@ pub fn status_command(path: &str) -> Result<()> {
         }
     }
 
-    Ok(())
-}
-
-/// Search for symbols by name
-pub fn search_command(path: &str, query: &str) -> Result<()> {
-    let project_root = canonicalize_path(path)?;
-    let db_path = database_path(&project_root);
-
-    if !db_path.exists() {
-        println!("No index found. Run 'codemap index' first.");
-        return Ok(());
-    }
-
-    let db = Database::open(&db_path)?;
-    let results = db.search_nodes(query, None, 20)?;
-
-    if results.is_empty() {
-        println!("No symbols found matching '{}'", query);
-        return Ok(());
-    }
-
-    println!("Found {} symbols matching '{}':\n", results.len(), query);
-
-    for node in results {
-        println!(
-            "  {} {} - {}:{}",
-            node.kind.as_str(),
-            node.name,
-            node.file_path,
-            node.start_line
-        );
-        if let Some(ref sig) = node.signature {
-            let sig = sig.lines().next().unwrap_or(sig);
-            if sig.len() > 80 {
-                println!("    {}...", &sig[..80]);
-            } else {
-                println!("    {}", sig);
-            }
+    println!("\nGrammars:");
+    for grammar in &stats.grammars {
+        let version = grammar.version.as_deref().unwrap_or("unknown version");
+        match &grammar.library {
+            Some(library) => println!(
+                "  {} {} (ABI {}, .{}) from {}",
+                grammar.name,
+                version,
+                grammar.abi_version,
+                grammar.extensions.join(", ."),
+                library
+            ),
+            None => println!(
+                "  {} {} (ABI {}, built in)",
+                grammar.name, version, grammar.abi_version
+            ),
         }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
-/// Build AI context for a task
-pub fn context_command(path: &str, task: &str) -> Result<()> {
-    let project_root = canonicalize_path(path)?;
-    let db_path = database_path(&project_root);
+/// Run a command that queries the index and print the same report as the
+/// equivalent MCP tool
+pub fn query_command(global: &GlobalArgs, command: &Command) -> Result<ExitCode> {
+    let format = global.output_format();
+    let project_root = canonicalize_path(&global.root)?;
+    let db_path = resolve_database_path(&project_root, global.db.as_deref());
 
+    let tool = tool_name(command)?;
     if !db_path.exists() {
-        println!("No index found. Run 'codemap index' first.");
-        return Ok(());
+        return missing_index(tool, &db_path, "codemap index", format);
     }
 
+    load_grammars(&project_root, global.allow_grammars);
     let db = Database::open(&db_path)?;
-    let builder = ContextBuilder::new(&db, project_root);
 
-    let options = ContextOptions {
-        max_nodes: 20,
-        include_code: true,
-        max_code_blocks: 5,
-        ..Default::default()
+    let result = match command {
+        Command::Search {
+            query,
+            filter,
+            language,
+            path,
+            param_type,
+            returns,
+        } => handlers::search::handle_search(
+            &db,
+            &SearchRequest {
+                query: query.clone(),
+                kind: filter.kind.clone(),
+                language: language.clone(),
+                path: path.clone(),
+                param_type: param_type.clone(),
+                returns: returns.clone(),
+                limit: filter.limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Context { task, limit } => handlers::context::handle_context(
+            &db,
+            &project_root,
+            &ContextRequest {
+                task: task.join(" "),
+                limit: *limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Node(args) => handlers::symbol::handle_node(&db, &args.request()),
+        Command::Callers(args) => handlers::graph::handle_callers(&db, &args.request()),
+        Command::Callees(args) => handlers::graph::handle_callees(&db, &args.request()),
+        Command::Impact(args) => handlers::graph::handle_impact(&db, &args.request()),
+        Command::Implementations(args) => {
+            handlers::implementations::handle_implementations(&db, &args.request())
+        }
+        Command::Decorated { decorator, filter } => handlers::decorated::handle_decorated(
+            &db,
+            &DecoratedRequest {
+                decorator: decorator.clone(),
+                kind: filter.kind.clone(),
+                limit: filter.limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Hierarchy(args) => handlers::hierarchy::handle_hierarchy(&db, &args.request()),
+        Command::References(args) => handlers::symbol::handle_references(&db, &args.request()),
+        Command::Definition {
+            target,
+            context_lines,
+        } => handlers::symbol::handle_definition(
+            &db,
+            &project_root,
+            &DefinitionRequest {
+                symbol: target.symbol.clone().unwrap_or_default(),
+                kind: target.kind.clone(),
+                file_path: target.file_path.clone(),
+                id: target.id,
+                context_lines: *context_lines,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Path(args) => handlers::path::handle_path(&db, &args.request()),
+        Command::Unused(filter) => handlers::unused::handle_unused(
+            &db,
+            &UnusedRequest {
+                kind: filter.kind.clone(),
+                limit: filter.limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::File { path, filter } => handlers::file::handle_file(
+            &db,
+            &FileRequest {
+                path: path.clone(),
+                kind: filter.kind.clone(),
+                limit: filter.limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::DiffImpact {
+            file,
+            start_line,
+            end_line,
+            limit,
+        } => handlers::diff_impact::handle_diff_impact(
+            &db,
+            &DiffImpactRequest {
+                file_path: file.clone(),
+                start_line: *start_line,
+                end_line: *end_line,
+                limit: *limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Dependencies {
+            path,
+            level,
+            direction,
+            limit,
+        } => handlers::dependencies::handle_dependencies(
+            &db,
+            &DependenciesRequest {
+                path: path.clone(),
+                level: Some(level.clone()),
+                direction: Some(direction.clone()),
+                limit: *limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Diagnostics { path, limit } => handlers::diagnostics::handle_diagnostics(
+            &db,
+            &DiagnosticsRequest {
+                path: path.clone(),
+                limit: *limit,
+                format: ResponseFormat::default(),
+            },
+        ),
+        Command::Serve { .. }
+        | Command::Index { .. }
+        | Command::Watch { .. }
+        | Command::Status { .. } => bail!("Not a query command"),
     };
 
-    let context = builder.build_context(task, &options)?;
-    let markdown = format_context_markdown(&context);
+    print_result(tool, &result, format)
+}
 
-    println!("{}", markdown);
+/// The MCP tool that a query command runs
+fn tool_name(command: &Command) -> Result<&'static str> {
+    Ok(match command {
+        Command::Search { .. } => "codemap-search",
+        Command::Context { .. } => "codemap-context",
+        Command::Node(_) => "codemap-node",
+        Command::Callers(_) => "codemap-callers",
+        Command::Callees(_) => "codemap-callees",
+        Command::Impact(_) => "codemap-impact",
+        Command::Implementations(_) => "codemap-implementations",
+        Command::Decorated { .. } => "codemap-decorated",
+        Command::Diagnostics { .. } => "codemap-diagnostics",
+        Command::Hierarchy(_) => "codemap-hierarchy",
+        Command::References(_) => "codemap-references",
+        Command::Definition { .. } => "codemap-definition",
+        Command::Path(_) => "codemap-path",
+        Command::Unused(_) => "codemap-unused",
+        Command::File { .. } => "codemap-file",
+        Command::DiffImpact { .. } => "codemap-diff-impact",
+        Command::Dependencies { .. } => "codemap-dependencies",
+        Command::Serve { .. }
+        | Command::Index { .. }
+        | Command::Watch { .. }
+        | Command::Status { .. } => bail!("Not a query command"),
+    })
+}
 
-    Ok(())
+/// Print a tool result and return the matching exit status
+///
+/// JSON goes to stdout whether or not the tool failed, so that scripts can
+/// always parse it; other formats report failures on stderr. A reader that
+/// stops early, as in `codemap callers foo | head`, is not an error.
+fn print_result(tool: &str, result: &ToolResult, format: OutputFormat) -> Result<ExitCode> {
+    let output = render(tool, result, format);
+    let output = output.trim_end_matches('\n');
+    let written = match (result, format) {
+        (Err(_), OutputFormat::Text | OutputFormat::Markdown) => {
+            writeln!(io::stderr().lock(), "{}", output)
+        }
+        _ => {
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "{}", output).and_then(|()| stdout.flush())
+        }
+    };
+    match written {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err.into()),
+        _ => {}
+    }
+    Ok(match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => ExitCode::from(match error.code {
+            ErrorCode::NotFound => EXIT_NOT_FOUND,
+            ErrorCode::InvalidRequest => EXIT_INVALID,
+            ErrorCode::Ambiguous => EXIT_AMBIGUOUS,
+            ErrorCode::Internal => 1,
+        }),
+    })
 }
 
-/// Initialize database for MCP server mode
-pub fn initialize_server_database() -> Result<(String, Database)> {
-    use std::env;
+/// Report that there is no index to query
+fn missing_index(tool: &str, db_path: &Path, hint: &str, format: OutputFormat) -> Result<ExitCode> {
+    let message = format!(
+        "No index found at {}\nRun '{}' first.",
+        db_path.display(),
+        hint
+    );
+    print_result(tool, &Err(ToolError::not_found(message)), format)
+}
 
-    // Get project root from environment or current directory
-    let project_root = env::var("CODEMAP_ROOT")
-        .or_else(|_| env::current_dir().map(|p| p.display().to_string()))
-        .context("Could not determine project root")?;
+/// Load the grammars of the project config, so that the languages they add
+/// can be read back from the index. Indexing fails on a broken config;
+/// reading the index only warns.
+fn load_grammars(project_root: &str, allow: bool) {
+    if let Err(err) = grammars::load(Path::new(project_root), allow) {
+        // Query commands run without a log subscriber
+        eprintln!("Warning: {:#}", err);
+    }
+}
 
-    let project_root = canonicalize_path(&project_root)?;
-    let db = open_project_database(&project_root)?;
+/// Initialize database for MCP server mode
+pub fn initialize_server_database(
+    project_root: &str,
+    db_path: Option<&Path>,
+    allow_grammars: bool,
+) -> Result<(String, Database)> {
+    let project_root = canonicalize_path(project_root)?;
+    load_grammars(&project_root, allow_grammars);
+    let db = open_database(&project_root, db_path)?;
 
     // Log database status
     let stats = db.get_stats()?;
@@ -1,7 +1,7 @@
 //! Database path and initialization utilities
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::db::Database;
 
@@ -13,6 +13,14 @@ pub fn database_path(project_root: &str) -> PathBuf {
     PathBuf::from(project_root).join(DB_DIR).join(DB_FILE)
 }
 
+/// Get the database path for a project, honouring an explicit location
+pub fn resolve_database_path(project_root: &str, db_path: Option<&Path>) -> PathBuf {
+    match db_path {
+        Some(path) => path.to_path_buf(),
+        None => database_path(project_root),
+    }
+}
+
 /// Ensure the database directory exists
 pub fn ensure_database_directory(project_root: &str) -> Result<()> {
     let dir = PathBuf::from(project_root).join(DB_DIR);
@@ -27,6 +35,17 @@ pub fn open_project_database(project_root: &str) -> Result<Database> {
     Database::open(&db_path)
 }
 
+/// Open or initialize the database for a project at an explicit or default location
+pub fn open_database(project_root: &str, db_path: Option<&Path>) -> Result<Database> {
+    let Some(db_path) = db_path else {
+        return open_project_database(project_root);
+    };
+    if let Some(dir) = db_path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    Database::open(db_path)
+}
+
 /// Canonicalize and validate a path
 pub fn canonicalize_path(path: &str) -> Result<String> {
     let canonical = std::path::Path::new(path)
@@ -2,12 +2,15 @@
 //!
 //! Handles all command-line interface operations:
 //! - index: Index a codebase
+//! - watch: Keep the index up to date as files change
 //! - status: Show index statistics  
-//! - search: Search for symbols
-//! - context: Build AI context for tasks
+//! - search, context and one subcommand per MCP tool: query the index
 
+mod args;
 mod commands;
 mod db_utils;
+mod output;
 
+pub use args::*;
 pub use commands::*;
 pub use db_utils::*;
@@ -0,0 +1,66 @@
+//! Rendering of command output
+
+use crate::mcp::response::{self, ResponseFormat, ToolResult};
+
+use super::args::OutputFormat;
+
+/// Render the result of a tool handler in the requested format
+pub fn render(tool: &str, result: &ToolResult, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => response::render(tool, result, ResponseFormat::Json),
+        OutputFormat::Markdown => response::render(tool, result, ResponseFormat::Markdown),
+        OutputFormat::Text => {
+            markdown_to_text(&response::render(tool, result, ResponseFormat::Markdown))
+        }
+    }
+}
+
+/// Strip markdown markup, keeping the text, layout and code
+fn markdown_to_text(markdown: &str) -> String {
+    let mut text = String::with_capacity(markdown.len());
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            text.push_str(line);
+        } else {
+            let line = line.trim_start_matches('#').trim_start();
+            text.push_str(&line.replace("**", "").replace('`', ""));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::response::ToolResponse;
+
+    #[test]
+    fn test_text_strips_markup() {
+        let markdown =
+            "## Symbols in `a.rs`\n\n- **function** `main` - a.rs:1\n```c\n#include <a.h>\n```\n";
+        let result: ToolResult = Ok(ToolResponse::new(markdown.to_string(), ()));
+        assert_eq!(
+            render("codemap-file", &result, OutputFormat::Text),
+            "Symbols in a.rs\n\n- function main - a.rs:1\n#include <a.h>\n"
+        );
+        assert_eq!(
+            render("codemap-file", &result, OutputFormat::Markdown),
+            markdown
+        );
+    }
+
+    #[test]
+    fn test_json_is_the_envelope() {
+        let result: ToolResult = Ok(ToolResponse::new("# Report".into(), vec!["a"]));
+        let json: serde_json::Value =
+            serde_json::from_str(&render("codemap-unused", &result, OutputFormat::Json)).unwrap();
+        assert_eq!(json["tool"], "codemap-unused");
+        assert_eq!(json["result"][0], "a");
+    }
+}
@@ -343,6 +343,9 @@ pub fn format_context_markdown(context: &TaskContext) -> String {
         if let Some(ref sig) = node.signature {
             output.push_str(&format!("  ```\n  {}\n  ```\n", sig));
         }
+        if let Some(summary) = node.doc_summary() {
+            output.push_str(&format!("  {}\n", summary));
+        }
     }
 
     if !context.related_nodes.is_empty() {
@@ -0,0 +1,119 @@
+//! Parse diagnostics
+//!
+//! Syntax errors found while extracting a file are stored with their
+//! location, so that tools can tell when the graph for a file is incomplete.
+//! They are replaced whenever the file is indexed again.
+
+use anyhow::Result;
+use rusqlite::params;
+
+use super::Database;
+use crate::types::ExtractionError;
+
+impl Database {
+    /// Record a parse error of an indexed file
+    pub fn insert_diagnostic(&self, error: &ExtractionError) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO diagnostics (file_path, line, column, message) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        stmt.execute(params![
+            error.file_path,
+            error.line,
+            error.column,
+            error.message
+        ])?;
+        Ok(())
+    }
+
+    /// Parse errors of one file, or of every file, in file and line order
+    pub fn get_diagnostics(&self, file_path: Option<&str>) -> Result<Vec<ExtractionError>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, line, column, message FROM diagnostics
+             WHERE ?1 IS NULL OR file_path = ?1
+             ORDER BY file_path, line, column, id",
+        )?;
+        let rows = stmt.query_map(params![file_path], |row| {
+            Ok(ExtractionError {
+                file_path: row.get(0)?,
+                line: row.get(1)?,
+                column: row.get(2)?,
+                message: row.get(3)?,
+            })
+        })?;
+        let mut diagnostics = Vec::new();
+        for row in rows {
+            diagnostics.push(row?);
+        }
+        Ok(diagnostics)
+    }
+
+    /// Number of parse errors, and of files with any
+    pub(super) fn diagnostic_counts(&self) -> Result<(u64, u64)> {
+        let counts = self.conn.query_row(
+            "SELECT COUNT(*), COUNT(DISTINCT file_path) FROM diagnostics",
+            [],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+        )?;
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileRecord, Language};
+
+    fn file(path: &str) -> FileRecord {
+        FileRecord {
+            path: path.to_string(),
+            content_hash: "hash".to_string(),
+            language: Language::Rust,
+            size: 10,
+            modified_at: 0,
+            indexed_at: 0,
+            node_count: 0,
+        }
+    }
+
+    fn error(file_path: &str, line: u32) -> ExtractionError {
+        ExtractionError {
+            message: "Missing `;`".to_string(),
+            file_path: file_path.to_string(),
+            line: Some(line),
+            column: Some(4),
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_follow_their_file() {
+        let db = Database::in_memory().unwrap();
+        db.insert_or_update_file(&file("a.rs")).unwrap();
+        db.insert_or_update_file(&file("b.rs")).unwrap();
+        db.insert_diagnostic(&error("b.rs", 3)).unwrap();
+        db.insert_diagnostic(&error("a.rs", 9)).unwrap();
+        db.insert_diagnostic(&error("a.rs", 2)).unwrap();
+
+        let lines = |file_path: Option<&str>| -> Vec<(String, Option<u32>)> {
+            db.get_diagnostics(file_path)
+                .unwrap()
+                .into_iter()
+                .map(|d| (d.file_path, d.line))
+                .collect()
+        };
+        assert_eq!(
+            lines(None),
+            [
+                ("a.rs".to_string(), Some(2)),
+                ("a.rs".to_string(), Some(9)),
+                ("b.rs".to_string(), Some(3))
+            ]
+        );
+        assert_eq!(db.diagnostic_counts().unwrap(), (3, 2));
+
+        db.rename_file("b.rs", "c.rs").unwrap();
+        assert_eq!(lines(Some("c.rs")), [("c.rs".to_string(), Some(3))]);
+
+        db.delete_file("a.rs").unwrap();
+        assert_eq!(db.diagnostic_counts().unwrap(), (1, 1));
+    }
+}
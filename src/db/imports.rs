@@ -0,0 +1,959 @@
+//! Import resolution and the dependency graph
+//!
+//! Module paths recorded from import statements are mapped onto indexed
+//! files with each language's lookup rules:
+//! - Rust: `crate`, `self`, `super` and the package name, via `foo.rs` or `foo/mod.rs`
+//! - TypeScript/JavaScript: relative specifiers, `tsconfig.json` `paths` and `baseUrl`
+//! - Python: dotted packages (`a/b.py`, `a/b/__init__.py`) and relative imports
+//! - Go: packages below the module path in `go.mod`
+//! - Java: classes and `.*` packages by directory
+//! - C/C++: `#include` next to the including file, then anywhere in the tree
+//!
+//! Imports of code outside the repository resolve to nothing. Each resolved
+//! import becomes an `imports` edge from the import node to the file node it
+//! names. Adding a file can change what an import resolves to, so indexing
+//! recomputes these edges from scratch. When only the content of some files
+//! changed, watch mode relinks just their imports and those into them.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::Database;
+use crate::types::{Edge, EdgeKind, Language, ModuleImport};
+
+/// Extensions tried, in order, for an extensionless TypeScript/JavaScript specifier
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+/// Files in the project root that `ProjectLayout` is read from
+pub const LAYOUT_FILES: &[&str] = &["Cargo.toml", "go.mod", "tsconfig.json"];
+
+/// Project files that decide where non-relative imports point
+#[derive(Debug, Clone, Default)]
+pub struct ProjectLayout {
+    /// Rust package name from `Cargo.toml`, as written in paths (`-` becomes `_`)
+    crate_name: Option<String>,
+    /// Module path from `go.mod`
+    go_module: Option<String>,
+    /// `compilerOptions.baseUrl` from `tsconfig.json`, relative to the root
+    ts_base_url: Option<String>,
+    /// `compilerOptions.paths` patterns and their targets, longest prefix first
+    ts_paths: Vec<(String, Vec<String>)>,
+}
+
+impl ProjectLayout {
+    /// Read `Cargo.toml`, `go.mod` and `tsconfig.json` from the project root.
+    /// Missing or unreadable files leave the corresponding imports unresolved.
+    pub fn load(root: &Path) -> Self {
+        let mut layout = Self::default();
+        if let Ok(manifest) = fs::read_to_string(root.join("Cargo.toml")) {
+            layout.crate_name = cargo_package_name(&manifest).map(|n| n.replace('-', "_"));
+        }
+        if let Ok(go_mod) = fs::read_to_string(root.join("go.mod")) {
+            layout.go_module = go_mod
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))
+                .map(|m| m.trim().trim_matches('"').to_string());
+        }
+        if let Ok(tsconfig) = fs::read_to_string(root.join("tsconfig.json")) {
+            layout.load_tsconfig(&tsconfig);
+        }
+        layout
+    }
+
+    fn load_tsconfig(&mut self, text: &str) {
+        let Ok(config) = serde_json::from_str::<serde_json::Value>(&strip_jsonc(text)) else {
+            return;
+        };
+        let options = &config["compilerOptions"];
+        let base_url = options["baseUrl"]
+            .as_str()
+            .map(|b| normalize(b).unwrap_or_default());
+        let targets_base = base_url.clone().unwrap_or_default();
+        if let Some(paths) = options["paths"].as_object() {
+            for (pattern, targets) in paths {
+                let targets = targets
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|t| t.as_str())
+                    .filter_map(|t| join(&targets_base, t))
+                    .collect();
+                self.ts_paths.push((pattern.clone(), targets));
+            }
+        }
+        self.ts_paths.sort_by_key(|(pattern, _)| {
+            std::cmp::Reverse(pattern.find('*').unwrap_or(pattern.len()))
+        });
+        self.ts_base_url = base_url;
+    }
+
+    /// Targets of the first `paths` pattern that matches a specifier
+    fn ts_path_targets(&self, specifier: &str) -> Vec<String> {
+        for (pattern, targets) in &self.ts_paths {
+            let star = match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let matches = specifier.len() >= prefix.len() + suffix.len()
+                        && specifier.starts_with(prefix)
+                        && specifier.ends_with(suffix);
+                    if !matches {
+                        continue;
+                    }
+                    &specifier[prefix.len()..specifier.len() - suffix.len()]
+                }
+                None if pattern == specifier => "",
+                None => continue,
+            };
+            return targets.iter().map(|t| t.replacen('*', star, 1)).collect();
+        }
+        Vec::new()
+    }
+}
+
+/// An import dependency between two files, or two directories
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dependency {
+    pub from: String,
+    pub to: String,
+    /// Number of import statements behind the dependency
+    pub imports: u32,
+}
+
+impl Database {
+    /// Record a module path named by an import statement
+    pub fn insert_import(&self, import: &ModuleImport) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO imports (source_node_id, module, file_path, line, language)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        stmt.execute(params![
+            import.source_node_id,
+            import.module,
+            import.file_path,
+            import.line as i64,
+            import.language.as_str(),
+        ])?;
+        Ok(())
+    }
+
+    /// Get every recorded module import, in file order
+    pub fn get_imports(&self) -> Result<Vec<ModuleImport>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source_node_id, module, file_path, line, language FROM imports
+             ORDER BY file_path, line, id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ModuleImport {
+                source_node_id: row.get(0)?,
+                module: row.get(1)?,
+                file_path: row.get(2)?,
+                line: row.get::<_, i64>(3)? as u32,
+                language: Language::parse(&row.get::<_, String>(4)?),
+            })
+        })?;
+
+        let mut imports = Vec::new();
+        for row in rows {
+            imports.push(row?);
+        }
+        Ok(imports)
+    }
+
+    /// Link every module import to the files it names, replacing the links
+    /// from previous runs. Returns the number of imports that resolved.
+    pub fn resolve_imports(&self, layout: &ProjectLayout) -> Result<u32> {
+        self.conn.execute(
+            "DELETE FROM edges WHERE kind = 'imports' AND ref_id IS NULL
+             AND source_id IN (SELECT source_node_id FROM imports)",
+            [],
+        )?;
+        self.link_imports(layout, self.get_imports()?)
+    }
+
+    /// Relink the imports made in `files`, replacing their links. Only valid
+    /// while the set of indexed files is unchanged, since a new file may be
+    /// a better match for imports elsewhere. Returns the number that resolved.
+    pub fn resolve_imports_in(&self, layout: &ProjectLayout, files: &[String]) -> Result<u32> {
+        let mut delete = self.conn.prepare_cached(
+            "DELETE FROM edges WHERE kind = 'imports' AND ref_id IS NULL
+             AND source_id IN (SELECT source_node_id FROM imports WHERE file_path = ?1)",
+        )?;
+        for file in files {
+            delete.execute(params![file])?;
+        }
+        let imports = self
+            .get_imports()?
+            .into_iter()
+            .filter(|import| files.contains(&import.file_path))
+            .collect();
+        self.link_imports(layout, imports)
+    }
+
+    fn link_imports(&self, layout: &ProjectLayout, imports: Vec<ModuleImport>) -> Result<u32> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT file_path, id FROM nodes WHERE kind = 'file'")?;
+        let files = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let tree = FileTree::new(files, layout);
+
+        let mut resolved = 0;
+        for import in imports {
+            let mut linked = false;
+            for target in tree.resolve(&import) {
+                let Some(&target_id) = tree.files.get(&target) else {
+                    continue;
+                };
+                if target == tree.key(&import.file_path) {
+                    continue;
+                }
+                self.insert_edge(&Edge {
+                    source_id: import.source_node_id,
+                    target_id,
+                    kind: EdgeKind::Imports,
+                    file_path: Some(import.file_path.clone()),
+                    line: Some(import.line),
+                    ..Default::default()
+                })?;
+                linked = true;
+            }
+            if linked {
+                resolved += 1;
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Files and the files they import
+    pub fn get_file_dependencies(&self) -> Result<Vec<Dependency>> {
+        self.dependencies(|path| path.to_string())
+    }
+
+    /// Directories and the directories their files import. Files at the
+    /// project root belong to `.`.
+    pub fn get_module_dependencies(&self) -> Result<Vec<Dependency>> {
+        self.dependencies(|path| match path.rfind(['/', '\\']) {
+            Some(pos) => path[..pos].to_string(),
+            None => ".".to_string(),
+        })
+    }
+
+    /// Resolved imports grouped by the unit `group` puts each file in,
+    /// counting each import statement once per pair
+    fn dependencies(&self, group: impl Fn(&str) -> String) -> Result<Vec<Dependency>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT e.source_id, s.file_path, t.file_path
+             FROM edges e
+             JOIN nodes s ON s.id = e.source_id
+             JOIN nodes t ON t.id = e.target_id
+             WHERE e.kind = 'imports' AND t.kind = 'file'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut pairs: BTreeMap<(String, String), HashSet<i64>> = BTreeMap::new();
+        for row in rows {
+            let (source_id, from, to) = row?;
+            let (from, to) = (group(&from), group(&to));
+            if from != to {
+                pairs.entry((from, to)).or_default().insert(source_id);
+            }
+        }
+        Ok(pairs
+            .into_iter()
+            .map(|((from, to), sources)| Dependency {
+                from,
+                to,
+                imports: sources.len() as u32,
+            })
+            .collect())
+    }
+}
+
+/// The indexed files, with lookups by directory and by file name
+struct FileTree<'a> {
+    /// File node ids by `/`-separated path
+    files: HashMap<String, i64>,
+    by_dir: HashMap<String, Vec<String>>,
+    by_name: HashMap<String, Vec<String>>,
+    layout: &'a ProjectLayout,
+}
+
+impl<'a> FileTree<'a> {
+    fn new(files: Vec<(String, i64)>, layout: &'a ProjectLayout) -> Self {
+        let mut tree = Self {
+            files: HashMap::new(),
+            by_dir: HashMap::new(),
+            by_name: HashMap::new(),
+            layout,
+        };
+        for (path, id) in files {
+            let path = tree.key(&path);
+            let (dir, name) = split(&path);
+            tree.by_dir
+                .entry(dir.to_string())
+                .or_default()
+                .push(path.clone());
+            tree.by_name
+                .entry(name.to_string())
+                .or_default()
+                .push(path.clone());
+            tree.files.insert(path, id);
+        }
+        for paths in tree.by_dir.values_mut().chain(tree.by_name.values_mut()) {
+            paths.sort();
+        }
+        tree
+    }
+
+    /// Paths are stored with the platform separator; lookups use `/`
+    fn key(&self, path: &str) -> String {
+        path.replace('\\', "/")
+    }
+
+    fn has(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// Files an import refers to; empty when it points outside the repository
+    fn resolve(&self, import: &ModuleImport) -> Vec<String> {
+        let from = self.key(&import.file_path);
+        let module = import.module.as_str();
+        match import.language {
+            Language::Rust => self.resolve_rust(&from, module).into_iter().collect(),
+            Language::TypeScript | Language::Tsx | Language::JavaScript | Language::Jsx => {
+                self.resolve_script(&from, module).into_iter().collect()
+            }
+            Language::Python => self.resolve_python(&from, module).into_iter().collect(),
+            Language::Go => self.resolve_go(module),
+            Language::Java => self.resolve_java(&from, module),
+            Language::C | Language::Cpp => {
+                self.resolve_include(&from, module).into_iter().collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Follow a `use` path through module files for as long as they exist.
+    /// The rest of the path names items inside the last module found.
+    fn resolve_rust(&self, from: &str, path: &str) -> Option<String> {
+        let mut segments = path.split("::").peekable();
+        let mut dir = match *segments.peek()? {
+            "crate" => {
+                segments.next();
+                self.rust_crate_dir(from)?
+            }
+            "self" => {
+                segments.next();
+                rust_module_dir(from)
+            }
+            "super" => {
+                let mut dir = rust_module_dir(from);
+                while segments.next_if_eq(&"super").is_some() {
+                    dir = split(&dir).0.to_string();
+                }
+                dir
+            }
+            name if self.layout.crate_name.as_deref() == Some(name) => {
+                segments.next();
+                ["src/lib.rs", "src/main.rs"]
+                    .iter()
+                    .any(|root| self.has(root))
+                    .then(|| "src".to_string())?
+            }
+            // A submodule in scope, or a 2015-style path from the crate root
+            name => {
+                let own_dir = rust_module_dir(from);
+                match self.rust_child_module(&own_dir, name) {
+                    Some(_) => own_dir,
+                    None => {
+                        let dir = self.rust_crate_dir(from)?;
+                        self.rust_child_module(&dir, name)?;
+                        dir
+                    }
+                }
+            }
+        };
+
+        let mut file = self.rust_module_file(&dir)?;
+        for segment in segments {
+            let Some(child) = self.rust_child_module(&dir, segment) else {
+                break;
+            };
+            file = child;
+            dir = join(&dir, segment)?;
+        }
+        Some(file)
+    }
+
+    /// Directory of the nearest crate root (`lib.rs` or `main.rs`) above a file
+    fn rust_crate_dir(&self, from: &str) -> Option<String> {
+        let mut dir = split(from).0;
+        loop {
+            if ["lib.rs", "main.rs"]
+                .iter()
+                .any(|root| self.has(&join(dir, root).unwrap_or_default()))
+            {
+                return Some(dir.to_string());
+            }
+            if dir.is_empty() {
+                return None;
+            }
+            dir = split(dir).0;
+        }
+    }
+
+    /// File of the module whose submodules live in `dir`
+    fn rust_module_file(&self, dir: &str) -> Option<String> {
+        let own_file = format!("{}.rs", dir);
+        [own_file.as_str(), "mod.rs", "lib.rs", "main.rs"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match i {
+                0 => name.to_string(),
+                _ => join(dir, name).unwrap_or_default(),
+            })
+            .find(|path| self.has(path))
+    }
+
+    fn rust_child_module(&self, dir: &str, name: &str) -> Option<String> {
+        [format!("{}.rs", name), format!("{}/mod.rs", name)]
+            .iter()
+            .filter_map(|file| join(dir, file))
+            .find(|path| self.has(path))
+    }
+
+    fn resolve_script(&self, from: &str, specifier: &str) -> Option<String> {
+        let bases = if specifier.starts_with('.') {
+            vec![join(split(from).0, specifier)?]
+        } else {
+            let mapped = self.layout.ts_path_targets(specifier);
+            match (&self.layout.ts_base_url, mapped.is_empty()) {
+                (_, false) => mapped,
+                (Some(base_url), true) => vec![join(base_url, specifier)?],
+                (None, true) => return None,
+            }
+        };
+        bases.iter().find_map(|base| self.script_file(base))
+    }
+
+    /// The file a module specifier loads: as written, with an extension,
+    /// or the directory's index file. `./util.js` may also mean `util.ts`.
+    fn script_file(&self, base: &str) -> Option<String> {
+        if self.has(base) {
+            return Some(base.to_string());
+        }
+        let stem = [".js", ".jsx", ".mjs", ".cjs"]
+            .iter()
+            .find_map(|ext| base.strip_suffix(ext))
+            .unwrap_or(base);
+        SCRIPT_EXTENSIONS
+            .iter()
+            .map(|ext| format!("{}.{}", stem, ext))
+            .chain(
+                SCRIPT_EXTENSIONS
+                    .iter()
+                    .map(|ext| format!("{}/index.{}", base, ext)),
+            )
+            .find(|path| self.has(path))
+    }
+
+    /// Dotted module paths, trying the longest prefix first since the last
+    /// segments of `a.b.name` may name a symbol rather than a module
+    fn resolve_python(&self, from: &str, module: &str) -> Option<String> {
+        let rest = module.trim_start_matches('.');
+        let dots = module.len() - rest.len();
+        let segments: Vec<&str> = rest.split('.').filter(|s| !s.is_empty()).collect();
+
+        if dots > 0 {
+            let mut dir = split(from).0;
+            for _ in 1..dots {
+                if dir.is_empty() {
+                    return None;
+                }
+                dir = split(dir).0;
+            }
+            return (0..=segments.len()).rev().find_map(|n| {
+                let package = join(dir, &segments[..n].join("/"))?;
+                let init = join(&package, "__init__.py")?;
+                let candidates = match n {
+                    0 => vec![init],
+                    _ => vec![format!("{}.py", package), init],
+                };
+                candidates.into_iter().find(|path| self.has(path))
+            });
+        }
+
+        (1..=segments.len()).rev().find_map(|n| {
+            let path = segments[..n].join("/");
+            self.find_by_suffix(from, &format!("{}.py", path))
+                .or_else(|| self.find_by_suffix(from, &format!("{}/__init__.py", path)))
+        })
+    }
+
+    /// Every non-test file of a package inside the main module
+    fn resolve_go(&self, import_path: &str) -> Vec<String> {
+        let Some(module) = &self.layout.go_module else {
+            return Vec::new();
+        };
+        let dir = match import_path.strip_prefix(module.as_str()) {
+            Some("") => "",
+            Some(rest) if rest.starts_with('/') => &rest[1..],
+            _ => return Vec::new(),
+        };
+        self.by_dir
+            .get(dir)
+            .into_iter()
+            .flatten()
+            .filter(|path| path.ends_with(".go") && !path.ends_with("_test.go"))
+            .cloned()
+            .collect()
+    }
+
+    /// A class file, or every file of a package for `pkg.*`. Static imports
+    /// name a member after the class, so shorter prefixes are tried too.
+    fn resolve_java(&self, from: &str, import: &str) -> Vec<String> {
+        let segments: Vec<&str> = import.split('.').collect();
+        let (names, wildcard) = match segments.split_last() {
+            Some((&"*", package)) => (package, true),
+            _ => (segments.as_slice(), false),
+        };
+
+        for n in (2..=names.len()).rev() {
+            let class = format!("{}.java", names[..n].join("/"));
+            if let Some(path) = self.find_by_suffix(from, &class) {
+                return vec![path];
+            }
+        }
+        if !wildcard {
+            return Vec::new();
+        }
+
+        let package = names.join("/");
+        let mut dirs: Vec<&String> = self
+            .by_dir
+            .keys()
+            .filter(|dir| **dir == package || dir.ends_with(&format!("/{}", package)))
+            .collect();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(common_prefix_len(dir, from)));
+        dirs.first()
+            .map(|dir| {
+                self.by_dir[*dir]
+                    .iter()
+                    .filter(|path| path.ends_with(".java"))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `"x.h"` is looked up next to the including file first; `<x.h>` only
+    /// matches when the repository has it
+    fn resolve_include(&self, from: &str, include: &str) -> Option<String> {
+        let (path, system) = match include.strip_prefix('<') {
+            Some(path) => (path.trim_end_matches('>'), true),
+            None => (include, false),
+        };
+        if !system {
+            if let Some(local) = join(split(from).0, path).filter(|p| self.has(p)) {
+                return Some(local);
+            }
+        }
+        self.find_by_suffix(from, path)
+    }
+
+    /// The file whose path ends with `suffix`, preferring the one that
+    /// shares the most directories with the importing file
+    fn find_by_suffix(&self, from: &str, suffix: &str) -> Option<String> {
+        let suffix = normalize(suffix)?;
+        let (_, name) = split(&suffix);
+        self.by_name
+            .get(name)?
+            .iter()
+            .filter(|path| **path == suffix || path.ends_with(&format!("/{}", suffix)))
+            .max_by_key(|path| {
+                (
+                    common_prefix_len(split(path).0, from),
+                    std::cmp::Reverse(path.len()),
+                )
+            })
+            .cloned()
+    }
+}
+
+/// Directory holding the submodules of the module defined in `file`
+fn rust_module_dir(file: &str) -> String {
+    let (dir, name) = split(file);
+    match name {
+        "mod.rs" | "lib.rs" | "main.rs" => dir.to_string(),
+        _ => {
+            let stem = name.strip_suffix(".rs").unwrap_or(name);
+            join(dir, stem).unwrap_or_default()
+        }
+    }
+}
+
+/// Split a `/`-separated path into its directory and file name
+fn split(path: &str) -> (&str, &str) {
+    path.rsplit_once('/').unwrap_or(("", path))
+}
+
+/// Join a relative path onto a directory, resolving `.` and `..`.
+/// Returns `None` for paths that leave the project root.
+fn join(dir: &str, path: &str) -> Option<String> {
+    normalize(&format!("{}/{}", dir, path))
+}
+
+fn normalize(path: &str) -> Option<String> {
+    let mut parts = Vec::new();
+    for part in path.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Number of leading directories two paths share
+fn common_prefix_len(dir: &str, file: &str) -> usize {
+    dir.split('/')
+        .zip(split(file).0.split('/'))
+        .take_while(|(a, b)| a == b && !a.is_empty())
+        .count()
+}
+
+/// `name` from the `[package]` table of a Cargo manifest
+fn cargo_package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "name" {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Remove the comments and trailing commas that `tsconfig.json` allows
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (',', _) => {
+                let rest: String = chars.clone().collect();
+                let next = rest.trim_start();
+                if !next.starts_with(['}', ']']) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileRecord, Node, NodeKind, Visibility};
+
+    fn add_file(db: &Database, path: &str, language: Language) -> i64 {
+        db.insert_or_update_file(&FileRecord {
+            path: path.to_string(),
+            content_hash: "hash".to_string(),
+            language,
+            size: 0,
+            modified_at: 0,
+            indexed_at: 0,
+            node_count: 0,
+        })
+        .unwrap();
+        db.insert_node(&Node {
+            id: 0,
+            kind: NodeKind::File,
+            name: split(path).1.to_string(),
+            qualified_name: Some(path.to_string()),
+            file_path: path.to_string(),
+            start_line: 0,
+            end_line: 0,
+            start_column: 0,
+            end_column: 0,
+            signature: None,
+            signature_parts: None,
+            arity: None,
+            visibility: Visibility::Public,
+            docstring: None,
+            doc_tags: None,
+            is_async: false,
+            is_static: false,
+            is_exported: true,
+            has_parse_errors: false,
+            language,
+            decorators: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    /// Index `files`, import `module` from the first one and return what it resolves to
+    fn resolve(layout: &ProjectLayout, files: &[&str], module: &str) -> Vec<String> {
+        let db = Database::in_memory().unwrap();
+        let language = Language::from_extension(files[0].rsplit('.').next().unwrap());
+        let ids: Vec<i64> = files.iter().map(|f| add_file(&db, f, language)).collect();
+        db.insert_import(&ModuleImport {
+            source_node_id: ids[0],
+            module: module.to_string(),
+            file_path: files[0].to_string(),
+            line: 1,
+            language,
+        })
+        .unwrap();
+        db.resolve_imports(layout).unwrap();
+
+        let mut targets: Vec<String> = db
+            .get_file_dependencies()
+            .unwrap()
+            .into_iter()
+            .map(|d| d.to)
+            .collect();
+        targets.sort();
+        targets
+    }
+
+    #[test]
+    fn test_rust_paths() {
+        let layout = ProjectLayout {
+            crate_name: Some("codemap".to_string()),
+            ..Default::default()
+        };
+        let files = [
+            "src/db/search.rs",
+            "src/lib.rs",
+            "src/db/mod.rs",
+            "src/types.rs",
+            "src/mcp/mod.rs",
+            "src/mcp/handlers/mod.rs",
+        ];
+        assert_eq!(
+            resolve(&layout, &files, "crate::types::Node"),
+            ["src/types.rs"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "super::Database"),
+            ["src/db/mod.rs"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "super::super::Config"),
+            ["src/lib.rs"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "crate::mcp::handlers::filter_nodes"),
+            ["src/mcp/handlers/mod.rs"]
+        );
+        assert!(resolve(&layout, &files, "std::collections::HashMap").is_empty());
+
+        let files = ["tests/integration_test.rs", "src/lib.rs", "src/db/mod.rs"];
+        assert_eq!(
+            resolve(&layout, &files, "codemap::db::Database"),
+            ["src/db/mod.rs"]
+        );
+    }
+
+    #[test]
+    fn test_rust_mod_declarations() {
+        let layout = ProjectLayout::default();
+        let files = ["src/db/mod.rs", "src/db/search.rs", "src/lib.rs"];
+        assert_eq!(
+            resolve(&layout, &files, "self::search"),
+            ["src/db/search.rs"]
+        );
+        let files = ["src/watch.rs", "src/watch/events.rs", "src/main.rs"];
+        assert_eq!(
+            resolve(&layout, &files, "self::events"),
+            ["src/watch/events.rs"]
+        );
+    }
+
+    #[test]
+    fn test_script_specifiers() {
+        let mut layout = ProjectLayout::default();
+        layout.load_tsconfig(
+            r#"{
+                // comments and trailing commas are allowed
+                "compilerOptions": {
+                    "baseUrl": "./src",
+                    "paths": { "@app/*": ["app/*"], },
+                },
+            }"#,
+        );
+        let files = [
+            "src/app/main.ts",
+            "src/app/http.ts",
+            "src/app/models/index.tsx",
+            "src/lib/util.ts",
+        ];
+        assert_eq!(resolve(&layout, &files, "./http"), ["src/app/http.ts"]);
+        assert_eq!(resolve(&layout, &files, "./http.js"), ["src/app/http.ts"]);
+        assert_eq!(
+            resolve(&layout, &files, "./models"),
+            ["src/app/models/index.tsx"]
+        );
+        assert_eq!(resolve(&layout, &files, "../lib/util"), ["src/lib/util.ts"]);
+        assert_eq!(resolve(&layout, &files, "@app/http"), ["src/app/http.ts"]);
+        assert_eq!(resolve(&layout, &files, "lib/util"), ["src/lib/util.ts"]);
+        assert!(resolve(&layout, &files, "react").is_empty());
+    }
+
+    #[test]
+    fn test_python_modules() {
+        let layout = ProjectLayout::default();
+        let files = [
+            "shop/api/views.py",
+            "shop/models.py",
+            "shop/api/__init__.py",
+            "shop/api/serializers.py",
+        ];
+        assert_eq!(
+            resolve(&layout, &files, "shop.models.User"),
+            ["shop/models.py"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, ".serializers"),
+            ["shop/api/serializers.py"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "..models.User"),
+            ["shop/models.py"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "shop.api"),
+            ["shop/api/__init__.py"]
+        );
+        assert!(resolve(&layout, &files, "os.path").is_empty());
+    }
+
+    #[test]
+    fn test_go_java_and_include() {
+        let layout = ProjectLayout {
+            go_module: Some("example.com/shop".to_string()),
+            ..Default::default()
+        };
+        let files = [
+            "cmd/main.go",
+            "cart/cart.go",
+            "cart/item.go",
+            "cart/cart_test.go",
+        ];
+        assert_eq!(
+            resolve(&layout, &files, "example.com/shop/cart"),
+            ["cart/cart.go", "cart/item.go"]
+        );
+        assert!(resolve(&layout, &files, "fmt").is_empty());
+
+        let layout = ProjectLayout::default();
+        let files = [
+            "src/main/java/com/shop/App.java",
+            "src/main/java/com/shop/cart/Cart.java",
+            "src/main/java/com/shop/cart/Item.java",
+        ];
+        assert_eq!(
+            resolve(&layout, &files, "com.shop.cart.Cart"),
+            ["src/main/java/com/shop/cart/Cart.java"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "com.shop.cart.Cart.EMPTY"),
+            ["src/main/java/com/shop/cart/Cart.java"]
+        );
+        assert_eq!(
+            resolve(&layout, &files, "com.shop.cart.*"),
+            [
+                "src/main/java/com/shop/cart/Cart.java",
+                "src/main/java/com/shop/cart/Item.java"
+            ]
+        );
+
+        let files = ["src/main.c", "src/util.h", "include/lib/api.h"];
+        assert_eq!(resolve(&layout, &files, "util.h"), ["src/util.h"]);
+        assert_eq!(
+            resolve(&layout, &files, "<lib/api.h>"),
+            ["include/lib/api.h"]
+        );
+        assert!(resolve(&layout, &files, "<stdio.h>").is_empty());
+    }
+
+    #[test]
+    fn test_module_dependencies() {
+        let db = Database::in_memory().unwrap();
+        let lib = add_file(&db, "src/lib.rs", Language::Rust);
+        add_file(&db, "src/db/mod.rs", Language::Rust);
+        add_file(&db, "src/db/search.rs", Language::Rust);
+        for module in ["self::db", "crate::db::search"] {
+            db.insert_import(&ModuleImport {
+                source_node_id: lib,
+                module: module.to_string(),
+                file_path: "src/lib.rs".to_string(),
+                line: 1,
+                language: Language::Rust,
+            })
+            .unwrap();
+        }
+        assert_eq!(db.resolve_imports(&ProjectLayout::default()).unwrap(), 2);
+        // Resolving again replaces the edges rather than adding to them
+        assert_eq!(db.resolve_imports(&ProjectLayout::default()).unwrap(), 2);
+
+        assert_eq!(db.get_file_dependencies().unwrap().len(), 2);
+        assert_eq!(
+            db.get_module_dependencies().unwrap(),
+            [Dependency {
+                from: "src".to_string(),
+                to: "src/db".to_string(),
+                imports: 1,
+            }]
+        );
+    }
+}
@@ -6,12 +6,24 @@
 //! - File tracking
 //! - Query operations
 
+mod diagnostics;
+mod imports;
+mod overrides;
+mod partials;
+mod resolve;
 mod schema;
+mod search;
+
+pub use imports::{Dependency, ProjectLayout, LAYOUT_FILES};
+pub use search::{MatchKind, SearchHit, SearchOptions};
 
 use anyhow::Result;
 use rusqlite::{params, Connection, OptionalExtension};
-use std::path::Path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
 
+use crate::extraction::grammars;
 use crate::types::{
     Edge, EdgeKind, FileRecord, IndexStats, Language, Node, NodeKind, UnresolvedReference,
     Visibility,
@@ -43,9 +55,34 @@ impl Database {
         Ok(db)
     }
 
-    /// Initialize the database schema
+    /// Path of the database file; `None` for an in-memory database
+    pub fn path(&self) -> Option<PathBuf> {
+        self.conn
+            .path()
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Initialize the database schema, rebuilding it if the version changed
     fn initialize(&self) -> Result<()> {
+        let version: i32 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version != schema::SCHEMA_VERSION {
+            if version != 0 {
+                info!(
+                    "Index schema version {} is outdated (expected {}), rebuilding",
+                    version,
+                    schema::SCHEMA_VERSION
+                );
+            }
+            self.conn.execute_batch(schema::DROP_ALL)?;
+        }
         self.conn.execute_batch(schema::SCHEMA)?;
+        self.conn
+            .pragma_update(None, "user_version", schema::SCHEMA_VERSION)?;
+        // Indexing and resolution keep a dozen or so hot statements cached
+        self.conn.set_prepared_statement_cache_capacity(64);
         Ok(())
     }
 
@@ -55,7 +92,7 @@ impl Database {
 
     /// Insert or update a file record (upsert operation)
     pub fn insert_or_update_file(&self, file: &FileRecord) -> Result<()> {
-        self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             r#"
             INSERT INTO files (path, content_hash, language, size, modified_at, indexed_at, node_count)
             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
@@ -67,16 +104,16 @@ impl Database {
                 indexed_at = excluded.indexed_at,
                 node_count = excluded.node_count
             "#,
-            params![
-                file.path,
-                file.content_hash,
-                file.language.as_str(),
-                file.size as i64,
-                file.modified_at,
-                file.indexed_at,
-                file.node_count as i64,
-            ],
         )?;
+        stmt.execute(params![
+            file.path,
+            file.content_hash,
+            file.language.as_str(),
+            file.size as i64,
+            file.modified_at,
+            file.indexed_at,
+            file.node_count as i64,
+        ])?;
         Ok(())
     }
 
@@ -87,22 +124,38 @@ impl Database {
             .query_row(
                 "SELECT path, content_hash, language, size, modified_at, indexed_at, node_count FROM files WHERE path = ?1",
                 params![path],
-                |row| {
-                    Ok(FileRecord {
-                        path: row.get(0)?,
-                        content_hash: row.get(1)?,
-                        language: Language::from_extension(row.get::<_, String>(2)?.as_str()),
-                        size: row.get::<_, i64>(3)? as u64,
-                        modified_at: row.get(4)?,
-                        indexed_at: row.get(5)?,
-                        node_count: row.get::<_, i64>(6)? as u32,
-                    })
-                },
+                Self::row_to_file,
             )
             .optional()?;
         Ok(result)
     }
 
+    /// Get all indexed file records
+    pub fn get_all_files(&self) -> Result<Vec<FileRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, content_hash, language, size, modified_at, indexed_at, node_count FROM files ORDER BY path",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_file)?;
+
+        let mut files = Vec::new();
+        for row in rows {
+            files.push(row?);
+        }
+        Ok(files)
+    }
+
+    fn row_to_file(row: &rusqlite::Row) -> rusqlite::Result<FileRecord> {
+        Ok(FileRecord {
+            path: row.get(0)?,
+            content_hash: row.get(1)?,
+            language: Language::parse(&row.get::<_, String>(2)?),
+            size: row.get::<_, i64>(3)? as u64,
+            modified_at: row.get(4)?,
+            indexed_at: row.get(5)?,
+            node_count: row.get::<_, i64>(6)? as u32,
+        })
+    }
+
     /// Check if a file needs reindexing
     pub fn needs_reindex(&self, path: &str, content_hash: &str) -> Result<bool> {
         match self.get_file(path)? {
@@ -112,7 +165,24 @@ impl Database {
     }
 
     /// Delete a file and its nodes/edges
+    ///
+    /// References from other files that were resolved to symbols with the
+    /// same names as this file's symbols are queued for resolution again.
     pub fn delete_file(&self, path: &str) -> Result<()> {
+        let names: Vec<String> = self
+            .get_nodes_by_file(path)?
+            .into_iter()
+            .filter(|n| n.kind != NodeKind::File)
+            .map(|n| n.name)
+            .collect();
+        self.invalidate_refs_named(&names)?;
+
+        // Delete edges resolved from this file's references; with a source
+        // name (`impl Trait for Type`) their source may live in another file
+        self.conn.execute(
+            "DELETE FROM edges WHERE ref_id IN (SELECT id FROM unresolved_refs WHERE file_path = ?1)",
+            params![path],
+        )?;
         // Delete edges where source or target is in this file
         self.conn.execute(
             "DELETE FROM edges WHERE source_id IN (SELECT id FROM nodes WHERE file_path = ?1)",
@@ -122,53 +192,163 @@ impl Database {
             "DELETE FROM edges WHERE target_id IN (SELECT id FROM nodes WHERE file_path = ?1)",
             params![path],
         )?;
-        // Delete nodes
-        self.conn
-            .execute("DELETE FROM nodes WHERE file_path = ?1", params![path])?;
-        // Delete unresolved references
+        // Delete unresolved references and module imports
         self.conn.execute(
             "DELETE FROM unresolved_refs WHERE file_path = ?1",
             params![path],
         )?;
+        self.conn
+            .execute("DELETE FROM imports WHERE file_path = ?1", params![path])?;
+        self.conn.execute(
+            "DELETE FROM diagnostics WHERE file_path = ?1",
+            params![path],
+        )?;
+        // Delete nodes
+        self.conn
+            .execute("DELETE FROM nodes WHERE file_path = ?1", params![path])?;
         // Delete file record
         self.conn
             .execute("DELETE FROM files WHERE path = ?1", params![path])?;
         Ok(())
     }
 
+    /// Move a file and its symbols to a new path, keeping node ids
+    ///
+    /// Only the paths change, so the caller must check that the file is
+    /// parsed the same way at its new path. Resolution ranks candidates by
+    /// location, so references from and to the file are queued for
+    /// resolution again.
+    pub fn rename_file(&self, from: &str, to: &str) -> Result<()> {
+        let Some(mut file) = self.get_file(from)? else {
+            return Ok(());
+        };
+
+        let names: Vec<String> = self
+            .get_nodes_by_file(from)?
+            .into_iter()
+            .filter(|n| n.kind != NodeKind::File)
+            .map(|n| n.name)
+            .collect();
+        self.invalidate_refs_named(&names)?;
+        self.conn.execute(
+            "DELETE FROM edges WHERE ref_id IN (SELECT id FROM unresolved_refs WHERE file_path = ?1)",
+            params![from],
+        )?;
+        self.conn.execute(
+            "UPDATE unresolved_refs SET file_path = ?2, resolved = 0 WHERE file_path = ?1",
+            params![from, to],
+        )?;
+        self.conn.execute(
+            "UPDATE imports SET file_path = ?2 WHERE file_path = ?1",
+            params![from, to],
+        )?;
+
+        // Insert the new record first; nodes reference files(path)
+        file.path = to.to_string();
+        self.insert_or_update_file(&file)?;
+        self.conn.execute(
+            "UPDATE nodes SET file_path = ?2 WHERE file_path = ?1",
+            params![from, to],
+        )?;
+        self.conn.execute(
+            "UPDATE diagnostics SET file_path = ?2 WHERE file_path = ?1",
+            params![from, to],
+        )?;
+        let file_name = Path::new(to)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| to.to_string());
+        self.conn.execute(
+            "UPDATE nodes SET name = ?2, qualified_name = ?1 WHERE file_path = ?1 AND kind = 'file'",
+            params![to, file_name],
+        )?;
+        self.reindex_search_text(to)?;
+        self.conn.execute(
+            "UPDATE edges SET file_path = ?2 WHERE file_path = ?1",
+            params![from, to],
+        )?;
+        self.conn
+            .execute("DELETE FROM files WHERE path = ?1", params![from])?;
+        Ok(())
+    }
+
+    /// Other files with edges into `files`, or located in them: the files
+    /// whose cross-file links must be redone when `files` are extracted again
+    pub fn get_dependent_files(&self, files: &[String]) -> Result<BTreeSet<String>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT DISTINCT s.file_path FROM edges e
+             INNER JOIN nodes s ON s.id = e.source_id
+             INNER JOIN nodes t ON t.id = e.target_id
+             WHERE (t.file_path = ?1 OR e.file_path = ?1) AND s.file_path != ?1",
+        )?;
+        let mut dependents = BTreeSet::new();
+        for file in files {
+            let rows = stmt.query_map(params![file], |row| row.get::<_, String>(0))?;
+            for row in rows {
+                dependents.insert(row?);
+            }
+        }
+        Ok(dependents)
+    }
+
     // =========================================================================
     // Node Operations
     // =========================================================================
 
     /// Insert a node and return its ID
     pub fn insert_node(&self, node: &Node) -> Result<i64> {
-        self.conn.execute(
+        let decorators = match node.decorators.is_empty() {
+            true => None,
+            false => Some(serde_json::to_string(&node.decorators)?),
+        };
+        let doc_tags = node
+            .doc_tags
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let signature_parts = node
+            .signature_parts
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let mut stmt = self.conn.prepare_cached(
             r#"
             INSERT INTO nodes (
                 kind, name, qualified_name, file_path, start_line, end_line,
                 start_column, end_column, signature, visibility, docstring,
-                is_async, is_static, is_exported, language
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                is_async, is_static, is_exported, language, decorators, arity,
+                doc_tags, signature_parts, has_parse_errors
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19,
+                ?20
+            )
             "#,
-            params![
-                node.kind.as_str(),
-                node.name,
-                node.qualified_name,
-                node.file_path,
-                node.start_line as i64,
-                node.end_line as i64,
-                node.start_column as i64,
-                node.end_column as i64,
-                node.signature,
-                node.visibility.as_str(),
-                node.docstring,
-                node.is_async,
-                node.is_static,
-                node.is_exported,
-                node.language.as_str(),
-            ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        stmt.execute(params![
+            node.kind.as_str(),
+            node.name,
+            node.qualified_name,
+            node.file_path,
+            node.start_line as i64,
+            node.end_line as i64,
+            node.start_column as i64,
+            node.end_column as i64,
+            node.signature,
+            node.visibility.as_str(),
+            node.docstring,
+            node.is_async,
+            node.is_static,
+            node.is_exported,
+            node.language.as_str(),
+            decorators,
+            node.arity.map(|a| a as i64),
+            doc_tags,
+            signature_parts,
+            node.has_parse_errors,
+        ])?;
+        let id = self.conn.last_insert_rowid();
+        self.index_search_text(id, node)?;
+        Ok(id)
     }
 
     /// Get a node by ID
@@ -182,49 +362,23 @@ impl Database {
         Ok(result)
     }
 
-    /// Search nodes by name (case-insensitive prefix match)
+    /// Search nodes by name, best matches first (see [`Database::search_symbols`])
     pub fn search_nodes(
         &self,
         query: &str,
         kind: Option<NodeKind>,
         limit: u32,
     ) -> Result<Vec<Node>> {
-        let pattern = format!("{}%", query.to_lowercase());
-
-        let sql = if kind.is_some() {
-            r#"
-            SELECT * FROM nodes
-            WHERE LOWER(name) LIKE ?1 AND kind = ?2
-            ORDER BY LENGTH(name), name
-            LIMIT ?3
-            "#
-        } else {
-            r#"
-            SELECT * FROM nodes
-            WHERE LOWER(name) LIKE ?1
-            ORDER BY LENGTH(name), name
-            LIMIT ?2
-            "#
+        let options = SearchOptions {
+            kind,
+            limit,
+            ..Default::default()
         };
-
-        let mut stmt = self.conn.prepare(sql)?;
-        let mut nodes = Vec::new();
-
-        if let Some(k) = kind {
-            let rows = stmt.query_map(params![pattern, k.as_str(), limit as i64], |row| {
-                Self::row_to_node(row)
-            })?;
-            for row in rows {
-                nodes.push(row?);
-            }
-        } else {
-            let rows = stmt.query_map(params![pattern, limit as i64], Self::row_to_node)?;
-            for row in rows {
-                nodes.push(row?);
-            }
-        }
-
-        Ok(nodes)
+        Ok(self
+            .search_symbols(query, &options)?
+            .into_iter()
+            .map(|hit| hit.node)
+            .collect())
     }
 
     /// Get nodes by file path
@@ -254,6 +408,20 @@ impl Database {
         Ok(result)
     }
 
+    /// Find all nodes with a name (exact match), in index order
+    pub fn find_nodes_by_name(&self, name: &str) -> Result<Vec<Node>> {
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT * FROM nodes WHERE name = ?1 ORDER BY id")?;
+        let rows = stmt.query_map(params![name], Self::row_to_node)?;
+
+        let mut nodes = Vec::new();
+        for row in rows {
+            nodes.push(row?);
+        }
+        Ok(nodes)
+    }
+
     fn row_to_node(row: &rusqlite::Row) -> rusqlite::Result<Node> {
         Ok(Node {
             id: row.get(0)?,
@@ -266,12 +434,24 @@ impl Database {
             start_column: row.get::<_, i64>(7)? as u32,
             end_column: row.get::<_, i64>(8)? as u32,
             signature: row.get(9)?,
+            signature_parts: row
+                .get::<_, Option<String>>(19)?
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            arity: row.get::<_, Option<i64>>(17)?.map(|a| a as u32),
             visibility: Visibility::parse(&row.get::<_, String>(10).unwrap_or_default()),
             docstring: row.get(11)?,
+            doc_tags: row
+                .get::<_, Option<String>>(18)?
+                .and_then(|json| serde_json::from_str(&json).ok()),
             is_async: row.get(12)?,
             is_static: row.get(13)?,
             is_exported: row.get(14)?,
+            has_parse_errors: row.get(20)?,
             language: Language::parse(&row.get::<_, String>(15).unwrap_or_default()),
+            decorators: row
+                .get::<_, Option<String>>(16)?
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
         })
     }
 
@@ -281,34 +461,60 @@ impl Database {
 
     /// Insert an edge
     pub fn insert_edge(&self, edge: &Edge) -> Result<i64> {
-        self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             r#"
-            INSERT INTO edges (source_id, target_id, kind, file_path, line, column)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            INSERT INTO edges (source_id, target_id, kind, file_path, line, column, confidence)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
             "#,
-            params![
-                edge.source_id,
-                edge.target_id,
-                edge.kind.as_str(),
-                edge.file_path,
-                edge.line.map(|l| l as i64),
-                edge.column.map(|c| c as i64),
-            ],
         )?;
+        stmt.execute(params![
+            edge.source_id,
+            edge.target_id,
+            edge.kind.as_str(),
+            edge.file_path,
+            edge.line.map(|l| l as i64),
+            edge.column.map(|c| c as i64),
+            edge.confidence,
+        ])?;
         Ok(self.conn.last_insert_rowid())
     }
 
     /// Get callers of a node (nodes that call this node)
+    ///
+    /// Calls that resolved to several equally likely targets are left out,
+    /// see [`Database::get_weighted_callers`].
     pub fn get_callers(&self, node_id: i64, limit: u32) -> Result<Vec<Node>> {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT n.* FROM nodes n
             INNER JOIN edges e ON e.source_id = n.id
-            WHERE e.target_id = ?1 AND e.kind = 'calls'
+            WHERE e.target_id = ?1 AND e.kind = 'calls' AND e.confidence >= ?3
             LIMIT ?2
             "#,
         )?;
-        let rows = stmt.query_map(params![node_id, limit as i64], Self::row_to_node)?;
+        let rows = stmt.query_map(
+            params![node_id, limit as i64, resolve::MIN_CONFIDENCE],
+            Self::row_to_node,
+        )?;
+
+        let mut nodes = Vec::new();
+        for row in rows {
+            nodes.push(row?);
+        }
+        Ok(nodes)
+    }
+
+    /// Get the methods that override a method
+    pub fn get_overriding(&self, node_id: i64) -> Result<Vec<Node>> {
+        let mut stmt = self.conn.prepare_cached(
+            r#"
+            SELECT n.* FROM nodes n
+            INNER JOIN edges e ON e.source_id = n.id
+            WHERE e.target_id = ?1 AND e.kind = 'overrides'
+            ORDER BY n.file_path, n.start_line
+            "#,
+        )?;
+        let rows = stmt.query_map(params![node_id], Self::row_to_node)?;
 
         let mut nodes = Vec::new();
         for row in rows {
@@ -318,16 +524,22 @@ impl Database {
     }
 
     /// Get callees of a node (nodes that this node calls)
+    ///
+    /// Calls that resolved to several equally likely targets are left out,
+    /// see [`Database::get_weighted_callees`].
     pub fn get_callees(&self, node_id: i64, limit: u32) -> Result<Vec<Node>> {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT n.* FROM nodes n
             INNER JOIN edges e ON e.target_id = n.id
-            WHERE e.source_id = ?1 AND e.kind = 'calls'
+            WHERE e.source_id = ?1 AND e.kind = 'calls' AND e.confidence >= ?3
             LIMIT ?2
             "#,
         )?;
-        let rows = stmt.query_map(params![node_id, limit as i64], Self::row_to_node)?;
+        let rows = stmt.query_map(
+            params![node_id, limit as i64, resolve::MIN_CONFIDENCE],
+            Self::row_to_node,
+        )?;
 
         let mut nodes = Vec::new();
         for row in rows {
@@ -336,6 +548,53 @@ impl Database {
         Ok(nodes)
     }
 
+    /// Get all callers of a node with the confidence of each call, most
+    /// certain first
+    pub fn get_weighted_callers(&self, node_id: i64, limit: u32) -> Result<Vec<(Node, f64)>> {
+        self.weighted_calls(
+            r#"
+            SELECT n.*, MAX(e.confidence) AS call_confidence FROM nodes n
+            INNER JOIN edges e ON e.source_id = n.id
+            WHERE e.target_id = ?1 AND e.kind = 'calls'
+            GROUP BY n.id
+            ORDER BY call_confidence DESC, n.file_path, n.start_line
+            LIMIT ?2
+            "#,
+            node_id,
+            limit,
+        )
+    }
+
+    /// Get all callees of a node with the confidence of each call, most
+    /// certain first
+    pub fn get_weighted_callees(&self, node_id: i64, limit: u32) -> Result<Vec<(Node, f64)>> {
+        self.weighted_calls(
+            r#"
+            SELECT n.*, MAX(e.confidence) AS call_confidence FROM nodes n
+            INNER JOIN edges e ON e.target_id = n.id
+            WHERE e.source_id = ?1 AND e.kind = 'calls'
+            GROUP BY n.id
+            ORDER BY call_confidence DESC, n.file_path, n.start_line
+            LIMIT ?2
+            "#,
+            node_id,
+            limit,
+        )
+    }
+
+    fn weighted_calls(&self, sql: &str, node_id: i64, limit: u32) -> Result<Vec<(Node, f64)>> {
+        let mut stmt = self.conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params![node_id, limit as i64], |row| {
+            Ok((Self::row_to_node(row)?, row.get("call_confidence")?))
+        })?;
+
+        let mut calls = Vec::new();
+        for row in rows {
+            calls.push(row?);
+        }
+        Ok(calls)
+    }
+
     /// Get all edges from a node
     pub fn get_outgoing_edges(&self, node_id: i64) -> Result<Vec<Edge>> {
         let mut stmt = self
@@ -373,6 +632,7 @@ impl Database {
             file_path: row.get(4)?,
             line: row.get::<_, Option<i64>>(5)?.map(|l| l as u32),
             column: row.get::<_, Option<i64>>(6)?.map(|c| c as u32),
+            confidence: row.get(7)?,
         })
     }
 
@@ -382,35 +642,51 @@ impl Database {
 
     /// Insert an unresolved reference
     pub fn insert_unresolved_ref(&self, uref: &UnresolvedReference) -> Result<()> {
-        self.conn.execute(
+        let mut stmt = self.conn.prepare_cached(
             r#"
-            INSERT INTO unresolved_refs (source_node_id, reference_name, kind, file_path, line, column)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            INSERT INTO unresolved_refs (source_node_id, reference_name, kind, file_path, line, column, source_name, receiver)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             "#,
-            params![
-                uref.source_node_id,
-                uref.reference_name,
-                uref.kind.as_str(),
-                uref.file_path,
-                uref.line as i64,
-                uref.column as i64,
-            ],
         )?;
+        stmt.execute(params![
+            uref.source_node_id,
+            uref.reference_name,
+            uref.kind.as_str(),
+            uref.file_path,
+            uref.line as i64,
+            uref.column as i64,
+            uref.source_name,
+            uref.receiver,
+        ])?;
         Ok(())
     }
 
-    /// Get all unresolved references
+    /// Get the references still waiting to be resolved
     pub fn get_unresolved_refs(&self) -> Result<Vec<UnresolvedReference>> {
-        let mut stmt = self.conn.prepare("SELECT * FROM unresolved_refs")?;
+        Ok(self
+            .pending_refs()?
+            .into_iter()
+            .map(|(_, uref)| uref)
+            .collect())
+    }
+
+    /// Pending references with their row ids
+    fn pending_refs(&self) -> Result<Vec<(i64, UnresolvedReference)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT * FROM unresolved_refs WHERE resolved = 0 ORDER BY id")?;
         let rows = stmt.query_map([], |row| {
-            Ok(UnresolvedReference {
+            let uref = UnresolvedReference {
                 source_node_id: row.get(1)?,
                 reference_name: row.get(2)?,
                 kind: EdgeKind::parse(&row.get::<_, String>(3)?).unwrap_or(EdgeKind::Calls),
                 file_path: row.get(4)?,
                 line: row.get::<_, i64>(5)? as u32,
                 column: row.get::<_, i64>(6)? as u32,
-            })
+                source_name: row.get(7)?,
+                receiver: row.get(8)?,
+            };
+            Ok((row.get(0)?, uref))
         })?;
 
         let mut refs = Vec::new();
@@ -420,31 +696,30 @@ impl Database {
         Ok(refs)
     }
 
-    /// Resolve references by matching names to nodes
-    pub fn resolve_references(&self) -> Result<u32> {
-        let refs = self.get_unresolved_refs()?;
-        let mut resolved = 0;
-
-        for uref in refs {
-            if let Some(target) = self.find_node_by_name(&uref.reference_name)? {
-                let edge = Edge {
-                    id: 0,
-                    source_id: uref.source_node_id,
-                    target_id: target.id,
-                    kind: uref.kind,
-                    file_path: Some(uref.file_path.clone()),
-                    line: Some(uref.line),
-                    column: Some(uref.column),
-                };
-                self.insert_edge(&edge)?;
-                resolved += 1;
-            }
-        }
+    // =========================================================================
+    // Settings
+    // =========================================================================
 
-        // Clear resolved refs
-        self.conn.execute("DELETE FROM unresolved_refs", [])?;
+    /// Get a setting the index was built with
+    pub fn get_meta(&self, key: &str) -> Result<Option<String>> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
 
-        Ok(resolved)
+    /// Record a setting the index was built with
+    pub fn set_meta(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
     }
 
     // =========================================================================
@@ -503,6 +778,7 @@ impl Database {
         for row in kind_rows {
             node_kinds.push(row?);
         }
+        let (parse_errors, files_with_errors) = self.diagnostic_counts()?;
 
         Ok(IndexStats {
             total_files: total_files as u64,
@@ -511,6 +787,9 @@ impl Database {
             db_size_bytes: db_size_bytes as u64,
             languages,
             node_kinds,
+            parse_errors,
+            files_with_errors,
+            grammars: grammars::available(),
         })
     }
 
@@ -532,40 +811,19 @@ impl Database {
         Ok(())
     }
 
-    /// Get the hierarchy of a symbol (parent contains relationships)
-    pub fn get_hierarchy(&self, symbol: &str) -> Result<Vec<Node>> {
+    /// Get the hierarchy of a symbol: what contains it and what it contains
+    pub fn get_hierarchy(&self, node_id: i64) -> Result<Vec<Node>> {
         let mut stmt = self.conn.prepare(
             "SELECT n.* FROM nodes n
              INNER JOIN edges e ON e.source_id = n.id
-             INNER JOIN nodes target ON e.target_id = target.id
-             WHERE e.kind = 'contains' AND target.name = ?
+             WHERE e.kind = 'contains' AND e.target_id = ?1
              UNION
              SELECT n.* FROM nodes n
              INNER JOIN edges e ON e.target_id = n.id
-             INNER JOIN nodes source ON e.source_id = source.id
-             WHERE e.kind = 'contains' AND source.name = ?",
+             WHERE e.kind = 'contains' AND e.source_id = ?1",
         )?;
 
-        let rows = stmt.query_map(params![symbol, symbol], |row| {
-            Ok(Node {
-                id: row.get(0)?,
-                kind: NodeKind::parse(&row.get::<_, String>(1)?).unwrap_or(NodeKind::Function),
-                name: row.get(2)?,
-                qualified_name: row.get(3)?,
-                file_path: row.get(4)?,
-                start_line: row.get::<_, i64>(5)? as u32,
-                end_line: row.get::<_, i64>(6)? as u32,
-                start_column: row.get::<_, i64>(7)? as u32,
-                end_column: row.get::<_, i64>(8)? as u32,
-                signature: row.get(9)?,
-                visibility: Visibility::parse(&row.get::<_, String>(10)?),
-                docstring: row.get(11)?,
-                is_async: row.get(12)?,
-                is_static: row.get(13)?,
-                is_exported: row.get(14)?,
-                language: Language::parse(&row.get::<_, String>(15)?),
-            })
-        })?;
+        let rows = stmt.query_map(params![node_id], Self::row_to_node)?;
 
         let mut nodes = Vec::new();
         for row in rows {
@@ -581,41 +839,44 @@ impl Database {
         let target = self.find_node_by_name(to)?;
 
         match (source, target) {
-            (Some(src), Some(tgt)) => {
-                let mut paths = Vec::new();
-                let mut visited = std::collections::HashSet::new();
-                let mut queue = std::collections::VecDeque::new();
-                queue.push_back((src.id, vec![src.clone()]));
-
-                while let Some((current_id, path)) = queue.pop_front() {
-                    if current_id == tgt.id {
-                        paths.push(path);
-                        if paths.len() >= 5 {
-                            // Limit to first 5 paths
-                            break;
-                        }
-                        continue;
-                    }
-
-                    if path.len() > 10 || visited.contains(&current_id) {
-                        // Depth limit and cycle prevention
-                        continue;
-                    }
-                    visited.insert(current_id);
-
-                    // Get all callees
-                    let callees = self.get_callees(current_id, 100)?;
-                    for callee in callees {
-                        let mut new_path = path.clone();
-                        new_path.push(callee.clone());
-                        queue.push_back((callee.id, new_path));
-                    }
+            (Some(src), Some(tgt)) => self.find_call_paths_between(&src, &tgt),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Find call paths from one resolved symbol to another using BFS
+    pub fn find_call_paths_between(&self, src: &Node, tgt: &Node) -> Result<Vec<Vec<Node>>> {
+        let mut paths = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back((src.id, vec![src.clone()]));
+
+        while let Some((current_id, path)) = queue.pop_front() {
+            if current_id == tgt.id {
+                paths.push(path);
+                if paths.len() >= 5 {
+                    // Limit to first 5 paths
+                    break;
                 }
+                continue;
+            }
 
-                Ok(paths)
+            if path.len() > 10 || visited.contains(&current_id) {
+                // Depth limit and cycle prevention
+                continue;
+            }
+            visited.insert(current_id);
+
+            // Get all callees
+            let callees = self.get_callees(current_id, 100)?;
+            for callee in callees {
+                let mut new_path = path.clone();
+                new_path.push(callee.clone());
+                queue.push_back((callee.id, new_path));
             }
-            _ => Ok(Vec::new()),
         }
+
+        Ok(paths)
     }
 
     /// Find unused symbols (no incoming calls/references)
@@ -623,30 +884,11 @@ impl Database {
         let mut stmt = self.conn.prepare(
             "SELECT n.* FROM nodes n
              WHERE n.kind IN ('function', 'method', 'class', 'struct', 'interface')
-             AND n.id NOT IN (SELECT DISTINCT target_id FROM edges WHERE kind IN ('calls', 'references', 'instantiates'))
+             AND n.id NOT IN (SELECT DISTINCT target_id FROM edges WHERE kind IN ('calls', 'references', 'type_of', 'returns', 'instantiates'))
              ORDER BY n.file_path, n.start_line",
         )?;
 
-        let rows = stmt.query_map([], |row| {
-            Ok(Node {
-                id: row.get(0)?,
-                kind: NodeKind::parse(&row.get::<_, String>(1)?).unwrap_or(NodeKind::Function),
-                name: row.get(2)?,
-                qualified_name: row.get(3)?,
-                file_path: row.get(4)?,
-                start_line: row.get::<_, i64>(5)? as u32,
-                end_line: row.get::<_, i64>(6)? as u32,
-                start_column: row.get::<_, i64>(7)? as u32,
-                end_column: row.get::<_, i64>(8)? as u32,
-                signature: row.get(9)?,
-                visibility: Visibility::parse(&row.get::<_, String>(10)?),
-                docstring: row.get(11)?,
-                is_async: row.get(12)?,
-                is_static: row.get(13)?,
-                is_exported: row.get(14)?,
-                language: Language::parse(&row.get::<_, String>(15)?),
-            })
-        })?;
+        let rows = stmt.query_map([], Self::row_to_node)?;
 
         let mut nodes = Vec::new();
         for row in rows {
@@ -656,34 +898,39 @@ impl Database {
     }
 
     /// Find all implementations of an interface/trait
-    pub fn find_implementations(&self, symbol: &str) -> Result<Vec<Node>> {
+    pub fn find_implementations(&self, node_id: i64) -> Result<Vec<Node>> {
         let mut stmt = self.conn.prepare(
             "SELECT n.* FROM nodes n
              INNER JOIN edges e ON e.source_id = n.id
-             INNER JOIN nodes target ON e.target_id = target.id
-             WHERE e.kind IN ('implements', 'extends') AND target.name = ?",
+             WHERE e.kind IN ('implements', 'extends') AND e.target_id = ?",
         )?;
 
-        let rows = stmt.query_map([symbol], |row| {
-            Ok(Node {
-                id: row.get(0)?,
-                kind: NodeKind::parse(&row.get::<_, String>(1)?).unwrap_or(NodeKind::Function),
-                name: row.get(2)?,
-                qualified_name: row.get(3)?,
-                file_path: row.get(4)?,
-                start_line: row.get::<_, i64>(5)? as u32,
-                end_line: row.get::<_, i64>(6)? as u32,
-                start_column: row.get::<_, i64>(7)? as u32,
-                end_column: row.get::<_, i64>(8)? as u32,
-                signature: row.get(9)?,
-                visibility: Visibility::parse(&row.get::<_, String>(10)?),
-                docstring: row.get(11)?,
-                is_async: row.get(12)?,
-                is_static: row.get(13)?,
-                is_exported: row.get(14)?,
-                language: Language::parse(&row.get::<_, String>(15)?),
-            })
-        })?;
+        let rows = stmt.query_map([node_id], Self::row_to_node)?;
+
+        let mut nodes = Vec::new();
+        for row in rows {
+            nodes.push(row?);
+        }
+        Ok(nodes)
+    }
+
+    /// Find symbols carrying a decorator, annotation or attribute
+    ///
+    /// `decorator` matches the name as written (`app.route`) or its last
+    /// segment (`route`).
+    pub fn find_decorated(&self, decorator: &str) -> Result<Vec<Node>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT n.* FROM nodes n
+             WHERE n.decorators IS NOT NULL
+             AND EXISTS (
+                 SELECT 1 FROM json_each(n.decorators) d
+                 WHERE json_extract(d.value, '$.name') = ?1
+                 OR substr(json_extract(d.value, '$.name'), -length(?1) - 1) IN ('.' || ?1, ':' || ?1)
+             )
+             ORDER BY n.file_path, n.start_line",
+        )?;
+
+        let rows = stmt.query_map([decorator], Self::row_to_node)?;
 
         let mut nodes = Vec::new();
         for row in rows {
@@ -711,26 +958,7 @@ impl Database {
 
         let rows = stmt.query_map(
             params![file_path, end_line, start_line, start_line, end_line],
-            |row| {
-                Ok(Node {
-                    id: row.get(0)?,
-                    kind: NodeKind::parse(&row.get::<_, String>(1)?).unwrap_or(NodeKind::Function),
-                    name: row.get(2)?,
-                    qualified_name: row.get(3)?,
-                    file_path: row.get(4)?,
-                    start_line: row.get::<_, i64>(5)? as u32,
-                    end_line: row.get::<_, i64>(6)? as u32,
-                    start_column: row.get::<_, i64>(7)? as u32,
-                    end_column: row.get::<_, i64>(8)? as u32,
-                    signature: row.get(9)?,
-                    visibility: Visibility::parse(&row.get::<_, String>(10)?),
-                    docstring: row.get(11)?,
-                    is_async: row.get(12)?,
-                    is_static: row.get(13)?,
-                    is_exported: row.get(14)?,
-                    language: Language::parse(&row.get::<_, String>(15)?),
-                })
-            },
+            Self::row_to_node,
         )?;
 
         for row in rows {
@@ -768,12 +996,17 @@ mod tests {
             start_column: 0,
             end_column: 1,
             signature: Some(format!("fn {}()", name)),
+            signature_parts: None,
+            arity: None,
             visibility: Visibility::Public,
             docstring: None,
+            doc_tags: None,
             is_async: false,
             is_static: false,
             is_exported: true,
+            has_parse_errors: false,
             language: Language::Rust,
+            decorators: Vec::new(),
         }
     }
 
@@ -1011,6 +1244,7 @@ mod tests {
             file_path: Some("test.rs".to_string()),
             line: Some(5),
             column: Some(10),
+            confidence: 1.0,
         };
 
         let edge_id = db.insert_edge(&edge).unwrap();
@@ -1038,6 +1272,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -1071,6 +1306,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -1101,6 +1337,8 @@ mod tests {
             file_path: "src/lib.rs".to_string(),
             line: 5,
             column: 10,
+            source_name: None,
+            receiver: None,
         };
 
         db.insert_unresolved_ref(&uref).unwrap();
@@ -1134,6 +1372,8 @@ mod tests {
             file_path: "test.rs".to_string(),
             line: 5,
             column: 10,
+            source_name: None,
+            receiver: None,
         };
         db.insert_unresolved_ref(&uref).unwrap();
 
@@ -1189,6 +1429,7 @@ mod tests {
             file_path: Some("test.rs".to_string()),
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -1262,16 +1503,17 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
         // Get hierarchy for the method
-        let hierarchy = db.get_hierarchy("my_method").unwrap();
+        let hierarchy = db.get_hierarchy(method_id).unwrap();
         assert_eq!(hierarchy.len(), 1);
         assert_eq!(hierarchy[0].name, "MyClass");
 
         // Get hierarchy for the class
-        let hierarchy = db.get_hierarchy("MyClass").unwrap();
+        let hierarchy = db.get_hierarchy(class_id).unwrap();
         assert_eq!(hierarchy.len(), 1);
         assert_eq!(hierarchy[0].name, "my_method");
     }
@@ -1301,6 +1543,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -1312,6 +1555,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -1358,6 +1602,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -1394,6 +1639,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -1405,11 +1651,12 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
         // Find implementations
-        let impls = db.find_implementations("MyTrait").unwrap();
+        let impls = db.find_implementations(interface_id).unwrap();
         assert_eq!(impls.len(), 2);
         assert!(impls.iter().any(|n| n.name == "Impl1"));
         assert!(impls.iter().any(|n| n.name == "Impl2"));
@@ -1444,6 +1691,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -1487,12 +1735,17 @@ mod language_tests {
             start_column: 0,
             end_column: 0,
             signature: Some("fn test_func()".to_string()),
+            signature_parts: None,
+            arity: None,
             visibility: Visibility::Private,
             docstring: None,
+            doc_tags: None,
             is_async: false,
             is_static: false,
             is_exported: false,
+            has_parse_errors: false,
             language: Language::Rust,
+            decorators: Vec::new(),
         };
 
         db.insert_node(&node).unwrap();
@@ -0,0 +1,254 @@
+//! Override detection
+//!
+//! Once `extends` and `implements` edges are resolved, each method of a type
+//! overrides the methods of its nearest ancestors that have the same name and
+//! arity. This covers subclass methods, interface implementations and C++
+//! virtual functions alike. Rust trait impl methods name their trait, so they
+//! are resolved as ordinary references instead.
+//!
+//! Like import edges, override edges depend on every file in the inheritance
+//! chain, so indexing recomputes them from scratch. Watch mode recomputes
+//! those of the types in changed files and of the types below them.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::Database;
+use crate::types::Node;
+
+/// Base types and methods of every type, read from the resolved graph
+struct TypeHierarchy {
+    /// Direct base types of each type, with the confidence of the link
+    bases: HashMap<i64, Vec<(i64, f64)>>,
+    /// Methods by the type that contains them
+    methods: BTreeMap<i64, Vec<Node>>,
+}
+
+impl Database {
+    /// Link methods to the base methods they override, replacing the links
+    /// from earlier runs. Returns the number of links made.
+    pub fn resolve_overrides(&self) -> Result<u32> {
+        self.conn.execute(
+            "DELETE FROM edges WHERE kind = 'overrides' AND ref_id IS NULL",
+            [],
+        )?;
+        let hierarchy = self.get_type_hierarchy()?;
+        let owners: Vec<i64> = hierarchy.methods.keys().copied().collect();
+        self.link_overrides(&hierarchy, &owners)
+    }
+
+    /// Relink the methods of the types declared in `files`, or with methods
+    /// there, and of every type that inherits from them, replacing their
+    /// links. Returns the number of links made.
+    pub fn resolve_overrides_in(&self, files: &[String]) -> Result<u32> {
+        let hierarchy = self.get_type_hierarchy()?;
+
+        let mut in_files = HashSet::new();
+        let mut stmt = self
+            .conn
+            .prepare_cached("SELECT id FROM nodes WHERE file_path = ?1")?;
+        for file in files {
+            let rows = stmt.query_map(params![file], |row| row.get::<_, i64>(0))?;
+            for row in rows {
+                in_files.insert(row?);
+            }
+        }
+        let mut affected: HashSet<i64> = hierarchy
+            .methods
+            .iter()
+            .filter(|(owner, own)| {
+                in_files.contains(owner) || own.iter().any(|m| files.contains(&m.file_path))
+            })
+            .map(|(&owner, _)| owner)
+            .collect();
+
+        // Subtypes inherit whatever changed above them
+        let mut derived: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (&source, targets) in &hierarchy.bases {
+            for &(target, _) in targets {
+                derived.entry(target).or_default().push(source);
+            }
+        }
+        let mut queue: Vec<i64> = affected.iter().chain(&in_files).copied().collect();
+        while let Some(type_id) = queue.pop() {
+            for &child in derived.get(&type_id).into_iter().flatten() {
+                if affected.insert(child) {
+                    queue.push(child);
+                }
+            }
+        }
+
+        let mut owners: Vec<i64> = affected.into_iter().collect();
+        owners.sort_unstable();
+        let mut delete = self.conn.prepare_cached(
+            "DELETE FROM edges WHERE kind = 'overrides' AND ref_id IS NULL AND source_id = ?1",
+        )?;
+        for method in owners
+            .iter()
+            .filter_map(|owner| hierarchy.methods.get(owner))
+            .flatten()
+        {
+            delete.execute(params![method.id])?;
+        }
+        self.link_overrides(&hierarchy, &owners)
+    }
+
+    fn get_type_hierarchy(&self) -> Result<TypeHierarchy> {
+        let mut bases: HashMap<i64, Vec<(i64, f64)>> = HashMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT source_id, target_id, confidence FROM edges
+             WHERE kind IN ('extends', 'implements')
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        for row in rows {
+            let (source, target, confidence): (i64, i64, f64) = row?;
+            bases.entry(source).or_default().push((target, confidence));
+        }
+
+        let mut methods: BTreeMap<i64, Vec<Node>> = BTreeMap::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT n.*, e.source_id AS owner FROM nodes n
+             INNER JOIN edges e ON e.target_id = n.id
+             WHERE e.kind = 'contains' AND n.kind IN ('function', 'method')
+             ORDER BY n.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>("owner")?, Self::row_to_node(row)?))
+        })?;
+        for row in rows {
+            let (owner, node) = row?;
+            methods.entry(owner).or_default().push(node);
+        }
+        Ok(TypeHierarchy { bases, methods })
+    }
+
+    /// Link the methods of `owners` to the methods they override
+    fn link_overrides(&self, hierarchy: &TypeHierarchy, owners: &[i64]) -> Result<u32> {
+        let TypeHierarchy { bases, methods } = hierarchy;
+        if bases.is_empty() {
+            return Ok(0);
+        }
+
+        let mut insert = self.conn.prepare_cached(
+            "INSERT INTO edges (source_id, target_id, kind, file_path, line, column, confidence)
+             SELECT ?1, ?2, 'overrides', ?3, ?4, ?5, ?6
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM edges WHERE source_id = ?1 AND target_id = ?2 AND kind = 'overrides'
+             )",
+        )?;
+        let mut linked = 0;
+        for &owner in owners.iter().filter(|o| bases.contains_key(o)) {
+            let Some(own) = methods.get(&owner) else {
+                continue;
+            };
+            // Constructors initialise their own type
+            for method in own.iter().filter(|m| m.name != "constructor") {
+                for (target, confidence) in overridden(method, owner, bases, methods) {
+                    linked += insert.execute(params![
+                        method.id,
+                        target,
+                        method.file_path,
+                        method.start_line as i64,
+                        method.start_column as i64,
+                        confidence,
+                    ])? as u32;
+                }
+            }
+        }
+        Ok(linked)
+    }
+}
+
+/// Methods of the nearest ancestors of `owner` that `method` overrides, with
+/// the confidence of the inheritance path to each
+fn overridden(
+    method: &Node,
+    owner: i64,
+    bases: &HashMap<i64, Vec<(i64, f64)>>,
+    methods: &BTreeMap<i64, Vec<Node>>,
+) -> Vec<(i64, f64)> {
+    let mut visited = HashSet::from([owner]);
+    let mut level = vec![(owner, 1.0)];
+    while !level.is_empty() {
+        let mut found = Vec::new();
+        let mut next = Vec::new();
+        for (type_id, confidence) in level {
+            for &(base, weight) in bases.get(&type_id).into_iter().flatten() {
+                if !visited.insert(base) {
+                    continue;
+                }
+                let confidence = confidence * weight;
+                found.extend(
+                    methods
+                        .get(&base)
+                        .into_iter()
+                        .flatten()
+                        .filter(|m| m.name == method.name && m.arity == method.arity)
+                        .map(|m| (m.id, confidence)),
+                );
+                next.push((base, confidence));
+            }
+        }
+        if !found.is_empty() {
+            return found;
+        }
+        level = next;
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Language, NodeKind, Visibility};
+
+    fn method(id: i64, name: &str, arity: u32) -> Node {
+        Node {
+            id,
+            kind: NodeKind::Method,
+            name: name.to_string(),
+            qualified_name: None,
+            file_path: "a.java".to_string(),
+            start_line: 1,
+            end_line: 1,
+            start_column: 0,
+            end_column: 0,
+            signature: None,
+            signature_parts: None,
+            arity: Some(arity),
+            visibility: Visibility::Public,
+            docstring: None,
+            doc_tags: None,
+            is_async: false,
+            is_static: false,
+            is_exported: false,
+            has_parse_errors: false,
+            language: Language::Java,
+            decorators: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_overrides_nearest_ancestor_with_same_arity() {
+        // C extends B extends A; B implements I
+        let bases = HashMap::from([(3, vec![(2, 1.0)]), (2, vec![(1, 0.5), (4, 1.0)])]);
+        let methods = BTreeMap::from([
+            (1, vec![method(10, "handle", 1), method(11, "close", 0)]),
+            (2, vec![method(20, "handle", 2)]),
+            (4, vec![method(40, "close", 0)]),
+        ]);
+
+        // B's handle takes two arguments, so C's one-argument handle overrides A's
+        let handle = method(30, "handle", 1);
+        assert_eq!(overridden(&handle, 3, &bases, &methods), [(10, 0.5)]);
+        // A and I are equally near
+        let close = method(31, "close", 0);
+        assert_eq!(
+            overridden(&close, 3, &bases, &methods),
+            [(11, 0.5), (40, 1.0)]
+        );
+        assert!(overridden(&method(32, "open", 0), 3, &bases, &methods).is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! Partial types
+//!
+//! A C# `partial` class, struct or interface is declared in parts, often one
+//! per file. Each part is a node of its own; this links every part to the
+//! members and base types declared by the others, so that any part answers
+//! for the whole type.
+//!
+//! Like override edges, the links depend on several files, so indexing
+//! recomputes them from scratch, and watch mode those of the types with a
+//! part in a changed file. They are told apart from the edges of extraction
+//! and resolution by having no reference and naming the file of the other
+//! part.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::BTreeMap;
+
+/// Parts of each partial type, by qualified name and kind
+type PartialTypes = BTreeMap<(String, String), Vec<Node>>;
+
+use super::Database;
+use crate::types::{Language, Node};
+
+impl Database {
+    /// Link the parts of each partial type to each other's members and base
+    /// types, replacing the links from earlier runs. Returns the number of
+    /// links made.
+    pub fn merge_partial_types(&self) -> Result<u32> {
+        self.conn.execute(
+            "DELETE FROM edges
+             WHERE kind IN ('contains', 'extends', 'implements') AND ref_id IS NULL
+               AND file_path IS NOT (SELECT file_path FROM nodes WHERE id = edges.source_id)",
+            [],
+        )?;
+        self.link_partial_types(&self.get_partial_types()?)
+    }
+
+    /// Relink the partial types with a part in `files`, replacing their
+    /// links. Returns the number of links made.
+    pub fn merge_partial_types_in(&self, files: &[String]) -> Result<u32> {
+        let mut types = self.get_partial_types()?;
+        types.retain(|_, parts| parts.iter().any(|p| files.contains(&p.file_path)));
+
+        let mut delete = self.conn.prepare_cached(
+            "DELETE FROM edges
+             WHERE source_id = ?1 AND kind IN ('contains', 'extends', 'implements')
+               AND ref_id IS NULL AND file_path IS NOT ?2",
+        )?;
+        for part in types.values().flatten() {
+            delete.execute(params![part.id, part.file_path])?;
+        }
+        self.link_partial_types(&types)
+    }
+
+    fn get_partial_types(&self) -> Result<PartialTypes> {
+        let mut types = PartialTypes::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT * FROM nodes
+             WHERE language = ?1 AND kind IN ('class', 'struct', 'interface')
+               AND qualified_name IS NOT NULL
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![Language::CSharp.as_str()], Self::row_to_node)?;
+        for row in rows {
+            let node = row?;
+            if !is_partial(&node) {
+                continue;
+            }
+            let key = (
+                node.qualified_name.clone().unwrap_or_default(),
+                node.kind.as_str().to_string(),
+            );
+            types.entry(key).or_default().push(node);
+        }
+        Ok(types)
+    }
+
+    fn link_partial_types(&self, types: &PartialTypes) -> Result<u32> {
+        let mut edges_of = self.conn.prepare(
+            "SELECT e.target_id, e.kind, e.confidence, n.file_path, n.start_line, n.start_column
+             FROM edges e INNER JOIN nodes n ON n.id = e.target_id
+             WHERE e.source_id = ?1 AND e.kind IN ('contains', 'extends', 'implements')
+             ORDER BY e.id",
+        )?;
+        let mut insert = self.conn.prepare_cached(
+            "INSERT INTO edges (source_id, target_id, kind, file_path, line, column, confidence)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM edges WHERE source_id = ?1 AND target_id = ?2 AND kind = ?3
+             )",
+        )?;
+        let mut linked = 0;
+        for parts in types.values().filter(|parts| parts.len() > 1) {
+            // What each part declares itself, before any links are added
+            let mut declared = Vec::new();
+            for part in parts {
+                let rows = edges_of.query_map(params![part.id], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, f64>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, i64>(4)?,
+                        row.get::<_, i64>(5)?,
+                    ))
+                })?;
+                declared.push(rows.collect::<rusqlite::Result<Vec<_>>>()?);
+            }
+            for (i, part) in parts.iter().enumerate() {
+                for (j, edges) in declared.iter().enumerate() {
+                    if i == j {
+                        continue;
+                    }
+                    // Members are located in their own file, base types in
+                    // the part that lists them
+                    for (target, kind, confidence, target_file, line, column) in edges {
+                        let (file_path, line, column) = match kind.as_str() {
+                            "contains" => (target_file.as_str(), *line, *column),
+                            _ => (
+                                parts[j].file_path.as_str(),
+                                parts[j].start_line as i64,
+                                parts[j].start_column as i64,
+                            ),
+                        };
+                        linked += insert.execute(params![
+                            part.id, target, kind, file_path, line, column, confidence,
+                        ])? as u32;
+                    }
+                }
+            }
+        }
+        Ok(linked)
+    }
+}
+
+/// Whether a type declaration carries the `partial` modifier, among the
+/// words before its `class`, `struct`, `interface` or `record` keyword
+fn is_partial(node: &Node) -> bool {
+    node.signature.as_deref().is_some_and(|signature| {
+        signature
+            .split_whitespace()
+            .take_while(|word| !matches!(*word, "class" | "struct" | "interface" | "record"))
+            .any(|word| word == "partial")
+    })
+}
@@ -0,0 +1,625 @@
+//! Reference resolution
+//!
+//! Binds unresolved references to nodes by ranking every node that carries
+//! the referenced name:
+//! - Candidates owned by the call's receiver type (or module) win outright
+//! - Then same file, same directory (module/package), and symbols the file imports
+//! - Remaining ties are kept as several edges that share the confidence, so
+//!   an edge below [`MIN_CONFIDENCE`] is one of several guesses
+//!
+//! References are kept after resolution. Each resolved edge remembers the
+//! reference it came from, so that changing a file only re-resolves the
+//! references whose candidate set may have changed.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::Database;
+use crate::types::{Edge, EdgeKind, Language, Node, NodeKind};
+
+/// Confidence of a unique match in each locality tier
+const SAME_FILE: f64 = 1.0;
+const SAME_MODULE: f64 = 0.9;
+const IMPORTED: f64 = 0.8;
+const ELSEWHERE: f64 = 0.5;
+/// Lower bound for a unique match on the receiver type
+const RECEIVER: f64 = 0.9;
+
+/// Confidence of the least certain unique match. Calls below it are left out
+/// of call graph traversals, such as impact analysis and call paths.
+pub(crate) const MIN_CONFIDENCE: f64 = ELSEWHERE;
+
+impl Database {
+    /// Resolve pending references by ranking candidate nodes with the same name
+    pub fn resolve_references(&self) -> Result<u32> {
+        let mut refs = self.pending_refs()?;
+        // Imports first, so later references can prefer what their file imports
+        refs.sort_by_key(|(_, r)| r.kind != EdgeKind::Imports);
+
+        let mut resolver = Resolver::new(self);
+        let mut resolved = 0;
+
+        for (ref_id, uref) in refs {
+            // Inheritance refs may name their source as well as their target
+            let sources = match uref.source_name {
+                Some(ref name) => {
+                    resolver.rank(name, |n| n.kind.is_type(), &uref.file_path, None)?
+                }
+                None => vec![(uref.source_node_id, 1.0)],
+            };
+            // A trait impl method overrides the method of that trait or nothing
+            let owner = match uref.kind {
+                EdgeKind::Overrides => uref.receiver.as_deref(),
+                _ => None,
+            };
+            let targets = resolver.rank(
+                &uref.reference_name,
+                |node| {
+                    accepts_target(uref.kind, node.kind)
+                        && owner.is_none_or(|o| owner_name(node) == Some(o))
+                },
+                &uref.file_path,
+                uref.receiver.as_deref(),
+            )?;
+
+            let mut linked = false;
+            for &(source_id, source_confidence) in &sources {
+                for &(target_id, confidence) in &targets {
+                    if target_id == source_id {
+                        continue;
+                    }
+                    // Calling a class or struct constructs it: Python `Foo()`, C++ `Foo(1)`
+                    let kind = match uref.kind {
+                        EdgeKind::Calls
+                            if resolver.is_constructible(&uref.reference_name, target_id)? =>
+                        {
+                            EdgeKind::Instantiates
+                        }
+                        // C# base lists name the base class and interfaces alike
+                        EdgeKind::Extends
+                            if resolver.is_interface(&uref.reference_name, target_id)?
+                                && self.lists_interfaces_as_bases(source_id)? =>
+                        {
+                            EdgeKind::Implements
+                        }
+                        kind => kind,
+                    };
+                    // A method defined outside its type names the type containing it
+                    let (source_id, target_id) = match kind {
+                        EdgeKind::Contains => (target_id, source_id),
+                        _ => (source_id, target_id),
+                    };
+                    let edge = Edge {
+                        id: 0,
+                        source_id,
+                        target_id,
+                        kind,
+                        file_path: Some(uref.file_path.clone()),
+                        line: Some(uref.line),
+                        column: Some(uref.column),
+                        confidence: source_confidence * confidence,
+                    };
+                    self.insert_resolved_edge(&edge, ref_id)?;
+                    linked = true;
+                }
+            }
+            if linked {
+                resolved += 1;
+                if uref.kind == EdgeKind::Imports {
+                    resolver.imports.remove(&uref.file_path);
+                }
+            }
+        }
+
+        // Keep the refs, including those without a target, for later re-resolution
+        self.conn.execute(
+            "UPDATE unresolved_refs SET resolved = 1 WHERE resolved = 0",
+            [],
+        )?;
+
+        Ok(resolved)
+    }
+
+    /// Queue references to (or from, by source name) any of `names` for
+    /// resolution again, dropping the edges they produced. Call this for
+    /// the symbols of a file that is added, changed or removed.
+    pub fn invalidate_refs_named(&self, names: &[String]) -> Result<()> {
+        let mut delete_edges = self.conn.prepare_cached(
+            "DELETE FROM edges WHERE ref_id IN (
+                SELECT id FROM unresolved_refs
+                WHERE resolved = 1 AND (reference_name = ?1 OR source_name = ?1))",
+        )?;
+        let mut requeue = self.conn.prepare_cached(
+            "UPDATE unresolved_refs SET resolved = 0
+             WHERE resolved = 1 AND (reference_name = ?1 OR source_name = ?1)",
+        )?;
+
+        let mut seen = HashSet::new();
+        for name in names {
+            if seen.insert(name) {
+                delete_edges.execute(params![name])?;
+                requeue.execute(params![name])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the node `id` is a C# class, struct or record, whose base list
+    /// mixes its base class with the interfaces it implements
+    fn lists_interfaces_as_bases(&self, id: i64) -> Result<bool> {
+        Ok(self
+            .get_node(id)?
+            .is_some_and(|n| n.language == Language::CSharp && n.kind != NodeKind::Interface))
+    }
+
+    fn insert_resolved_edge(&self, edge: &Edge, ref_id: i64) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO edges (source_id, target_id, kind, file_path, line, column, confidence, ref_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        stmt.execute(params![
+            edge.source_id,
+            edge.target_id,
+            edge.kind.as_str(),
+            edge.file_path,
+            edge.line.map(|l| l as i64),
+            edge.column.map(|c| c as i64),
+            edge.confidence,
+            ref_id,
+        ])?;
+        Ok(())
+    }
+}
+
+/// Whether a node of `kind` can be the target of an edge of `edge_kind`
+fn accepts_target(edge_kind: EdgeKind, kind: NodeKind) -> bool {
+    match edge_kind {
+        EdgeKind::Extends
+        | EdgeKind::Implements
+        | EdgeKind::TypeOf
+        | EdgeKind::Returns
+        | EdgeKind::Instantiates
+        | EdgeKind::Contains => kind.is_type(),
+        // Type names, and fields or properties read through `obj.member`
+        EdgeKind::References => {
+            kind.is_type()
+                || matches!(
+                    kind,
+                    NodeKind::Field | NodeKind::Property | NodeKind::EnumMember
+                )
+        }
+        EdgeKind::Calls => matches!(
+            kind,
+            NodeKind::Function | NodeKind::Method | NodeKind::Class | NodeKind::Struct
+        ),
+        EdgeKind::Overrides => matches!(kind, NodeKind::Function | NodeKind::Method),
+        // Decorator functions and classes, Java annotation types
+        EdgeKind::Decorates => matches!(
+            kind,
+            NodeKind::Function | NodeKind::Method | NodeKind::Class | NodeKind::Interface
+        ),
+        _ => !matches!(kind, NodeKind::File | NodeKind::Import),
+    }
+}
+
+/// Per-run caches for ranking candidates
+struct Resolver<'a> {
+    db: &'a Database,
+    by_name: HashMap<String, Vec<Node>>,
+    imports: HashMap<String, HashSet<i64>>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(db: &'a Database) -> Self {
+        Self {
+            db,
+            by_name: HashMap::new(),
+            imports: HashMap::new(),
+        }
+    }
+
+    /// Best-ranked nodes named `name` for a reference made from `file_path`,
+    /// with the confidence of each
+    fn rank(
+        &mut self,
+        name: &str,
+        accepts: impl Fn(&Node) -> bool,
+        file_path: &str,
+        receiver: Option<&str>,
+    ) -> Result<Vec<(i64, f64)>> {
+        let mut candidates: Vec<Node> = self
+            .nodes_named(name)?
+            .iter()
+            .filter(|n| accepts(n))
+            .cloned()
+            .collect();
+
+        let mut receiver_matched = false;
+        if let Some(receiver) = receiver {
+            let owned: Vec<Node> = candidates
+                .iter()
+                .filter(|n| matches_receiver(n, receiver))
+                .cloned()
+                .collect();
+            if !owned.is_empty() {
+                candidates = owned;
+                receiver_matched = true;
+            } else if is_type_name(receiver) {
+                // `Other::new()` must not bind to a `new` owned by some unrelated type
+                candidates.retain(|n| owner_name(n).is_none_or(|o| !is_type_name(o)));
+            }
+        }
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dir = parent_dir(file_path);
+        let mut tiers: [Vec<&Node>; 4] = Default::default();
+        for node in &candidates {
+            let tier = if node.file_path == file_path {
+                0
+            } else if parent_dir(&node.file_path) == dir {
+                1
+            } else if self.imported_by(file_path)?.contains(&node.id) {
+                2
+            } else {
+                3
+            };
+            tiers[tier].push(node);
+        }
+
+        let weights = [SAME_FILE, SAME_MODULE, IMPORTED, ELSEWHERE];
+        let Some((tier, mut nodes)) = tiers.into_iter().enumerate().find(|(_, t)| !t.is_empty())
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut weight = weights[tier];
+        if receiver_matched {
+            weight = weight.max(RECEIVER);
+        }
+        let confidence = weight / nodes.len() as f64;
+        nodes.sort_by(|a, b| {
+            (&a.file_path, a.start_line, a.id).cmp(&(&b.file_path, b.start_line, b.id))
+        });
+        Ok(nodes.into_iter().map(|n| (n.id, confidence)).collect())
+    }
+
+    /// Whether the node `id` named `name` is a class or struct
+    fn is_constructible(&mut self, name: &str, id: i64) -> Result<bool> {
+        Ok(self
+            .nodes_named(name)?
+            .iter()
+            .any(|n| n.id == id && matches!(n.kind, NodeKind::Class | NodeKind::Struct)))
+    }
+
+    /// Whether the node `id` named `name` is an interface
+    fn is_interface(&mut self, name: &str, id: i64) -> Result<bool> {
+        Ok(self
+            .nodes_named(name)?
+            .iter()
+            .any(|n| n.id == id && n.kind == NodeKind::Interface))
+    }
+
+    fn nodes_named(&mut self, name: &str) -> Result<&Vec<Node>> {
+        if !self.by_name.contains_key(name) {
+            let mut stmt = self
+                .db
+                .conn
+                .prepare_cached("SELECT * FROM nodes WHERE name = ?1 ORDER BY id")?;
+            let rows = stmt.query_map(params![name], Database::row_to_node)?;
+            let mut nodes = Vec::new();
+            for row in rows {
+                nodes.push(row?);
+            }
+            self.by_name.insert(name.to_string(), nodes);
+        }
+        Ok(&self.by_name[name])
+    }
+
+    /// Ids of the nodes that `file_path` imports
+    fn imported_by(&mut self, file_path: &str) -> Result<&HashSet<i64>> {
+        if !self.imports.contains_key(file_path) {
+            let mut stmt = self.db.conn.prepare_cached(
+                "SELECT target_id FROM edges WHERE kind = 'imports' AND file_path = ?1",
+            )?;
+            let rows = stmt.query_map(params![file_path], |row| row.get(0))?;
+            let mut ids = HashSet::new();
+            for row in rows {
+                ids.insert(row?);
+            }
+            self.imports.insert(file_path.to_string(), ids);
+        }
+        Ok(&self.imports[file_path])
+    }
+}
+
+/// Enclosing symbol name taken from the qualified name (`Foo::bar` -> `Foo`)
+fn owner_name(node: &Node) -> Option<&str> {
+    let qualified = node.qualified_name.as_deref()?;
+    let mut parts = qualified.rsplit("::");
+    parts.next();
+    parts.next()
+}
+
+/// Whether a candidate belongs to the receiver, either as its owning type or
+/// as the module (file or directory) it lives in
+fn matches_receiver(node: &Node, receiver: &str) -> bool {
+    let receiver = receiver
+        .rsplit(['.', ':', '/'])
+        .find(|s| !s.is_empty())
+        .unwrap_or(receiver);
+    if owner_name(node) == Some(receiver) {
+        return true;
+    }
+    let path = Path::new(&node.file_path);
+    let stem = path.file_stem().and_then(|s| s.to_str());
+    let dir = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str());
+    stem == Some(receiver) || dir == Some(receiver)
+}
+
+/// Capitalized qualifiers name types; lowercase ones are modules or variables
+fn is_type_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_uppercase())
+}
+
+fn parent_dir(path: &str) -> &Path {
+    Path::new(path).parent().unwrap_or(Path::new(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileRecord, Language, UnresolvedReference, Visibility};
+
+    fn add_file(db: &Database, path: &str) {
+        db.insert_or_update_file(&FileRecord {
+            path: path.to_string(),
+            content_hash: "hash".to_string(),
+            language: Language::Rust,
+            size: 0,
+            modified_at: 0,
+            indexed_at: 0,
+            node_count: 0,
+        })
+        .unwrap();
+    }
+
+    fn add_node(db: &Database, qualified_name: &str, kind: NodeKind, path: &str) -> i64 {
+        let name = qualified_name.rsplit("::").next().unwrap();
+        db.insert_node(&Node {
+            id: 0,
+            kind,
+            name: name.to_string(),
+            qualified_name: Some(qualified_name.to_string()),
+            file_path: path.to_string(),
+            start_line: 1,
+            end_line: 1,
+            start_column: 0,
+            end_column: 0,
+            signature: None,
+            signature_parts: None,
+            arity: None,
+            visibility: Visibility::Public,
+            docstring: None,
+            doc_tags: None,
+            is_async: false,
+            is_static: false,
+            is_exported: false,
+            has_parse_errors: false,
+            language: Language::Rust,
+            decorators: Vec::new(),
+        })
+        .unwrap()
+    }
+
+    fn add_ref(db: &Database, source: i64, name: &str, path: &str, receiver: Option<&str>) {
+        db.insert_unresolved_ref(&UnresolvedReference {
+            source_node_id: source,
+            reference_name: name.to_string(),
+            kind: EdgeKind::Calls,
+            file_path: path.to_string(),
+            line: 1,
+            column: 0,
+            source_name: None,
+            receiver: receiver.map(str::to_string),
+        })
+        .unwrap();
+    }
+
+    fn targets(db: &Database, source: i64) -> Vec<(i64, f64)> {
+        db.get_outgoing_edges(source)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.target_id, e.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn test_prefers_same_file() {
+        let db = Database::in_memory().unwrap();
+        for path in ["src/a.rs", "src/b.rs", "lib/c.rs"] {
+            add_file(&db, path);
+        }
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let _other = add_node(&db, "build", NodeKind::Function, "lib/c.rs");
+        let _sibling = add_node(&db, "build", NodeKind::Function, "src/b.rs");
+        let local = add_node(&db, "build", NodeKind::Function, "src/a.rs");
+        add_ref(&db, caller, "build", "src/a.rs", None);
+
+        db.resolve_references().unwrap();
+        assert_eq!(targets(&db, caller), vec![(local, 1.0)]);
+    }
+
+    #[test]
+    fn test_prefers_same_module() {
+        let db = Database::in_memory().unwrap();
+        for path in ["src/a.rs", "src/b.rs", "lib/c.rs"] {
+            add_file(&db, path);
+        }
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let _other = add_node(&db, "build", NodeKind::Function, "lib/c.rs");
+        let sibling = add_node(&db, "build", NodeKind::Function, "src/b.rs");
+        add_ref(&db, caller, "build", "src/a.rs", None);
+
+        db.resolve_references().unwrap();
+        assert_eq!(targets(&db, caller), vec![(sibling, SAME_MODULE)]);
+    }
+
+    #[test]
+    fn test_prefers_imported() {
+        let db = Database::in_memory().unwrap();
+        for path in ["src/a.rs", "x/b.rs", "y/c.rs"] {
+            add_file(&db, path);
+        }
+        let file = add_node(&db, "src/a.rs", NodeKind::File, "src/a.rs");
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let _other = add_node(&db, "build", NodeKind::Function, "x/b.rs");
+        let imported = add_node(&db, "build", NodeKind::Function, "y/c.rs");
+        db.insert_unresolved_ref(&UnresolvedReference {
+            source_node_id: file,
+            reference_name: "build".to_string(),
+            kind: EdgeKind::Imports,
+            file_path: "src/a.rs".to_string(),
+            line: 1,
+            column: 0,
+            source_name: None,
+            receiver: Some("crate::c".to_string()),
+        })
+        .unwrap();
+        add_ref(&db, caller, "build", "src/a.rs", None);
+
+        db.resolve_references().unwrap();
+        assert_eq!(targets(&db, file), vec![(imported, RECEIVER)]);
+        assert_eq!(targets(&db, caller), vec![(imported, IMPORTED)]);
+    }
+
+    #[test]
+    fn test_receiver_type() {
+        let db = Database::in_memory().unwrap();
+        add_file(&db, "src/a.rs");
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let _order = add_node(&db, "Order::new", NodeKind::Method, "src/a.rs");
+        let user = add_node(&db, "User::new", NodeKind::Method, "src/a.rs");
+        add_ref(&db, caller, "new", "src/a.rs", Some("User"));
+        add_ref(&db, caller, "new", "src/a.rs", Some("String"));
+
+        db.resolve_references().unwrap();
+        // `String::new()` is external and must not bind to either method
+        assert_eq!(targets(&db, caller), vec![(user, 1.0)]);
+    }
+
+    #[test]
+    fn test_ambiguous_edges_share_confidence() {
+        let db = Database::in_memory().unwrap();
+        for path in ["src/a.rs", "x/b.rs", "y/c.rs"] {
+            add_file(&db, path);
+        }
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let first = add_node(&db, "handle", NodeKind::Function, "x/b.rs");
+        let second = add_node(&db, "handle", NodeKind::Function, "y/c.rs");
+        add_ref(&db, caller, "handle", "src/a.rs", None);
+
+        db.resolve_references().unwrap();
+        let confidence = ELSEWHERE / 2.0;
+        assert_eq!(
+            targets(&db, caller),
+            vec![(first, confidence), (second, confidence)]
+        );
+
+        // Graph traversals skip the guesses, weighted listings show them
+        assert!(db.get_callers(first, 10).unwrap().is_empty());
+        assert!(db.get_callees(caller, 10).unwrap().is_empty());
+        let callers = db.get_weighted_callers(first, 10).unwrap();
+        assert_eq!(callers.len(), 1);
+        assert_eq!((callers[0].0.id, callers[0].1), (caller, confidence));
+        let callees: Vec<_> = db
+            .get_weighted_callees(caller, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(n, c)| (n.id, c))
+            .collect();
+        assert_eq!(callees, vec![(first, confidence), (second, confidence)]);
+    }
+
+    #[test]
+    fn test_widely_ambiguous_refs_link_every_candidate() {
+        let db = Database::in_memory().unwrap();
+        add_file(&db, "src/a.rs");
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        let mut handlers = Vec::new();
+        for i in (0..8).rev() {
+            let path = format!("m{}/h.rs", i);
+            add_file(&db, &path);
+            handlers.push(add_node(&db, "handle", NodeKind::Function, &path));
+        }
+        handlers.reverse();
+        add_ref(&db, caller, "handle", "src/a.rs", None);
+
+        db.resolve_references().unwrap();
+        let confidence = ELSEWHERE / 8.0;
+        let expected: Vec<_> = handlers.iter().map(|&id| (id, confidence)).collect();
+        assert_eq!(targets(&db, caller), expected);
+    }
+
+    #[test]
+    fn test_calls_skip_non_callables() {
+        let db = Database::in_memory().unwrap();
+        add_file(&db, "src/a.rs");
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        add_node(&db, "config", NodeKind::Module, "src/a.rs");
+        add_ref(&db, caller, "config", "src/a.rs", None);
+
+        assert_eq!(db.resolve_references().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_calls_to_classes_are_instantiations() {
+        let db = Database::in_memory().unwrap();
+        add_file(&db, "app.py");
+        let caller = add_node(&db, "main", NodeKind::Function, "app.py");
+        let class = add_node(&db, "Cart", NodeKind::Class, "app.py");
+        let function = add_node(&db, "checkout", NodeKind::Function, "app.py");
+        add_ref(&db, caller, "Cart", "app.py", None);
+        add_ref(&db, caller, "checkout", "app.py", None);
+        db.resolve_references().unwrap();
+
+        let kinds: Vec<(i64, EdgeKind)> = db
+            .get_outgoing_edges(caller)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.target_id, e.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![(class, EdgeKind::Instantiates), (function, EdgeKind::Calls)]
+        );
+    }
+
+    #[test]
+    fn test_refs_requeued_when_target_file_changes() {
+        let db = Database::in_memory().unwrap();
+        add_file(&db, "src/a.rs");
+        add_file(&db, "src/b.rs");
+        let caller = add_node(&db, "caller", NodeKind::Function, "src/a.rs");
+        add_node(&db, "build", NodeKind::Function, "src/b.rs");
+        add_ref(&db, caller, "build", "src/a.rs", None);
+        db.resolve_references().unwrap();
+        assert!(db.get_unresolved_refs().unwrap().is_empty());
+
+        // Replacing b.rs drops the edge but keeps the caller's reference
+        db.delete_file("src/b.rs").unwrap();
+        assert!(targets(&db, caller).is_empty());
+        assert_eq!(db.get_unresolved_refs().unwrap().len(), 1);
+
+        add_file(&db, "src/b.rs");
+        let rebuilt = add_node(&db, "build", NodeKind::Function, "src/b.rs");
+        db.resolve_references().unwrap();
+        assert_eq!(targets(&db, caller), vec![(rebuilt, SAME_MODULE)]);
+    }
+}
@@ -1,5 +1,21 @@
 //! Database schema definition
 
+/// Schema version stored in `PRAGMA user_version`. Bump whenever the schema
+/// changes; databases with a different version are rebuilt from scratch.
+pub const SCHEMA_VERSION: i32 = 11;
+
+/// Drops every table so an outdated index can be recreated
+pub const DROP_ALL: &str = r#"
+DROP TABLE IF EXISTS nodes_fts;
+DROP TABLE IF EXISTS meta;
+DROP TABLE IF EXISTS diagnostics;
+DROP TABLE IF EXISTS imports;
+DROP TABLE IF EXISTS unresolved_refs;
+DROP TABLE IF EXISTS edges;
+DROP TABLE IF EXISTS nodes;
+DROP TABLE IF EXISTS files;
+"#;
+
 pub const SCHEMA: &str = r#"
 -- Files table: tracks indexed files
 CREATE TABLE IF NOT EXISTS files (
@@ -30,6 +46,11 @@ CREATE TABLE IF NOT EXISTS nodes (
     is_static INTEGER NOT NULL DEFAULT 0,
     is_exported INTEGER NOT NULL DEFAULT 0,
     language TEXT NOT NULL,
+    decorators TEXT, -- JSON array of decorators, NULL when there are none
+    arity INTEGER, -- parameter count of functions and methods
+    doc_tags TEXT, -- JSON object of documented params, returns, throws and examples
+    signature_parts TEXT, -- JSON object of parameters, return type, type parameters and receiver
+    has_parse_errors INTEGER NOT NULL DEFAULT 0, -- the symbol's source has syntax errors
     FOREIGN KEY (file_path) REFERENCES files(path)
 );
 
@@ -42,11 +63,14 @@ CREATE TABLE IF NOT EXISTS edges (
     file_path TEXT,
     line INTEGER,
     column INTEGER,
+    confidence REAL NOT NULL DEFAULT 1.0,
+    ref_id INTEGER, -- unresolved_refs row this edge was resolved from
     FOREIGN KEY (source_id) REFERENCES nodes(id),
     FOREIGN KEY (target_id) REFERENCES nodes(id)
 );
 
--- Unresolved references: references that couldn't be resolved during extraction
+-- Unresolved references: by-name references found during extraction. They are kept
+-- after resolution so they can be re-resolved when the files they point into change.
 CREATE TABLE IF NOT EXISTS unresolved_refs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     source_node_id INTEGER NOT NULL,
@@ -55,9 +79,40 @@ CREATE TABLE IF NOT EXISTS unresolved_refs (
     file_path TEXT NOT NULL,
     line INTEGER NOT NULL,
     column INTEGER NOT NULL,
+    source_name TEXT,
+    receiver TEXT,
+    resolved INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (source_node_id) REFERENCES nodes(id)
+);
+
+-- Module paths named by import statements. They are resolved to the files
+-- they refer to after every indexing run, as `imports` edges to file nodes.
+CREATE TABLE IF NOT EXISTS imports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_node_id INTEGER NOT NULL,
+    module TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    language TEXT NOT NULL,
     FOREIGN KEY (source_node_id) REFERENCES nodes(id)
 );
 
+-- Syntax errors found while parsing a file, replaced when it is reindexed
+CREATE TABLE IF NOT EXISTS diagnostics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    file_path TEXT NOT NULL,
+    line INTEGER,
+    column INTEGER,
+    message TEXT NOT NULL,
+    FOREIGN KEY (file_path) REFERENCES files(path)
+);
+
+-- Settings the index was built with, e.g. the extraction queries
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
 -- Indexes for efficient queries
 CREATE INDEX IF NOT EXISTS idx_nodes_name ON nodes(name);
 CREATE INDEX IF NOT EXISTS idx_nodes_name_lower ON nodes(LOWER(name));
@@ -68,9 +123,27 @@ CREATE INDEX IF NOT EXISTS idx_nodes_qualified_name ON nodes(qualified_name);
 CREATE INDEX IF NOT EXISTS idx_edges_source ON edges(source_id);
 CREATE INDEX IF NOT EXISTS idx_edges_target ON edges(target_id);
 CREATE INDEX IF NOT EXISTS idx_edges_kind ON edges(kind);
+CREATE INDEX IF NOT EXISTS idx_edges_file_path ON edges(file_path);
+CREATE INDEX IF NOT EXISTS idx_edges_ref ON edges(ref_id);
 
 CREATE INDEX IF NOT EXISTS idx_unresolved_name ON unresolved_refs(reference_name);
+CREATE INDEX IF NOT EXISTS idx_unresolved_source_name ON unresolved_refs(source_name);
+CREATE INDEX IF NOT EXISTS idx_unresolved_file ON unresolved_refs(file_path);
+CREATE INDEX IF NOT EXISTS idx_unresolved_resolved ON unresolved_refs(resolved);
+
+CREATE INDEX IF NOT EXISTS idx_imports_file ON imports(file_path);
+
+CREATE INDEX IF NOT EXISTS idx_diagnostics_file ON diagnostics(file_path);
+
+-- Full-text search over symbols, keyed by node id. `tokens` holds the
+-- camelCase/snake_case parts of the name and their joined suffixes, so that
+-- `userrepo` finds `UserRepository`. Rows are written with their node.
+CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(
+    name, qualified_name, tokens, signature, docstring,
+    tokenize = 'unicode61'
+);
 
--- Full-text search for symbol names (optional, can be enabled later)
--- CREATE VIRTUAL TABLE IF NOT EXISTS nodes_fts USING fts5(name, qualified_name, docstring, content=nodes, content_rowid=id);
+CREATE TRIGGER IF NOT EXISTS nodes_fts_delete AFTER DELETE ON nodes BEGIN
+    DELETE FROM nodes_fts WHERE rowid = old.id;
+END;
 "#;
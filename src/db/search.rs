@@ -0,0 +1,817 @@
+//! Symbol search
+//!
+//! Candidates come from the `nodes_fts` full-text index, which matches each
+//! query term as a prefix of a name, qualified name, signature, docstring or
+//! of the camelCase/snake_case parts of a name. When that finds fewer symbols
+//! than requested, names within a small edit distance of the query are added
+//! so that typos still find something.
+//!
+//! Candidates are ranked by how well their name matches the query, then by
+//! symbol kind, visibility and how often the symbol is referenced.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use globset::GlobMatcher;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter};
+use serde::Serialize;
+
+use super::Database;
+use crate::types::{Language, Node, NodeKind, Visibility};
+
+/// Full-text candidates fetched per requested result
+const CANDIDATES_PER_RESULT: u32 = 10;
+/// Further factor when candidates are filtered by path or signature afterwards
+const POST_FILTER_FACTOR: u32 = 10;
+/// Queries shorter than this are not matched fuzzily
+const MIN_FUZZY_LEN: usize = 4;
+/// Incoming references at which the centrality bonus is maxed out
+const CENTRALITY_SATURATION: f64 = 50.0;
+
+/// Filters and limits for [`Database::search_symbols`]
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    pub kind: Option<NodeKind>,
+    pub language: Option<Language>,
+    /// Only symbols whose file path matches this glob
+    pub path: Option<GlobMatcher>,
+    /// Only functions and methods with a parameter of this type (see [`type_matches`])
+    pub param_type: Option<String>,
+    /// Only functions and methods whose return type matches (see [`type_matches`])
+    pub returns: Option<String>,
+    pub limit: u32,
+}
+
+impl SearchOptions {
+    /// Whether results are filtered by their signature
+    fn filters_signature(&self) -> bool {
+        self.param_type.is_some() || self.returns.is_some()
+    }
+}
+
+/// How a search result matched the query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// The name is the query, ignoring case and separators
+    Exact,
+    /// The name starts with the query
+    Prefix,
+    /// A later camelCase/snake_case part of the name starts with the query
+    SubToken,
+    /// The qualified name, signature or docstring matches
+    Text,
+    /// The name is within a small edit distance of the query
+    Fuzzy,
+    /// There was no query; the symbol only matches the filters
+    Filter,
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub node: Node,
+    pub score: f64,
+    #[serde(rename = "match")]
+    pub match_kind: MatchKind,
+}
+
+impl Database {
+    /// Search symbols by name and text, best matches first. The query may be
+    /// empty when results are filtered by signature.
+    pub fn search_symbols(&self, query: &str, options: &SearchOptions) -> Result<Vec<SearchHit>> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|term| identifier_parts(term).concat())
+            .filter(|term| !term.is_empty())
+            .collect();
+        if options.limit == 0 || terms.is_empty() && !options.filters_signature() {
+            return Ok(Vec::new());
+        }
+        let needle = terms.concat();
+
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        if terms.is_empty() {
+            for node in self.signature_candidates(options)? {
+                matches.push((node, MatchKind::Filter, 0.0));
+            }
+        }
+        for (node, rank) in self.full_text_candidates(&terms, options)? {
+            if seen.insert(node.id) {
+                let (match_kind, score) = text_score(&needle, &node.name, rank);
+                matches.push((node, match_kind, score));
+            }
+        }
+
+        if matches.len() < options.limit as usize && needle.chars().count() >= MIN_FUZZY_LEN {
+            for (node, distance) in self.fuzzy_candidates(&needle, options)? {
+                if seen.insert(node.id) {
+                    matches.push((node, MatchKind::Fuzzy, 0.5 - 0.1 * distance as f64));
+                }
+            }
+        }
+
+        let mut hits = Vec::with_capacity(matches.len());
+        for (node, match_kind, text) in matches {
+            let score = text
+                + kind_weight(node.kind)
+                + visibility_weight(&node)
+                + self.centrality(node.id)?;
+            hits.push(SearchHit {
+                node,
+                score: (score * 1000.0).round() / 1000.0,
+                match_kind,
+            });
+        }
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.node.name.len().cmp(&b.node.name.len()))
+                .then_with(|| a.node.name.cmp(&b.node.name))
+                .then_with(|| a.node.id.cmp(&b.node.id))
+        });
+        hits.truncate(options.limit as usize);
+        Ok(hits)
+    }
+
+    /// Nodes whose indexed text matches every term as a prefix, with their
+    /// bm25 rank (lower is better)
+    fn full_text_candidates(
+        &self,
+        terms: &[String],
+        options: &SearchOptions,
+    ) -> Result<Vec<(Node, f64)>> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let expression = terms
+            .iter()
+            .map(|term| format!("\"{}\"*", term))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+
+        let mut sql = String::from(
+            "SELECT n.*, bm25(nodes_fts, 10.0, 5.0, 4.0, 1.0, 1.0) AS text_rank
+             FROM nodes_fts JOIN nodes n ON n.id = nodes_fts.rowid
+             WHERE nodes_fts MATCH ?",
+        );
+        let mut values = vec![Value::Text(expression)];
+        push_filters(&mut sql, &mut values, options);
+
+        let mut cap = options.limit.saturating_mul(CANDIDATES_PER_RESULT);
+        if options.path.is_some() || options.filters_signature() {
+            cap = cap.saturating_mul(POST_FILTER_FACTOR);
+        }
+        sql.push_str(" ORDER BY text_rank LIMIT ?");
+        values.push(Value::Integer(cap as i64));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Ok((Self::row_to_node(row)?, row.get("text_rank")?))
+        })?;
+
+        let mut candidates = Vec::new();
+        for row in rows {
+            let (node, rank) = row?;
+            if matches_filters(&node, options) {
+                candidates.push((node, rank));
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Nodes whose name is within a small edit distance of the query, with
+    /// that distance
+    fn fuzzy_candidates(
+        &self,
+        needle: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<(Node, usize)>> {
+        let max_distance = max_distance(needle.chars().count());
+
+        let mut sql = String::from("SELECT DISTINCT n.name FROM nodes n WHERE 1 = 1");
+        let mut values = Vec::new();
+        push_filters(&mut sql, &mut values, options);
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let names = stmt.query_map(params_from_iter(values), |row| row.get::<_, String>(0))?;
+
+        let mut close = Vec::new();
+        for name in names {
+            let name = name?;
+            let distance = fuzzy_distance(needle, &name);
+            if distance <= max_distance {
+                close.push((distance, name));
+            }
+        }
+        close.sort();
+        close.truncate(options.limit as usize);
+
+        let mut candidates = Vec::new();
+        for (distance, name) in close {
+            for node in self.find_nodes_by_name(&name)? {
+                let wanted = options.kind.is_none_or(|k| node.kind == k)
+                    && options.language.is_none_or(|l| node.language == l)
+                    && matches_filters(&node, options);
+                if wanted {
+                    candidates.push((node, distance));
+                }
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Functions and methods whose signature matches the signature filters
+    fn signature_candidates(&self, options: &SearchOptions) -> Result<Vec<Node>> {
+        let mut sql = String::from("SELECT n.* FROM nodes n WHERE 1 = 1");
+        let mut values = Vec::new();
+        push_filters(&mut sql, &mut values, options);
+        sql.push_str(" ORDER BY n.id");
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), Self::row_to_node)?;
+        let mut candidates = Vec::new();
+        for row in rows {
+            let node = row?;
+            if matches_filters(&node, options) {
+                candidates.push(node);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Ranking bonus for symbols that many other symbols refer to
+    fn centrality(&self, id: i64) -> Result<f64> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT COUNT(*) FROM edges WHERE target_id = ?1 AND kind != 'contains'",
+        )?;
+        let incoming: i64 = stmt.query_row(params![id], |row| row.get(0))?;
+        let saturation = (1.0 + CENTRALITY_SATURATION).ln();
+        Ok(0.15 * ((1.0 + incoming as f64).ln() / saturation).min(1.0))
+    }
+
+    /// Add a node's text to the search index
+    pub(super) fn index_search_text(&self, id: i64, node: &Node) -> Result<()> {
+        let mut stmt = self.conn.prepare_cached(
+            "INSERT INTO nodes_fts (rowid, name, qualified_name, tokens, signature, docstring)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        stmt.execute(params![
+            id,
+            node.name,
+            node.qualified_name,
+            search_tokens(&node.name),
+            node.signature,
+            node.docstring
+        ])?;
+        Ok(())
+    }
+
+    /// Refresh the search text of a file's nodes after they were updated in place
+    pub(super) fn reindex_search_text(&self, file_path: &str) -> Result<()> {
+        for node in self.get_nodes_by_file(file_path)? {
+            self.conn
+                .execute("DELETE FROM nodes_fts WHERE rowid = ?1", params![node.id])?;
+            self.index_search_text(node.id, &node)?;
+        }
+        Ok(())
+    }
+}
+
+/// Append the kind and language filters as `AND` clauses on `n`
+fn push_filters(sql: &mut String, values: &mut Vec<Value>, options: &SearchOptions) {
+    if let Some(kind) = options.kind {
+        sql.push_str(" AND n.kind = ?");
+        values.push(Value::Text(kind.as_str().to_string()));
+    }
+    if let Some(language) = options.language {
+        sql.push_str(" AND n.language = ?");
+        values.push(Value::Text(language.as_str().to_string()));
+    }
+    if options.filters_signature() {
+        sql.push_str(" AND n.signature_parts IS NOT NULL");
+    }
+}
+
+/// Whether a node passes the filters that are checked after the query: the
+/// path glob and the signature filters
+fn matches_filters(node: &Node, options: &SearchOptions) -> bool {
+    let path = options
+        .path
+        .as_ref()
+        .is_none_or(|glob| glob.is_match(&node.file_path));
+    let parts = node.signature_parts.as_ref();
+    let param_type = options.param_type.as_deref().is_none_or(|pattern| {
+        parts.is_some_and(|parts| {
+            parts
+                .params
+                .iter()
+                .filter_map(|p| p.type_name.as_deref())
+                .any(|ty| type_matches(pattern, ty))
+        })
+    });
+    let returns = options.returns.as_deref().is_none_or(|pattern| {
+        parts
+            .and_then(|parts| parts.returns.as_deref())
+            .is_some_and(|ty| type_matches(pattern, ty))
+    });
+    path && param_type && returns
+}
+
+/// Lowercase camelCase, PascalCase, snake_case and kebab-case parts of an
+/// identifier: `HTTPServerConfig` gives `http`, `server`, `config`
+pub(crate) fn identifier_parts(identifier: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    for word in identifier.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = word.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            let boundary = (cur.is_uppercase() && (prev.is_lowercase() || prev.is_numeric()))
+                || (cur.is_uppercase() && prev.is_uppercase() && next_is_lower);
+            if boundary {
+                parts.push(chars[start..i].iter().collect::<String>().to_lowercase());
+                start = i;
+            }
+        }
+        if start < chars.len() {
+            parts.push(chars[start..].iter().collect::<String>().to_lowercase());
+        }
+    }
+    parts
+}
+
+/// Text indexed for a name's sub-token matches: its parts, and each run of
+/// parts to the end of the name joined together
+fn search_tokens(name: &str) -> String {
+    let parts = identifier_parts(name);
+    let mut tokens = parts.clone();
+    if parts.len() > 1 {
+        tokens.extend((0..parts.len() - 1).map(|i| parts[i..].concat()));
+    }
+    tokens.join(" ")
+}
+
+/// How well a full-text candidate's name matches the normalized query
+fn text_score(needle: &str, name: &str, rank: f64) -> (MatchKind, f64) {
+    let parts = identifier_parts(name);
+    let joined = parts.concat();
+    let coverage = needle.len() as f64 / joined.len().max(1) as f64;
+
+    if joined == needle {
+        (MatchKind::Exact, 1.0)
+    } else if joined.starts_with(needle) {
+        (MatchKind::Prefix, 0.7 + 0.2 * coverage)
+    } else if (1..parts.len()).any(|i| parts[i..].concat().starts_with(needle)) {
+        (MatchKind::SubToken, 0.5 + 0.2 * coverage)
+    } else {
+        // bm25 ranks are negative, lower is better
+        (MatchKind::Text, 0.2 + 0.01 * (-rank).clamp(0.0, 10.0))
+    }
+}
+
+fn kind_weight(kind: NodeKind) -> f64 {
+    match kind {
+        k if k.is_type() => 0.15,
+        NodeKind::Function | NodeKind::Method => 0.12,
+        NodeKind::Module | NodeKind::Namespace | NodeKind::Route | NodeKind::Component => 0.1,
+        NodeKind::Constant | NodeKind::Property | NodeKind::Field | NodeKind::EnumMember => 0.05,
+        NodeKind::File => -0.1,
+        _ => 0.0,
+    }
+}
+
+fn visibility_weight(node: &Node) -> f64 {
+    if node.is_exported || node.visibility == Visibility::Public {
+        0.05
+    } else if node.visibility == Visibility::Private {
+        -0.05
+    } else {
+        0.0
+    }
+}
+
+/// Typos tolerated in a query of `len` characters
+fn max_distance(len: usize) -> usize {
+    if len < 6 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Smallest edit distance between the query and the name, its start, or one
+/// of its longer parts
+fn fuzzy_distance(needle: &str, name: &str) -> usize {
+    let needle: Vec<char> = needle.chars().collect();
+    let parts = identifier_parts(name);
+    let joined: Vec<char> = parts.concat().chars().collect();
+
+    let mut best = edit_distance(&needle, &joined);
+    if joined.len() > needle.len() {
+        best = best.min(edit_distance(&needle, &joined[..needle.len()]));
+    }
+    for part in parts.iter().filter(|p| p.chars().count() >= MIN_FUZZY_LEN) {
+        let part: Vec<char> = part.chars().collect();
+        best = best.min(edit_distance(&needle, &part));
+    }
+    best
+}
+
+/// Levenshtein distance that also counts swapping two adjacent characters as
+/// one edit
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Whether `ty` is, or contains as a type argument, element or referent, a
+/// type matching `pattern`. `_` in the pattern stands for any one type, and
+/// whitespace, lifetimes and qualifiers such as `dyn` and `const` are
+/// ignored: `Result<_, AuthError>` matches `Result<User, AuthError>`,
+/// `Connection` matches `&'a mut Connection`, and `char` matches `const char *`.
+pub fn type_matches(pattern: &str, ty: &str) -> bool {
+    let pattern = type_tokens(pattern);
+    !pattern.is_empty() && contains_match(&pattern, &type_tokens(ty))
+}
+
+/// Words that qualify a type without naming one
+const TYPE_QUALIFIERS: &[&str] = &[
+    "dyn", "impl", "const", "mut", "volatile", "struct", "enum", "union",
+];
+
+/// Split a type into names and punctuation, without lifetimes and
+/// qualifiers: `&'a mut dyn Read` gives `&mut`, `Read`
+fn type_tokens(ty: &str) -> Vec<&str> {
+    let is_name = |c: char| c.is_alphanumeric() || matches!(c, '_' | '$' | '\'');
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut rest = ty.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if is_name(c) {
+            // Paths such as `std::io::Error` and `java.util.List` are one name
+            let mut len = c.len_utf8();
+            for (i, next) in rest.char_indices().skip(1) {
+                let after = rest[i..].trim_start_matches([':', '.']);
+                let path = (rest[i..].starts_with("::") || next == '.')
+                    && after.starts_with(is_name)
+                    || next == ':' && rest[..i].ends_with(':');
+                if !is_name(next) && !path {
+                    break;
+                }
+                len = i + next.len_utf8();
+            }
+            len
+        } else if ["...", "->", "=>"].iter().any(|p| rest.starts_with(p)) {
+            if rest.starts_with("...") {
+                3
+            } else {
+                2
+            }
+        } else {
+            c.len_utf8()
+        };
+        let token = &rest[..len];
+        rest = rest[len..].trim_start();
+
+        if token.starts_with('\'') {
+            continue;
+        }
+        if token == "mut" && tokens.last() == Some(&"&") {
+            tokens.pop();
+            tokens.push("&mut");
+        } else if !TYPE_QUALIFIERS.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+fn contains_match(pattern: &[&str], ty: &[&str]) -> bool {
+    if matches_whole(pattern, ty) {
+        return true;
+    }
+    nested_types(ty)
+        .into_iter()
+        .any(|nested| contains_match(pattern, nested))
+}
+
+/// Types nested in `ty`: the referent of `&`, `&mut`, `*`, `...` and `[]`
+/// before or after it, each comma-separated argument between brackets, and
+/// the return type after `->`
+fn nested_types<'t, 's>(ty: &'t [&'s str]) -> Vec<&'t [&'s str]> {
+    if ty.len() > 1 {
+        if matches!(ty[0], "&" | "&mut" | "*" | "...") {
+            return vec![&ty[1..]];
+        }
+        if matches!(ty[ty.len() - 1], "&" | "*" | "...") {
+            return vec![&ty[..ty.len() - 1]];
+        }
+    }
+    if ty.len() > 2 {
+        if ty[..2] == ["[", "]"] {
+            return vec![&ty[2..]];
+        }
+        if ty[ty.len() - 2..] == ["[", "]"] {
+            return vec![&ty[..ty.len() - 2]];
+        }
+    }
+
+    let mut nested = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, &token) in ty.iter().enumerate() {
+        match token {
+            "<" | "(" | "[" | "{" => {
+                if depth == 0 {
+                    start = i + 1;
+                }
+                depth += 1;
+            }
+            ">" | ")" | "]" | "}" if depth > 0 => {
+                depth -= 1;
+                if depth == 0 && start < i {
+                    nested.push(&ty[start..i]);
+                }
+            }
+            "," if depth == 1 => {
+                nested.push(&ty[start..i]);
+                start = i + 1;
+            }
+            "->" if depth == 0 => {
+                nested.push(&ty[i + 1..]);
+                break;
+            }
+            _ => {}
+        }
+    }
+    nested
+}
+
+/// Whether the whole of `ty` matches `pattern`, with `_` matching one type
+fn matches_whole(pattern: &[&str], ty: &[&str]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return ty.is_empty();
+    };
+    if first == "_" {
+        // Any balanced run of tokens up to the next delimiter at this depth
+        let mut depth = 0;
+        for (i, &token) in ty.iter().enumerate() {
+            match token {
+                "<" | "(" | "[" | "{" => depth += 1,
+                ">" | ")" | "]" | "}" | "," if depth == 0 => {
+                    return i > 0 && matches_whole(rest, &ty[i..]);
+                }
+                ">" | ")" | "]" | "}" => depth -= 1,
+                _ => {}
+            }
+        }
+        return !ty.is_empty() && rest.is_empty();
+    }
+    match ty.split_first() {
+        Some((&token, ty_rest)) if token == first => matches_whole(rest, ty_rest),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Edge, EdgeKind, FileRecord};
+
+    fn node(name: &str, kind: NodeKind, file_path: &str) -> Node {
+        Node {
+            id: 0,
+            kind,
+            name: name.to_string(),
+            qualified_name: Some(name.to_string()),
+            file_path: file_path.to_string(),
+            start_line: 1,
+            end_line: 2,
+            start_column: 0,
+            end_column: 0,
+            signature: None,
+            signature_parts: None,
+            arity: None,
+            visibility: Visibility::Unknown,
+            docstring: None,
+            doc_tags: None,
+            is_async: false,
+            is_static: false,
+            is_exported: false,
+            has_parse_errors: false,
+            language: Language::from_extension(file_path.rsplit('.').next().unwrap_or("")),
+            decorators: Vec::new(),
+        }
+    }
+
+    fn setup(nodes: &[Node]) -> (Database, Vec<i64>) {
+        let db = Database::in_memory().unwrap();
+        let mut files: Vec<&str> = nodes.iter().map(|n| n.file_path.as_str()).collect();
+        files.dedup();
+        for path in files {
+            db.insert_or_update_file(&FileRecord {
+                path: path.to_string(),
+                content_hash: "hash".to_string(),
+                language: Language::from_extension(path.rsplit('.').next().unwrap_or("")),
+                size: 0,
+                modified_at: 0,
+                indexed_at: 0,
+                node_count: 0,
+            })
+            .unwrap();
+        }
+        let ids = nodes.iter().map(|n| db.insert_node(n).unwrap()).collect();
+        (db, ids)
+    }
+
+    fn names(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.node.name.as_str()).collect()
+    }
+
+    fn options(limit: u32) -> SearchOptions {
+        SearchOptions {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_identifier_parts() {
+        assert_eq!(identifier_parts("UserRepository"), ["user", "repository"]);
+        assert_eq!(
+            identifier_parts("get_user_by_id"),
+            ["get", "user", "by", "id"]
+        );
+        assert_eq!(
+            identifier_parts("HTTPServerConfig"),
+            ["http", "server", "config"]
+        );
+        assert_eq!(identifier_parts("utf8Decode"), ["utf8", "decode"]);
+        assert_eq!(
+            search_tokens("UserRepositoryImpl"),
+            "user repository impl userrepositoryimpl repositoryimpl"
+        );
+    }
+
+    #[test]
+    fn test_sub_token_and_text_matches() {
+        let mut documented = node("load", NodeKind::Function, "src/config.rs");
+        documented.docstring = Some("Read the repository settings".to_string());
+        let (db, _) = setup(&[
+            node("UserRepository", NodeKind::Class, "src/user.ts"),
+            node("find_user_repo", NodeKind::Function, "src/user.rs"),
+            documented,
+        ]);
+
+        let hits = db.search_symbols("userrepo", &options(10)).unwrap();
+        assert_eq!(names(&hits), ["UserRepository", "find_user_repo"]);
+        assert_eq!(hits[0].match_kind, MatchKind::Prefix);
+        assert_eq!(hits[1].match_kind, MatchKind::SubToken);
+
+        let hits = db.search_symbols("repository", &options(10)).unwrap();
+        assert_eq!(names(&hits), ["UserRepository", "load"]);
+        assert_eq!(hits[1].match_kind, MatchKind::Text);
+    }
+
+    #[test]
+    fn test_fuzzy_matches_tolerate_typos() {
+        let (db, _) = setup(&[
+            node("resolve_references", NodeKind::Function, "src/db.rs"),
+            node("Parser", NodeKind::Struct, "src/parse.rs"),
+        ]);
+
+        let hits = db.search_symbols("resolev", &options(10)).unwrap();
+        assert_eq!(names(&hits), ["resolve_references"]);
+        assert_eq!(hits[0].match_kind, MatchKind::Fuzzy);
+
+        assert_eq!(
+            names(&db.search_symbols("Parsre", &options(10)).unwrap()),
+            ["Parser"]
+        );
+        assert!(db.search_symbols("xyz", &options(10)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_ranking_prefers_exact_types_and_central_symbols() {
+        let (db, ids) = setup(&[
+            node("config_value", NodeKind::Variable, "src/a.rs"),
+            node("config_path", NodeKind::Function, "src/a.rs"),
+            node("Config", NodeKind::Struct, "src/b.rs"),
+            node("caller", NodeKind::Function, "src/b.rs"),
+        ]);
+        db.insert_edge(&Edge {
+            id: 0,
+            source_id: ids[3],
+            target_id: ids[1],
+            kind: EdgeKind::Calls,
+            file_path: Some("src/b.rs".to_string()),
+            line: Some(1),
+            column: None,
+            confidence: 1.0,
+        })
+        .unwrap();
+
+        let hits = db.search_symbols("config", &options(10)).unwrap();
+        assert_eq!(names(&hits), ["Config", "config_path", "config_value"]);
+        assert_eq!(hits[0].match_kind, MatchKind::Exact);
+    }
+
+    #[test]
+    fn test_filters_and_deleted_nodes() {
+        let (db, _) = setup(&[
+            node("render", NodeKind::Function, "src/view.ts"),
+            node("render", NodeKind::Function, "lib/view.py"),
+            node("Renderer", NodeKind::Class, "src/renderer.ts"),
+        ]);
+
+        let by_language = SearchOptions {
+            language: Some(Language::Python),
+            ..options(10)
+        };
+        let hits = db.search_symbols("render", &by_language).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].node.file_path, "lib/view.py");
+
+        let by_path = SearchOptions {
+            path: Some(globset::Glob::new("src/**").unwrap().compile_matcher()),
+            kind: Some(NodeKind::Class),
+            ..options(10)
+        };
+        assert_eq!(
+            names(&db.search_symbols("render", &by_path).unwrap()),
+            ["Renderer"]
+        );
+
+        db.delete_file("src/renderer.ts").unwrap();
+        assert!(db.search_symbols("renderer", &by_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_type_matches() {
+        assert!(type_matches("&mut Connection", "&mut Connection"));
+        assert!(type_matches("Connection", "&mut Connection"));
+        assert!(type_matches("Connection", "Option<Arc<Connection>>"));
+        assert!(!type_matches("&mut Connection", "&Connection"));
+        assert!(!type_matches("Connection", "ConnectionPool"));
+
+        assert!(type_matches(
+            "Result<_, AuthError>",
+            "Result<User, AuthError>"
+        ));
+        assert!(type_matches(
+            "Result<_, AuthError>",
+            "Result<Vec<(u32, String)>, AuthError>"
+        ));
+        assert!(!type_matches(
+            "Result<_, AuthError>",
+            "Result<User, IoError>"
+        ));
+        assert!(type_matches("Map<_, _>", "Map < string, number >"));
+        assert!(type_matches("_user", "_user"));
+        assert!(!type_matches("", "User"));
+    }
+
+    #[test]
+    fn test_type_matches_skips_lifetimes_and_qualifiers() {
+        assert!(type_matches("Connection", "&'a mut Connection"));
+        assert!(type_matches("&mut Connection", "&'a mut Connection"));
+        assert!(!type_matches("&mut Connection", "&'a Connection"));
+        assert!(type_matches("Connection", "Box<dyn Connection>"));
+        assert!(type_matches("Box<dyn Connection>", "Box<Connection>"));
+        assert!(type_matches("Read", "impl Read"));
+        assert!(type_matches("Error", "Box<dyn Fn(u32) -> Error>"));
+        assert!(!type_matches("Error", "std::io::Error"));
+
+        assert!(type_matches("char", "const char *"));
+        assert!(type_matches("Conn", "struct Conn *"));
+        assert!(type_matches("std::string", "const std::string &"));
+        assert!(type_matches("string", "[]string"));
+        assert!(type_matches("User", "User[]"));
+        assert!(type_matches("T", "...T"));
+        assert!(type_matches("String", "String..."));
+        assert!(type_matches("T", "Vec::<T>"));
+    }
+}
@@ -0,0 +1,596 @@
+//! Documentation text and its structured parts
+//!
+//! Doc comments and docstrings are cleaned of their comment markers and
+//! quotes, keeping paragraphs and the indentation of examples. Parameters,
+//! return values, thrown errors and examples are then read in the convention
+//! of each language:
+//! - Rust: `# Arguments`, `# Returns`, `# Errors`/`# Panics` and `# Examples` sections
+//! - Python: Google (`Args:`), NumPy (`Parameters` + `----------`) and reST (`:param x:`)
+//! - JSDoc, Javadoc and Doxygen: `@param`, `@returns`, `@throws` and `@example` tags
+//! - C#: `<param>`, `<returns>`, `<exception>` and `<example>` XML elements
+
+use crate::types::{DocEntry, DocTags, Language};
+
+/// Text of a comment without its markers: `///`, `//!`, `//`, `/** */`, `#`
+/// and the leading `*` of block comment lines
+pub fn comment_text(comment: &str) -> String {
+    let mut lines = Vec::new();
+    for line in comment.lines() {
+        let trimmed = line.trim();
+        let trimmed = trimmed.strip_suffix("*/").unwrap_or(trimmed).trim_end();
+        let text = ["///", "//!", "//", "/**", "/*!", "/*", "#"]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+            .unwrap_or_else(|| trimmed.strip_prefix('*').unwrap_or(trimmed));
+        // One space separates the marker from the text; more is indentation
+        lines.push(text.strip_prefix(' ').unwrap_or(text).to_string());
+    }
+    trim_blank_lines(&lines)
+}
+
+/// Text of a Python docstring literal, without prefix and quotes and with the
+/// common indentation of its continuation lines removed
+pub fn python_docstring(literal: &str) -> String {
+    let literal = literal.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let body = ["\"\"\"", "'''", "\"", "'"]
+        .iter()
+        .find_map(|quote| {
+            literal
+                .strip_prefix(quote)
+                .and_then(|rest| rest.strip_suffix(quote))
+        })
+        .unwrap_or(literal);
+
+    let mut lines = body.lines();
+    let first = lines.next().unwrap_or_default().trim().to_string();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut cleaned = vec![first];
+    for line in rest {
+        cleaned.push(line.get(indent..).unwrap_or("").trim_end().to_string());
+    }
+    trim_blank_lines(&cleaned)
+}
+
+fn trim_blank_lines(lines: &[String]) -> String {
+    let start = lines.iter().position(|l| !l.trim().is_empty());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Structured parts of documentation written in the convention of `language`
+pub fn parse_tags(language: Language, doc: &str) -> Option<DocTags> {
+    let tags = match language {
+        Language::Rust => markdown_sections(doc),
+        Language::Python => python_sections(doc),
+        Language::Go => return None,
+        Language::CSharp => xml_tags(doc),
+        _ => block_tags(doc),
+    };
+    (tags != DocTags::default()).then_some(tags)
+}
+
+fn entry(name: &str, description: &str) -> DocEntry {
+    DocEntry {
+        name: name.trim().to_string(),
+        description: description.trim().to_string(),
+    }
+}
+
+/// Append a continuation line to the last entry's description
+fn continue_entry(entries: &mut [DocEntry], line: &str) {
+    if let Some(last) = entries.last_mut() {
+        if !last.description.is_empty() {
+            last.description.push(' ');
+        }
+        last.description.push_str(line.trim());
+    }
+}
+
+/// `@param`, `@returns`, `@throws` and `@example` tags of JSDoc and Javadoc,
+/// and their Doxygen `\param` spellings
+fn block_tags(doc: &str) -> DocTags {
+    let mut tags = DocTags::default();
+    let mut current: Option<&str> = None;
+    let mut example: Vec<&str> = Vec::new();
+
+    let finish_example = |tags: &mut DocTags, example: &mut Vec<&str>| {
+        let text = example.join("\n").trim().to_string();
+        if !text.is_empty() {
+            tags.examples.push(text);
+        }
+        example.clear();
+    };
+
+    for line in doc.lines() {
+        let trimmed = line.trim();
+        let Some(tagged) = trimmed
+            .strip_prefix('@')
+            .or_else(|| trimmed.strip_prefix('\\'))
+        else {
+            match current {
+                Some("example") => example.push(line),
+                Some("param") => continue_entry(&mut tags.params, trimmed),
+                Some("throws") => continue_entry(&mut tags.throws, trimmed),
+                Some("returns") if !trimmed.is_empty() => {
+                    let returns = tags.returns.get_or_insert_with(String::new);
+                    returns.push(' ');
+                    returns.push_str(trimmed);
+                }
+                _ => {}
+            }
+            continue;
+        };
+
+        if current == Some("example") {
+            finish_example(&mut tags, &mut example);
+        }
+        // The Doxygen direction stays with the text: \param[in] name
+        let end = tagged
+            .find(|c: char| c.is_whitespace() || c == '[')
+            .unwrap_or(tagged.len());
+        let (tag, rest) = tagged.split_at(end);
+        current = match tag {
+            "param" | "arg" | "argument" | "tparam" => {
+                // @param {Type} name description, @param[in] name description
+                let rest = skip_type(rest);
+                let (name, description) =
+                    rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                let name = name.trim_matches(['[', ']']);
+                let name = name.split('=').next().unwrap_or(name);
+                tags.params
+                    .push(entry(name, description.trim_start_matches(['-', ' '])));
+                Some("param")
+            }
+            "returns" | "return" | "retval" => {
+                tags.returns = Some(skip_type(rest).trim().to_string());
+                Some("returns")
+            }
+            "throws" | "throw" | "exception" | "raises" => {
+                // @throws {Error} description, @throws IOException description
+                let rest = rest.trim();
+                let (name, description) = match rest.strip_prefix('{') {
+                    Some(braced) => braced.split_once('}').unwrap_or((braced, "")),
+                    None => rest.split_once(char::is_whitespace).unwrap_or((rest, "")),
+                };
+                tags.throws.push(entry(name, description));
+                Some("throws")
+            }
+            "example" => {
+                if !rest.trim().is_empty() {
+                    example.push(rest);
+                }
+                Some("example")
+            }
+            _ => None,
+        };
+    }
+    if current == Some("example") {
+        finish_example(&mut tags, &mut example);
+    }
+    tags
+}
+
+/// `<param name="x">`, `<returns>`, `<exception cref="T">` and `<example>`
+/// elements of C# XML documentation
+fn xml_tags(doc: &str) -> DocTags {
+    let mut tags = DocTags::default();
+    for (attribute, text) in xml_elements(doc, "param") {
+        tags.params.push(entry(&attribute, &xml_text(&text)));
+    }
+    tags.returns = xml_elements(doc, "returns")
+        .first()
+        .map(|(_, text)| xml_text(text));
+    for (attribute, text) in xml_elements(doc, "exception") {
+        // cref="T:System.ArgumentException" names the type with a prefix
+        let name = attribute.split_once(':').map_or(&*attribute, |(_, t)| t);
+        tags.throws.push(entry(name, &xml_text(&text)));
+    }
+    for (_, text) in xml_elements(doc, "example") {
+        let code = text.replace("<code>", "").replace("</code>", "");
+        let lines: Vec<String> = code.lines().map(str::to_string).collect();
+        let example = trim_blank_lines(&lines);
+        if !example.is_empty() {
+            tags.examples.push(example);
+        }
+    }
+    tags
+}
+
+/// The `name` or `cref` attribute and the content of each `<tag>` element
+fn xml_elements(doc: &str, tag: &str) -> Vec<(String, String)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut elements = Vec::new();
+    let mut rest = doc;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // <paramref> is not <param>
+        if !after.starts_with(['>', ' ', '/']) {
+            rest = after;
+            continue;
+        }
+        let Some(head_end) = after.find('>') else {
+            break;
+        };
+        let head = &after[..head_end];
+        let attribute = ["name=\"", "cref=\""]
+            .iter()
+            .find_map(|a| head.split_once(a))
+            .and_then(|(_, value)| value.split_once('"'))
+            .map(|(value, _)| value.to_string())
+            .unwrap_or_default();
+        let body = &after[head_end + 1..];
+        if head.ends_with('/') {
+            elements.push((attribute, String::new()));
+            rest = body;
+            continue;
+        }
+        let end = body.find(&close).unwrap_or(body.len());
+        elements.push((attribute, body[..end].to_string()));
+        rest = &body[end..];
+    }
+    elements
+}
+
+/// Text of XML documentation on one line, with `<see cref="T"/>` and
+/// `<paramref name="x"/>` replaced by what they name and other markup dropped
+fn xml_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let markup = &rest[start..start + end];
+        let named = ["cref=\"", "name=\"", "langword=\""]
+            .iter()
+            .find_map(|a| markup.split_once(a))
+            .and_then(|(_, value)| value.split_once('"'));
+        if let Some((value, _)) = named.filter(|_| markup.ends_with('/')) {
+            text.push_str(value.split_once(':').map_or(value, |(_, t)| t));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Drop a leading `{Type}` or `[in]` from a tag's text
+fn skip_type(text: &str) -> &str {
+    let text = text.trim_start();
+    let text = match text.strip_prefix('{') {
+        Some(braced) => braced.split_once('}').map_or(braced, |(_, rest)| rest),
+        None => text,
+    };
+    let text = text.trim_start();
+    match text.strip_prefix('[') {
+        Some(direction) if direction.starts_with("in") || direction.starts_with("out") => direction
+            .split_once(']')
+            .map_or(direction, |(_, rest)| rest),
+        _ => text,
+    }
+    .trim_start()
+}
+
+/// Markdown sections of Rust docs: `# Arguments`, `# Returns`, `# Errors`,
+/// `# Panics` and `# Examples`
+fn markdown_sections(doc: &str) -> DocTags {
+    let mut tags = DocTags::default();
+    for (heading, body) in markdown_headings(doc) {
+        match heading.to_lowercase().as_str() {
+            "arguments" | "parameters" | "params" => {
+                for item in list_items(&body) {
+                    // * `name` - description, - name: description
+                    let (name, description) = item
+                        .split_once(" - ")
+                        .or_else(|| item.split_once(':'))
+                        .unwrap_or((&item, ""));
+                    tags.params.push(entry(name.trim_matches('`'), description));
+                }
+            }
+            "returns" | "return value" => tags.returns = Some(body.trim().to_string()),
+            "errors" | "panics" => tags.throws.push(entry("", &body.replace('\n', " "))),
+            "examples" | "example" => tags.examples.extend(code_blocks(&body)),
+            _ => {}
+        }
+    }
+    tags
+}
+
+/// `(heading, body)` of each `# Heading` of a markdown document, outside of
+/// code blocks
+fn markdown_headings(doc: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    let mut in_code = false;
+    for line in doc.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        let heading = line.strip_prefix('#').filter(|_| !in_code);
+        match heading {
+            Some(heading) => {
+                let heading = heading.trim_start_matches('#').trim();
+                sections.push((heading.to_string(), String::new()));
+            }
+            None => {
+                if let Some((_, body)) = sections.last_mut() {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+            }
+        }
+    }
+    sections
+}
+
+/// Items of a markdown `*` or `-` list, with continuation lines joined
+fn list_items(body: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        match trimmed
+            .strip_prefix("* ")
+            .or_else(|| trimmed.strip_prefix("- "))
+        {
+            Some(item) => items.push(item.to_string()),
+            None if !trimmed.is_empty() => {
+                if let Some(last) = items.last_mut() {
+                    last.push(' ');
+                    last.push_str(trimmed);
+                }
+            }
+            None => {}
+        }
+    }
+    items
+}
+
+/// Contents of the fenced code blocks of a markdown text
+fn code_blocks(body: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => blocks.push(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    blocks
+}
+
+/// Sections of a Python docstring in Google, NumPy or reST style
+fn python_sections(doc: &str) -> DocTags {
+    let mut tags = DocTags::default();
+    let lines: Vec<&str> = doc.lines().collect();
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        i += 1;
+
+        // reST fields: :param name: description, :returns:, :raises Error:
+        if let Some((field, description)) = trimmed
+            .strip_prefix(':')
+            .and_then(|field| field.split_once(':'))
+        {
+            let mut words = field.split_whitespace();
+            let tag = words.next().unwrap_or_default();
+            let name = words.last().unwrap_or_default();
+            match tag {
+                "param" | "parameter" | "arg" | "argument" => {
+                    tags.params.push(entry(name, description))
+                }
+                "returns" | "return" => tags.returns = Some(description.trim().to_string()),
+                "raises" | "raise" | "except" | "exception" => {
+                    tags.throws.push(entry(name, description))
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        // Google headers end with a colon; NumPy headers are underlined
+        let numpy = lines.get(i).is_some_and(|next| {
+            let next = next.trim();
+            !next.is_empty() && next.chars().all(|c| c == '-')
+        });
+        let header = if numpy {
+            Some(trimmed)
+        } else {
+            trimmed.strip_suffix(':')
+        };
+        let Some(section) = header.and_then(python_section_kind) else {
+            continue;
+        };
+        if numpy {
+            i += 1;
+        }
+
+        // Google entries are indented under their header, NumPy entries are
+        // level with it; deeper lines continue the entry
+        let header_indent = indent_of(lines[i - 1 - usize::from(numpy)]);
+        let start = i;
+        while i < lines.len() {
+            let line = lines[i];
+            let ends_section = if numpy {
+                lines.get(i + 1).is_some_and(|next| {
+                    let next = next.trim();
+                    !next.is_empty() && next.chars().all(|c| c == '-')
+                })
+            } else {
+                !line.trim().is_empty() && indent_of(line) <= header_indent
+            };
+            if ends_section {
+                break;
+            }
+            i += 1;
+        }
+        let body = &lines[start..i];
+        let entry_indent = body
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| indent_of(l))
+            .min()
+            .unwrap_or(0);
+
+        match section {
+            "examples" => push_example(&mut tags, body),
+            "returns" => {
+                let text: Vec<&str> = body.iter().map(|l| l.trim()).collect();
+                let text = text.join(" ");
+                if !text.trim().is_empty() {
+                    tags.returns = Some(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+            }
+            _ => {
+                let entries = match section {
+                    "params" => &mut tags.params,
+                    _ => &mut tags.throws,
+                };
+                for line in body.iter().filter(|l| !l.trim().is_empty()) {
+                    let trimmed = line.trim();
+                    if indent_of(line) > entry_indent {
+                        continue_entry(entries, trimmed);
+                    } else if numpy {
+                        // name : type, with the description on the following lines
+                        let name = trimmed.split(" : ").next().unwrap_or(trimmed);
+                        entries.push(entry(name, ""));
+                    } else {
+                        // name (type): description
+                        let (name, description) = trimmed.split_once(':').unwrap_or((trimmed, ""));
+                        let name = name.split(" (").next().unwrap_or(name);
+                        entries.push(entry(name, description));
+                    }
+                }
+            }
+        }
+    }
+    tags
+}
+
+fn push_example(tags: &mut DocTags, example: &[&str]) {
+    let indent = example
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<String> = example
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or("").trim_end().to_string())
+        .collect();
+    let text = trim_blank_lines(&lines);
+    if !text.is_empty() {
+        tags.examples.push(text);
+    }
+}
+
+fn python_section_kind(header: &str) -> Option<&'static str> {
+    match header.to_lowercase().as_str() {
+        "args" | "arguments" | "parameters" | "params" | "keyword args" | "other parameters" => {
+            Some("params")
+        }
+        "returns" | "return" | "yields" | "yield" => Some("returns"),
+        "raises" | "raise" | "exceptions" | "throws" => Some("throws"),
+        "example" | "examples" => Some("examples"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_text() {
+        assert_eq!(
+            comment_text("/// Sum of\n///   a and b\n"),
+            "Sum of\n  a and b"
+        );
+        assert_eq!(
+            comment_text("/**\n * First.\n *\n * Second.\n */"),
+            "First.\n\nSecond."
+        );
+        assert_eq!(comment_text("/* inline */"), "inline");
+    }
+
+    #[test]
+    fn test_python_docstring() {
+        assert_eq!(python_docstring(r#""""One line.""""#), "One line.");
+        assert_eq!(
+            python_docstring("r'''Summary.\n\n    Details\n      indented.\n    '''"),
+            "Summary.\n\nDetails\n  indented."
+        );
+    }
+
+    #[test]
+    fn test_block_tags() {
+        let tags = parse_tags(
+            Language::Java,
+            "Parse a date.\n@param text the text,\n  in ISO format\n@param[in] strict whether to reject\n@return the date\n@exception ParseException if malformed",
+        )
+        .unwrap();
+        let params: Vec<_> = tags
+            .params
+            .iter()
+            .map(|p| (p.name.as_str(), p.description.as_str()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                ("text", "the text, in ISO format"),
+                ("strict", "whether to reject")
+            ]
+        );
+        assert_eq!(tags.returns.as_deref(), Some("the date"));
+        assert_eq!(tags.throws[0].name, "ParseException");
+        assert_eq!(tags.throws[0].description, "if malformed");
+
+        assert_eq!(parse_tags(Language::TypeScript, "No tags here."), None);
+        assert_eq!(parse_tags(Language::Go, "@param x ignored"), None);
+    }
+
+    #[test]
+    fn test_xml_tags() {
+        let tags = parse_tags(
+            Language::CSharp,
+            "<summary>Parse a date.</summary>\n<param name=\"text\">The text, like\n<paramref name=\"sample\"/>.</param>\n<param name=\"strict\"/>\n<returns>The <see cref=\"T:System.DateTime\"/>.</returns>\n<exception cref=\"FormatException\">If malformed.</exception>\n<example>\n<code>\nParse(\"2024-01-01\");\n</code>\n</example>",
+        )
+        .unwrap();
+        let params: Vec<_> = tags
+            .params
+            .iter()
+            .map(|p| (p.name.as_str(), p.description.as_str()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![("text", "The text, like sample."), ("strict", "")]
+        );
+        assert_eq!(tags.returns.as_deref(), Some("The System.DateTime."));
+        assert_eq!(tags.throws[0].name, "FormatException");
+        assert_eq!(tags.throws[0].description, "If malformed.");
+        assert_eq!(tags.examples, vec!["Parse(\"2024-01-01\");"]);
+
+        assert_eq!(
+            parse_tags(Language::CSharp, "<summary>No tags.</summary>"),
+            None
+        );
+    }
+}
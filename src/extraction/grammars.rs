@@ -0,0 +1,289 @@
+//! Tree-sitter grammars loaded at runtime
+//!
+//! A project can index languages that codemap has no compiled-in grammar for
+//! by listing grammar shared libraries in `.codemap/config.json`:
+//!
+//! ```json
+//! {
+//!   "grammars": [
+//!     { "name": "lua", "library": "grammars/lua.so", "extensions": ["lua"] }
+//!   ]
+//! }
+//! ```
+//!
+//! The library is built by the tree-sitter CLI (`tree-sitter build`) and
+//! exports `tree_sitter_<name>`, or the function named by `symbol`. Its
+//! extraction queries are the `.scm` files in `.codemap/queries/<name>/`, or
+//! in the directory named by `queries`. Relative paths are resolved against
+//! the project root.
+//!
+//! Loading a library runs its code, so a project's grammars are only loaded
+//! when the user trusts it with `--allow-grammars` (or
+//! `CODEMAP_ALLOW_GRAMMARS=1`). Loaded grammars live for the rest of the
+//! process: their parse tables are static data inside the library.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+use libloading::Library;
+use serde::Deserialize;
+use tracing::warn;
+use tree_sitter::{LANGUAGE_VERSION, MIN_COMPATIBLE_LANGUAGE_VERSION};
+use tree_sitter_language::LanguageFn;
+
+use super::{languages, queries};
+use crate::types::{GrammarInfo, Language};
+
+/// Project configuration, relative to the project root
+pub const CONFIG_FILE: &str = ".codemap/config.json";
+
+/// Grammars loaded so far, in load order
+static LOADED: Mutex<Vec<&'static Grammar>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    grammars: Vec<GrammarConfig>,
+}
+
+/// A grammar entry of the project configuration
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GrammarConfig {
+    name: String,
+    library: PathBuf,
+    extensions: Vec<String>,
+    symbol: Option<String>,
+    queries: Option<PathBuf>,
+}
+
+/// A grammar loaded from a shared library
+#[derive(Debug)]
+pub struct Grammar {
+    pub name: &'static str,
+    pub library: PathBuf,
+    /// File extensions, without the dot
+    pub extensions: Vec<String>,
+    /// Directory of the grammar's extraction queries
+    pub queries: PathBuf,
+    language: tree_sitter::Language,
+    _library: Library,
+}
+
+impl Grammar {
+    /// The language of the files this grammar parses: one of the known
+    /// languages without a compiled-in grammar, such as Ruby, or a new one
+    pub fn language(&self) -> Language {
+        Language::named(self.name).unwrap_or(Language::Plugin(self.name))
+    }
+
+    pub fn ts_language(&self) -> tree_sitter::Language {
+        self.language.clone()
+    }
+
+    /// Version and ABI of the grammar
+    pub fn info(&self) -> GrammarInfo {
+        let mut info = info(self.name, &self.language);
+        info.library = Some(self.library.display().to_string());
+        info.extensions = self.extensions.clone();
+        info
+    }
+}
+
+/// Load the grammars listed in a project's `.codemap/config.json`
+///
+/// Unless `allow` is set, the listed libraries are not opened: the grammars
+/// are skipped with a warning and their files are not indexed. Grammars that
+/// are already loaded from the same library are reused, so this can be
+/// called for every index run.
+pub fn load(root: &Path, allow: bool) -> Result<Vec<&'static Grammar>> {
+    let path = root.join(CONFIG_FILE);
+    let config: ProjectConfig = match fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text)
+            .with_context(|| format!("Invalid project config {}", path.display()))?,
+        Err(_) => ProjectConfig::default(),
+    };
+    if !allow && !config.grammars.is_empty() {
+        warn!(
+            "Skipping the grammar libraries listed in {}; pass --allow-grammars to load them",
+            path.display()
+        );
+        return Ok(Vec::new());
+    }
+
+    let mut grammars = Vec::new();
+    for entry in config.grammars {
+        let grammar = load_grammar(root, entry)
+            .with_context(|| format!("Failed to load a grammar listed in {}", path.display()))?;
+        grammars.push(grammar);
+    }
+    Ok(grammars)
+}
+
+fn load_grammar(root: &Path, entry: GrammarConfig) -> Result<&'static Grammar> {
+    let name = entry.name.as_str();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        bail!(
+            "Grammar name `{}` must be lowercase letters, digits and underscores",
+            name
+        );
+    }
+    if name == "unknown" || languages::COMPILED.iter().any(|l| l.as_str() == name) {
+        bail!("`{}` has a built-in grammar", name);
+    }
+    let library = root.join(&entry.library);
+
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(&grammar) = loaded.iter().find(|g| g.name == name) {
+        if grammar.library != library {
+            bail!(
+                "Grammar `{}` is already loaded from {}",
+                name,
+                grammar.library.display()
+            );
+        }
+        return Ok(grammar);
+    }
+
+    let extensions: Vec<String> = entry
+        .extensions
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+    if extensions.is_empty() {
+        bail!("Grammar `{}` lists no file extensions", name);
+    }
+    let symbol = entry
+        .symbol
+        .unwrap_or_else(|| format!("tree_sitter_{}", name));
+
+    // SAFETY: loading a library runs its initializers. The project config
+    // names the library explicitly, as it would a build script.
+    let lib = unsafe { Library::new(&library) }
+        .with_context(|| format!("Failed to open {}", library.display()))?;
+    // SAFETY: tree-sitter grammars export `const TSLanguage *tree_sitter_<name>(void)`.
+    // The library is kept open for as long as the language is used.
+    let language = unsafe {
+        let function = lib
+            .get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes())
+            .with_context(|| format!("{} does not export `{}`", library.display(), symbol))?;
+        tree_sitter::Language::new(LanguageFn::from_raw(*function))
+    };
+    let abi = language.abi_version();
+    if !(MIN_COMPATIBLE_LANGUAGE_VERSION..=LANGUAGE_VERSION).contains(&abi) {
+        bail!(
+            "{} was generated for tree-sitter ABI {}, codemap supports {} to {}",
+            library.display(),
+            abi,
+            MIN_COMPATIBLE_LANGUAGE_VERSION,
+            LANGUAGE_VERSION
+        );
+    }
+
+    let queries = match entry.queries {
+        Some(dir) => root.join(dir),
+        None => root.join(queries::QUERIES_DIR).join(name),
+    };
+    let grammar: &'static Grammar = Box::leak(Box::new(Grammar {
+        name: Box::leak(entry.name.into_boxed_str()),
+        library,
+        extensions,
+        queries,
+        language,
+        _library: lib,
+    }));
+    loaded.push(grammar);
+    Ok(grammar)
+}
+
+/// Grammars loaded so far
+pub fn loaded() -> Vec<&'static Grammar> {
+    LOADED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The loaded grammar with this name
+pub fn by_name(name: &str) -> Option<&'static Grammar> {
+    loaded().into_iter().find(|g| g.name == name)
+}
+
+/// The loaded grammar for files with this extension
+pub fn by_extension(ext: &str) -> Option<&'static Grammar> {
+    loaded()
+        .into_iter()
+        .find(|g| g.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+fn info(name: &str, language: &tree_sitter::Language) -> GrammarInfo {
+    GrammarInfo {
+        name: name.to_string(),
+        version: language.metadata().map(|m| {
+            format!(
+                "{}.{}.{}",
+                m.major_version, m.minor_version, m.patch_version
+            )
+        }),
+        abi_version: language.abi_version(),
+        library: None,
+        extensions: Vec::new(),
+    }
+}
+
+/// The compiled-in grammars and those loaded at runtime
+pub fn available() -> Vec<GrammarInfo> {
+    let mut grammars: Vec<GrammarInfo> = languages::COMPILED
+        .iter()
+        .filter_map(|&language| {
+            let name = queries::grammar_name(language)?;
+            Some(info(name, &languages::get_language(language)?))
+        })
+        .collect();
+    // JSX shares the JavaScript grammar
+    grammars.dedup_by(|a, b| a.name == b.name);
+    grammars.extend(loaded().iter().map(|g| g.info()));
+    grammars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_config(root: &Path, config: &str) {
+        fs::create_dir_all(root.join(".codemap")).unwrap();
+        fs::write(root.join(CONFIG_FILE), config).unwrap();
+    }
+
+    #[test]
+    fn test_no_config() {
+        let dir = tempdir().unwrap();
+        assert!(load(dir.path(), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_grammars() {
+        let dir = tempdir().unwrap();
+        let error = |config: &str| {
+            write_config(dir.path(), config);
+            format!("{:#}", load(dir.path(), true).unwrap_err())
+        };
+
+        let err =
+            error(r#"{"grammars": [{"name": "rust", "library": "x.so", "extensions": ["rs"]}]}"#);
+        assert!(err.contains("`rust` has a built-in grammar"), "{}", err);
+        let err =
+            error(r#"{"grammars": [{"name": "Lua", "library": "x.so", "extensions": ["lua"]}]}"#);
+        assert!(err.contains("must be lowercase"), "{}", err);
+        let err = error(
+            r#"{"grammars": [{"name": "lua", "library": "missing.so", "extensions": ["lua"]}]}"#,
+        );
+        assert!(err.contains("Failed to open"), "{}", err);
+        let err = error(r#"{"grammars": [{"name": "lua", "libary": "lua.so"}]}"#);
+        assert!(err.contains("Invalid project config"), "{}", err);
+    }
+}
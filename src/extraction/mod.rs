@@ -3,28 +3,46 @@
 //! Uses tree-sitter to parse source code and extract:
 //! - Symbols (functions, classes, methods, etc.)
 //! - Relationships (calls, contains, imports, etc.)
+//!
+//! Which syntax nodes are symbols and references is declared by the
+//! per-language queries in `queries/`.
 
+mod docs;
+pub mod grammars;
 mod languages;
+mod queries;
+mod signature;
 
+use std::collections::HashSet;
 use std::path::Path;
+use std::sync::Arc;
 use tree_sitter::Parser;
 
 use crate::types::{
-    Edge, EdgeKind, ExtractionError, ExtractionResult, Language, Node, NodeKind,
-    UnresolvedReference, Visibility,
+    Decorator, DocTags, Edge, EdgeKind, ExtractionError, ExtractionResult, Language, ModuleImport,
+    Node, NodeKind, UnresolvedReference, Visibility,
 };
 
-use languages::LanguageConfig;
+use queries::{Captures, ReferenceKind};
+pub use queries::{Queries, QUERIES_DIR};
 
 /// Extracts code symbols from source files using tree-sitter
 pub struct Extractor {
     parser: Parser,
+    queries: Arc<Queries>,
 }
 
 impl Extractor {
+    /// An extractor using the built-in queries
     pub fn new() -> Self {
+        Self::with_queries(Queries::builtin())
+    }
+
+    /// An extractor using a project's queries, see [`Queries::load`]
+    pub fn with_queries(queries: Arc<Queries>) -> Self {
         Self {
             parser: Parser::new(),
+            queries,
         }
     }
 
@@ -82,7 +100,6 @@ impl Extractor {
             };
         };
 
-        let config = languages::get_config(language);
         let file_path = path.display().to_string();
 
         let mut ctx = ExtractionContext {
@@ -90,7 +107,7 @@ impl Extractor {
             file_path: file_path.clone(),
             content,
             language,
-            config,
+            captures: self.queries.capture(language, tree.root_node(), content),
             node_stack: Vec::new(),
             next_id: 1,
         };
@@ -111,17 +128,24 @@ impl Extractor {
             start_column: 0,
             end_column: 0,
             signature: None,
+            signature_parts: None,
+            arity: None,
             visibility: Visibility::Public,
             docstring: None,
+            doc_tags: None,
             is_async: false,
             is_static: false,
             is_exported: true,
+            has_parse_errors: tree.root_node().has_error(),
             language,
+            decorators: Vec::new(),
         };
         ctx.next_id += 1;
         ctx.result.nodes.push(file_node);
         ctx.node_stack.push(1); // file node ID
 
+        ctx.result.errors = syntax_errors(tree.root_node(), content, &file_path);
+
         // Traverse the tree
         ctx.traverse_node(tree.root_node());
 
@@ -134,17 +158,17 @@ struct ExtractionContext<'a> {
     file_path: String,
     content: &'a str,
     language: Language,
-    config: &'static LanguageConfig,
+    captures: Captures<'a>,
     node_stack: Vec<i64>, // Stack of parent node IDs
     next_id: i64,
 }
 
 impl<'a> ExtractionContext<'a> {
     fn traverse_node(&mut self, node: tree_sitter::Node) {
-        let node_type = node.kind();
+        self.extract_relationship_hints(&node);
 
         // Check if this is a symbol we care about
-        if let Some(kind) = self.config.node_type_to_kind(node_type) {
+        if let Some(kind) = self.symbol_kind(&node) {
             self.extract_symbol(node, kind);
         } else {
             // Continue traversing children
@@ -156,7 +180,16 @@ impl<'a> ExtractionContext<'a> {
     }
 
     fn extract_symbol(&mut self, node: tree_sitter::Node, kind: NodeKind) {
-        let name = self.extract_name(&node, kind);
+        // C++ method declarations share `field_declaration` with data members
+        if self.declares_members(&node) && (kind.is_member() || kind == NodeKind::Constant) {
+            self.extract_members(node, kind);
+            return;
+        }
+
+        let (name, modules) = match kind {
+            NodeKind::Import => self.import_modules(&node),
+            _ => (self.extract_name(&node, kind), Vec::new()),
+        };
         if name.is_empty() {
             // Skip anonymous nodes
             let mut cursor = node.walk();
@@ -167,31 +200,108 @@ impl<'a> ExtractionContext<'a> {
         }
 
         let start = node.start_position();
-        let end = node.end_position();
+        // `namespace Shop.Orders;` holds the rest of the file
+        let file_scoped = node.kind() == "file_scoped_namespace_declaration";
+        let end = match node.parent() {
+            Some(parent) if file_scoped => parent.end_position(),
+            _ => node.end_position(),
+        };
+        let decorators = self.extract_decorators(&node);
+        let (docstring, doc_tags) = self.extract_docs(&node);
+        let signature_parts = matches!(kind, NodeKind::Function | NodeKind::Method)
+            .then(|| self.extract_signature_parts(&node));
+        let owner = self.external_owner(&node);
+        let qualified_name = match owner {
+            Some(ref owner) => self.build_qualified_name(&format!("{}::{}", owner, name)),
+            None => self.build_qualified_name(&name),
+        };
 
         let symbol = Node {
             id: self.next_id,
             kind,
             name: name.clone(),
-            qualified_name: self.build_qualified_name(&name),
+            qualified_name,
             file_path: self.file_path.clone(),
             start_line: start.row as u32 + 1,
             end_line: end.row as u32 + 1,
             start_column: start.column as u32,
             end_column: end.column as u32,
             signature: self.extract_signature(&node, kind),
-            visibility: self.extract_visibility(&node),
-            docstring: self.extract_docstring(&node),
+            arity: signature_parts.as_ref().map(|p| p.params.len() as u32),
+            signature_parts,
+            visibility: self.extract_visibility(&node, &name),
+            docstring,
+            doc_tags,
             is_async: self.check_async(&node),
             is_static: self.check_static(&node),
-            is_exported: self.check_exported(&node),
+            is_exported: self.check_exported(&node, &name),
+            has_parse_errors: self.has_parse_errors(&node),
             language: self.language,
+            decorators: decorators.iter().map(|(_, d)| d.clone()).collect(),
         };
 
         let symbol_id = self.next_id;
         self.next_id += 1;
         self.result.nodes.push(symbol);
 
+        // Link to decorator definitions; built-in ones have none in the repo
+        for (at, decorator) in &decorators {
+            if is_builtin_decorator(self.language, &decorator.name) {
+                continue;
+            }
+            let (qualifier, name) = split_callee(&decorator.name);
+            let receiver = qualifier.map(str::to_string);
+            // C# `[Serializable]` names the class `SerializableAttribute`
+            let name = match self.language {
+                Language::CSharp if !name.ends_with("Attribute") => format!("{}Attribute", name),
+                _ => name.to_string(),
+            };
+            self.push_reference(symbol_id, None, name, EdgeKind::Decorates, at, receiver);
+        }
+
+        // `mod foo;` pulls in a file the same way `use self::foo` names it
+        let is_mod_declaration = self.language == Language::Rust
+            && node.kind() == "mod_item"
+            && node.child_by_field_name("body").is_none();
+        let modules = match is_mod_declaration {
+            true => vec![format!("self::{}", name)],
+            false => modules,
+        };
+        let modules: Vec<String> = match self.language {
+            Language::Rust => modules
+                .iter()
+                .map(|m| self.rust_path_from_file(&node, m))
+                .collect(),
+            _ => modules,
+        };
+        for module in modules {
+            self.result.imports.push(ModuleImport {
+                source_node_id: symbol_id,
+                module,
+                file_path: self.file_path.clone(),
+                line: start.row as u32 + 1,
+                language: self.language,
+            });
+        }
+
+        // Methods of `impl Trait for Type` override the trait's methods
+        if let Some(trait_name) = self.implemented_trait(&node) {
+            self.push_reference(
+                symbol_id,
+                None,
+                name.clone(),
+                EdgeKind::Overrides,
+                &node,
+                Some(trait_name),
+            );
+        }
+
+        // The owning type of an out-of-body method may live in another file;
+        // resolution links it with a Contains edge from the type
+        if let Some(owner) = owner {
+            self.push_reference(symbol_id, None, owner, EdgeKind::Contains, &node, None);
+        }
+
         // Create contains edge from parent
         if let Some(&parent_id) = self.node_stack.last() {
             let edge = Edge {
@@ -201,16 +311,20 @@ impl<'a> ExtractionContext<'a> {
                 kind: EdgeKind::Contains,
                 file_path: Some(self.file_path.clone()),
                 line: Some(start.row as u32 + 1),
-                column: Some(start.column as u32),
+                column: Some(start.column as u32 + 1),
+                confidence: 1.0,
             };
             self.result.edges.push(edge);
         }
 
+        // Record extends/implements clauses of type declarations
+        self.extract_inheritance(&node, kind, symbol_id);
+
         // Push this symbol onto the stack and traverse children
         self.node_stack.push(symbol_id);
 
         // Extract function calls and other references from body
-        self.extract_references(&node, symbol_id);
+        self.extract_references(&node, symbol_id, kind);
 
         // Traverse children for nested definitions
         let mut cursor = node.walk();
@@ -218,22 +332,445 @@ impl<'a> ExtractionContext<'a> {
             self.traverse_node(child);
         }
 
-        self.node_stack.pop();
+        // The declarations after a file-scoped namespace are its siblings
+        if !file_scoped {
+            self.node_stack.pop();
+        }
+    }
+
+    /// The kind of symbol `node` declares, if any
+    fn symbol_kind(&self, node: &tree_sitter::Node) -> Option<NodeKind> {
+        let kind = self.captures.definition(node)?.kind;
+        self.refine_kind(node, kind)
+    }
+
+    /// Whether the query captures `node` as a declaration of members of its
+    /// owner rather than of a symbol with a body: fields, properties, enum
+    /// members and parameters
+    fn declares_members(&self, node: &tree_sitter::Node) -> bool {
+        self.captures
+            .definition(node)
+            .is_some_and(|d| d.kind.is_member())
+    }
+
+    /// Extract the fields, properties, enum members or parameters that `node`
+    /// declares, contained by the enclosing symbol and typed by their
+    /// declared type
+    fn extract_members(&mut self, node: tree_sitter::Node, kind: NodeKind) {
+        let start = node.start_position();
+        let end = node.end_position();
+        let signature = self
+            .get_node_text(&node)
+            .lines()
+            .next()
+            .map(|line| line.trim().trim_end_matches([',', ';']).to_string());
+        let decorators: Vec<Decorator> = self
+            .extract_decorators(&node)
+            .into_iter()
+            .map(|(_, d)| d)
+            .collect();
+        // C# fields and events declare their type and names in a
+        // `variable_declaration`
+        let declaration = {
+            let mut cursor = node.walk();
+            let inner = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "variable_declaration");
+            inner.unwrap_or(node)
+        };
+        let type_node = declaration.child_by_field_name("type");
+        let (docstring, doc_tags) = self.extract_docs(&node);
+
+        let mut first_id = None;
+        for name_node in self.member_names(&declaration) {
+            let name = self.get_node_text(&name_node);
+            let visibility = match kind {
+                NodeKind::Parameter => Visibility::Private,
+                _ => self.extract_visibility(&node, &name),
+            };
+            let member = Node {
+                id: self.next_id,
+                kind,
+                name: name.clone(),
+                qualified_name: self.build_qualified_name(&name),
+                file_path: self.file_path.clone(),
+                start_line: start.row as u32 + 1,
+                end_line: end.row as u32 + 1,
+                start_column: start.column as u32,
+                end_column: end.column as u32,
+                signature: signature.clone(),
+                signature_parts: None,
+                arity: None,
+                visibility,
+                docstring: docstring.clone(),
+                doc_tags: doc_tags.clone(),
+                is_async: false,
+                is_static: kind != NodeKind::Parameter && self.check_static(&node),
+                is_exported: kind != NodeKind::Parameter && self.check_exported(&node, &name),
+                has_parse_errors: self.has_parse_errors(&node),
+                language: self.language,
+                decorators: decorators.clone(),
+            };
+            let member_id = self.next_id;
+            self.next_id += 1;
+            self.result.nodes.push(member);
+            first_id.get_or_insert(member_id);
+
+            if let Some(&parent_id) = self.node_stack.last() {
+                self.result.edges.push(Edge {
+                    id: 0,
+                    source_id: parent_id,
+                    target_id: member_id,
+                    kind: EdgeKind::Contains,
+                    file_path: Some(self.file_path.clone()),
+                    line: Some(start.row as u32 + 1),
+                    column: Some(start.column as u32),
+                    confidence: 1.0,
+                });
+            }
+            if let Some(type_node) = type_node {
+                let mut seen = HashSet::new();
+                self.find_type_uses(&type_node, member_id, EdgeKind::TypeOf, false, &mut seen);
+            }
+        }
+
+        // Fields of enum variants, functions assigned to properties
+        if let Some(member_id) = first_id {
+            self.node_stack.push(member_id);
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.traverse_node(child);
+        }
+        if first_id.is_some() {
+            self.node_stack.pop();
+        }
+    }
+
+    /// Name nodes of the members a declaration declares; Go, Java, C and C#
+    /// can declare several at once (`a, b int`, `int a, b;`)
+    fn member_names<'t>(&self, node: &tree_sitter::Node<'t>) -> Vec<tree_sitter::Node<'t>> {
+        if let Some(name) = self.captures.definition(node).and_then(|d| d.name) {
+            return node
+                .descendant_for_byte_range(name.start_byte(), name.end_byte())
+                .into_iter()
+                .collect();
+        }
+        // Bare identifiers name themselves: TS enum members, JS/Python parameters
+        if matches!(node.kind(), "identifier" | "property_identifier") {
+            return vec![*node];
+        }
+        let mut declared = Vec::new();
+        for field in ["name", "declarator", "pattern", "left", "property"] {
+            let mut cursor = node.walk();
+            declared.extend(node.children_by_field_name(field, &mut cursor));
+            if !declared.is_empty() {
+                break;
+            }
+        }
+        // Python typed and splat parameters, Java varargs, C# variables
+        if declared.is_empty() {
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.named_children(&mut cursor).collect();
+            declared.extend(
+                children
+                    .iter()
+                    .filter(|c| c.kind() == "variable_declarator"),
+            );
+            if declared.is_empty() {
+                declared.extend(children.into_iter().find(|c| c.kind() == "identifier"));
+            }
+        }
+        declared
+            .into_iter()
+            .filter_map(|n| declared_name(&n))
+            .collect()
+    }
+
+    /// Narrow a kind where one node type covers several kinds, or rule the
+    /// node out when it declares no symbol
+    fn refine_kind(&self, node: &tree_sitter::Node, kind: NodeKind) -> Option<NodeKind> {
+        match (self.language, node.kind(), kind) {
+            // Out-of-line `Foo::bar` definitions are methods
+            (Language::Cpp, "function_definition", NodeKind::Function)
+                if self.external_owner(node).is_some() =>
+            {
+                Some(NodeKind::Method)
+            }
+            // Function and class expressions are symbols when something names
+            // them; anonymous callbacks belong to the enclosing function
+            (_, "arrow_function" | "function_expression" | "class", _) if self.is_js() => {
+                match self.binding_site(node) {
+                    Some(binding) if binding.in_class => Some(NodeKind::Method),
+                    Some(_) => Some(kind),
+                    None => node.child_by_field_name("name").map(|_| kind),
+                }
+            }
+            // `handle = () => {}` in a class body is a method, not a property
+            (_, "field_definition" | "public_field_definition", NodeKind::Property)
+                if node
+                    .child_by_field_name("value")
+                    .is_some_and(|v| is_function_value(v.kind())) =>
+            {
+                None
+            }
+            // C++ member declarations are methods when they declare a function
+            (Language::Cpp, "field_declaration", _) if self.function_declarator(node).is_some() => {
+                Some(NodeKind::Method)
+            }
+            (Language::Java, "field_declaration", _) => {
+                let modifiers = node
+                    .named_child(0)
+                    .filter(|m| m.kind() == "modifiers")
+                    .map(|m| self.get_node_text(&m))
+                    .unwrap_or_default();
+                let words: Vec<&str> = modifiers.split_whitespace().collect();
+                match words.contains(&"static") && words.contains(&"final") {
+                    true => Some(NodeKind::Constant),
+                    false => Some(kind),
+                }
+            }
+            (Language::CSharp, "field_declaration", _) if self.has_modifier(node, "const") => {
+                Some(NodeKind::Constant)
+            }
+            // Python @property methods
+            (Language::Python, "function_definition", _) => {
+                let is_property = self.extract_decorators(node).iter().any(|(_, d)| {
+                    matches!(
+                        d.name.rsplit('.').next(),
+                        Some("property" | "cached_property")
+                    )
+                });
+                Some(if is_property {
+                    NodeKind::Property
+                } else {
+                    kind
+                })
+            }
+            (_, _, NodeKind::Parameter) => {
+                let list = node.parent()?;
+                // `x => x * 2`
+                if list.kind() == "arrow_function" {
+                    let is_parameter = list.child_by_field_name("parameter") == Some(*node);
+                    return (is_parameter && self.symbol_kind(&list).is_some()).then_some(kind);
+                }
+                // Python methods name their receiver
+                if self.language == Language::Python
+                    && list.named_child(0) == Some(*node)
+                    && matches!(self.get_node_text(node).as_str(), "self" | "cls")
+                {
+                    return None;
+                }
+                self.declares_parameters(&list).then_some(kind)
+            }
+            _ => Some(kind),
+        }
+    }
+
+    /// Name of the type a method defined outside of its body belongs to:
+    /// `impl Foo { fn bar() }`, Go `func (s *Server) Run()` and C++
+    /// `void Foo::bar() {}`
+    fn external_owner(&self, node: &tree_sitter::Node) -> Option<String> {
+        let owner = match (self.language, node.kind()) {
+            (Language::Rust, "function_item" | "function_signature_item") => {
+                let block = node.parent().filter(|p| p.kind() == "declaration_list")?;
+                let impl_item = block.parent().filter(|p| p.kind() == "impl_item")?;
+                self.get_node_text(&impl_item.child_by_field_name("type")?)
+            }
+            (Language::Go, "method_declaration") => {
+                let receiver = node.child_by_field_name("receiver")?;
+                let param = receiver.named_child(0)?;
+                self.get_node_text(&param.child_by_field_name("type")?)
+            }
+            (Language::Cpp, "function_definition") => {
+                let declarator = self.function_declarator(node)?;
+                let (scope, _) = split_qualified(declarator.child_by_field_name("declarator")?)?;
+                // `ns::helper()` defines a free function of a namespace
+                let scope = self.get_node_text(&scope);
+                let is_namespace = self
+                    .result
+                    .nodes
+                    .iter()
+                    .any(|n| n.kind == NodeKind::Module && n.name == scope);
+                if is_namespace {
+                    return None;
+                }
+                scope
+            }
+            _ => return None,
+        };
+        // `*Server`, `Server[T]`, `Foo<T>`
+        let owner = base_type_name(owner.split('[').next().unwrap_or(&owner));
+        is_identifier(&owner).then_some(owner)
+    }
+
+    fn is_js(&self) -> bool {
+        matches!(
+            self.language,
+            Language::TypeScript | Language::Tsx | Language::JavaScript | Language::Jsx
+        )
+    }
+
+    /// Where a JS/TS function or class expression is bound, which names it:
+    /// `const f = () => {}`, `exports.f = function () {}`, `{ f: () => {} }`,
+    /// `f = () => {}` in a class body, or `export default`
+    fn binding_site(&self, node: &tree_sitter::Node) -> Option<Binding> {
+        if !self.is_js()
+            || !matches!(
+                node.kind(),
+                "arrow_function" | "function_expression" | "class"
+            )
+        {
+            return None;
+        }
+        let mut value = *node;
+        let mut parent = node.parent()?;
+        while parent.kind() == "parenthesized_expression" {
+            value = parent;
+            parent = parent.parent()?;
+        }
+        let binds = |field: &str| parent.child_by_field_name(field) == Some(value);
+        let text = |field: &str| {
+            parent
+                .child_by_field_name(field)
+                .map(|n| self.get_node_text(&n))
+        };
+        let binding = |name: String, exported: bool, in_class: bool| {
+            is_identifier(&name).then_some(Binding {
+                name,
+                exported,
+                in_class,
+            })
+        };
+
+        match parent.kind() {
+            "variable_declarator" if binds("value") => {
+                let exported = parent
+                    .parent()
+                    .and_then(|d| d.parent())
+                    .is_some_and(|e| e.kind() == "export_statement");
+                binding(text("name")?, exported, false)
+            }
+            "assignment_expression" if binds("right") => {
+                let left = parent.child_by_field_name("left")?;
+                if left.kind() != "member_expression" {
+                    return binding(self.get_node_text(&left), false, false);
+                }
+                // module.exports = function () {} is the module's default export
+                if self.get_node_text(&left) == "module.exports" {
+                    let name = node
+                        .child_by_field_name("name")
+                        .map(|n| self.get_node_text(&n))
+                        .unwrap_or_else(|| "default".to_string());
+                    return binding(name, true, false);
+                }
+                let object = left.child_by_field_name("object")?;
+                let property = left.child_by_field_name("property")?;
+                let exported = matches!(
+                    self.get_node_text(&object).as_str(),
+                    "exports" | "module.exports"
+                );
+                binding(self.get_node_text(&property), exported, false)
+            }
+            "pair" if binds("value") => {
+                let key = text("key")?;
+                binding(key.trim_matches(['"', '\'', '`']).to_string(), false, false)
+            }
+            "field_definition" | "public_field_definition" if binds("value") => {
+                binding(text("property").or_else(|| text("name"))?, false, true)
+            }
+            "export_statement" => binding("default".to_string(), true, false),
+            _ => None,
+        }
+    }
+
+    /// Where the binding of a JS/TS function expression starts, which heads
+    /// its signature: `handleSubmit = async (event)`, `exports.parse = text`,
+    /// `post: function ()`
+    fn binding_start(&self, node: &tree_sitter::Node) -> Option<usize> {
+        if !self.is_js() || !is_function_value(node.kind()) {
+            return None;
+        }
+        let mut value = *node;
+        let mut parent = node.parent()?;
+        while parent.kind() == "parenthesized_expression" {
+            value = parent;
+            parent = parent.parent()?;
+        }
+        let binds = |field: &str| parent.child_by_field_name(field) == Some(value);
+        let site = match parent.kind() {
+            "variable_declarator" | "pair" if binds("value") => parent,
+            "assignment_expression" if binds("right") => parent,
+            // After any decorators and modifiers of the field
+            "field_definition" | "public_field_definition" if binds("value") => parent
+                .child_by_field_name("property")
+                .or_else(|| parent.child_by_field_name("name"))?,
+            _ => return None,
+        };
+        Some(site.start_byte())
+    }
+
+    /// Whether `list` is the parameter list of a function or method symbol,
+    /// rather than of a lambda, a function type or a Go receiver or result
+    fn declares_parameters(&self, list: &tree_sitter::Node) -> bool {
+        let Some(mut owner) = list.parent() else {
+            return false;
+        };
+        if owner.child_by_field_name("parameters") != Some(*list) {
+            return false;
+        }
+        // C/C++: the function declarator sits below the definition
+        while is_declarator_wrapper(owner.kind()) {
+            match owner.parent() {
+                Some(parent) => owner = parent,
+                None => return false,
+            }
+        }
+        matches!(
+            self.symbol_kind(&owner),
+            Some(NodeKind::Function | NodeKind::Method | NodeKind::Property)
+        )
     }
 
     fn extract_name(&self, node: &tree_sitter::Node, _kind: NodeKind) -> String {
+        if let Some(name) = self.captures.definition(node).and_then(|d| d.name) {
+            return self.get_node_text(&name);
+        }
+        // Function and class expressions are named where they are bound
+        if self.is_js()
+            && matches!(
+                node.kind(),
+                "arrow_function" | "function_expression" | "class"
+            )
+        {
+            return match self.binding_site(node) {
+                Some(binding) => binding.name,
+                None => node
+                    .child_by_field_name("name")
+                    .map(|n| self.get_node_text(&n))
+                    .unwrap_or_default(),
+            };
+        }
+
         // Try to find name child
         for field_name in &["name", "declarator", "identifier"] {
             if let Some(name_node) = node.child_by_field_name(field_name) {
                 let name = self.get_node_text(&name_node);
                 if !name.is_empty() {
-                    // Handle pointer declarators in C/C++
-                    if name_node.kind() == "pointer_declarator"
-                        || name_node.kind() == "function_declarator"
-                    {
-                        if let Some(id) = name_node.child_by_field_name("declarator") {
-                            return self.get_node_text(&id);
+                    // Handle pointer, reference and function declarators in C/C++
+                    if is_declarator_wrapper(name_node.kind()) {
+                        let mut inner = name_node;
+                        while is_declarator_wrapper(inner.kind()) {
+                            match inner_declarator(&inner) {
+                                Some(next) => inner = next,
+                                None => break,
+                            }
+                        }
+                        // C++ out-of-line definitions: `Foo::bar`
+                        if let Some((_, member)) = split_qualified(inner) {
+                            inner = member;
                         }
+                        return self.get_node_text(&inner);
                     }
                     return name;
                 }
@@ -251,133 +788,602 @@ impl<'a> ExtractionContext<'a> {
         String::new()
     }
 
+    /// The C/C++ function declarator of a definition or member declaration,
+    /// under any pointer or reference declarators
+    fn function_declarator<'t>(
+        &self,
+        node: &tree_sitter::Node<'t>,
+    ) -> Option<tree_sitter::Node<'t>> {
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while declarator.kind() != "function_declarator" {
+            if !is_declarator_wrapper(declarator.kind()) {
+                return None;
+            }
+            declarator = inner_declarator(&declarator)?;
+        }
+        Some(declarator)
+    }
+
+    /// The declaration up to its body, on one line: `pub fn get(&self, id: u32) -> Option<User>`
     fn extract_signature(&self, node: &tree_sitter::Node, kind: NodeKind) -> Option<String> {
-        match kind {
-            NodeKind::Function | NodeKind::Method => {
-                // Get the first line or until opening brace
-                let text = self.get_node_text(node);
-                let sig = text.lines().next().unwrap_or("");
-                // Truncate at opening brace or newline
-                let sig = sig.split('{').next().unwrap_or(sig).trim();
-                if sig.len() > 200 {
-                    Some(format!("{}...", &sig[..200]))
+        let function = matches!(kind, NodeKind::Function | NodeKind::Method);
+        let header_type = matches!(
+            kind,
+            NodeKind::Class | NodeKind::Struct | NodeKind::Interface | NodeKind::Trait
+        );
+        if !function && !header_type {
+            return None;
+        }
+        let start = self
+            .binding_start(node)
+            .unwrap_or_else(|| self.header_start(node));
+        let text = match node.child_by_field_name("body") {
+            Some(body) if body.start_byte() > start => &self.content[start..body.start_byte()],
+            // Declarations without a body, such as prototypes and trait methods
+            _ if function => &self.content[start..node.end_byte()],
+            _ => {
+                let text = &self.content[start..node.end_byte()];
+                let line = text.lines().next().unwrap_or_default();
+                line.split('{').next().unwrap_or(line)
+            }
+        };
+        let sig = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let sig = sig.trim_end_matches(['{', ':', ';', ' ']);
+        let sig = sig.strip_suffix("=>").unwrap_or(sig).trim_end();
+        Some(sig.to_string())
+    }
+
+    /// Where the declaration's header starts, after any decorators,
+    /// annotations and attributes it contains
+    fn header_start(&self, node: &tree_sitter::Node) -> usize {
+        let is_annotation = |kind: &str| {
+            matches!(
+                kind,
+                "decorator"
+                    | "annotation"
+                    | "marker_annotation"
+                    | "attribute_item"
+                    | "attribute_list"
+            ) || kind.contains("comment")
+        };
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if is_annotation(child.kind()) {
+                continue;
+            }
+            // Java keeps annotations among its modifiers
+            if child.kind() == "modifiers" {
+                let mut inner = child.walk();
+                let first = child
+                    .children(&mut inner)
+                    .find(|c| !is_annotation(c.kind()));
+                match first {
+                    Some(first) => return first.start_byte(),
+                    None => continue,
+                }
+            }
+            return child.start_byte();
+        }
+        node.start_byte()
+    }
+
+    /// Visibility from the declaration's modifiers, or the language's
+    /// default for where it is declared
+    fn extract_visibility(&self, node: &tree_sitter::Node, name: &str) -> Visibility {
+        let declaration = self.declaration_node(node);
+        for keyword in self.modifier_keywords(&declaration) {
+            let visibility = match keyword.as_str() {
+                "pub" => Visibility::Public,
+                // pub(crate), pub(super), pub(in path)
+                "pub(self)" => Visibility::Private,
+                k if k.starts_with("pub(") => Visibility::Internal,
+                k => Visibility::parse(k),
+            };
+            if visibility != Visibility::Unknown {
+                return visibility;
+            }
+        }
+
+        let parent = declaration.parent();
+        let parent_kind = parent.map(|p| p.kind()).unwrap_or_default();
+        match self.language {
+            Language::Rust => {
+                // Trait items and enum variants are as visible as their owner
+                let owner = match (node.kind(), parent.and_then(|p| p.parent())) {
+                    ("enum_variant", Some(owner)) => Some(owner),
+                    (_, Some(owner)) if parent_kind == "declaration_list" => {
+                        Some(owner).filter(|o| o.kind() == "trait_item")
+                    }
+                    _ => None,
+                };
+                match owner {
+                    Some(owner) => self.extract_visibility(&owner, name),
+                    // Methods of trait impls are as visible as the trait
+                    None if self.implemented_trait(node).is_some() => Visibility::Public,
+                    None => Visibility::Private,
+                }
+            }
+            Language::Go => match name.starts_with(char::is_uppercase) {
+                true => Visibility::Public,
+                false => Visibility::Private,
+            },
+            // _private and __mangled names; __dunder__ names are public
+            Language::Python => {
+                let dunder = name.starts_with("__") && name.ends_with("__");
+                match name.starts_with('_') && !dunder {
+                    true => Visibility::Private,
+                    false => Visibility::Public,
+                }
+            }
+            Language::TypeScript | Language::JavaScript | Language::Tsx | Language::Jsx => {
+                if name.starts_with('#') {
+                    Visibility::Private
+                } else if matches!(
+                    parent_kind,
+                    "class_body" | "object_type" | "interface_body" | "object" | "enum_body"
+                ) || self.check_exported(node, name)
+                {
+                    Visibility::Public
                 } else {
-                    Some(sig.to_string())
+                    // Top-level declarations without export are module-private
+                    Visibility::Private
                 }
             }
-            NodeKind::Class | NodeKind::Struct | NodeKind::Interface | NodeKind::Trait => {
-                let text = self.get_node_text(node);
-                let sig = text.lines().next().unwrap_or("");
-                let sig = sig.split('{').next().unwrap_or(sig).trim();
-                Some(sig.to_string())
+            // Interface members and enum constants are public, the rest of
+            // Java is package-private by default
+            Language::Java => match parent_kind {
+                "interface_body" | "annotation_type_body" | "enum_body" => Visibility::Public,
+                _ => Visibility::Internal,
+            },
+            // Interface and enum members and positional record parameters are
+            // public, other members private, and top-level types internal
+            Language::CSharp => {
+                let owner = parent.and_then(|p| p.parent());
+                match (parent_kind, owner.map(|o| o.kind()).unwrap_or_default()) {
+                    (_, "interface_declaration")
+                    | ("enum_member_declaration_list", _)
+                    | ("parameter_list", "record_declaration") => Visibility::Public,
+                    ("compilation_unit", _) | ("declaration_list", "namespace_declaration") => {
+                        Visibility::Internal
+                    }
+                    _ => Visibility::Private,
+                }
             }
-            _ => None,
+            Language::C | Language::Cpp => {
+                if parent_kind == "field_declaration_list" {
+                    return self.cpp_member_access(&declaration);
+                }
+                match self.has_modifier(&declaration, "static") {
+                    true => Visibility::Private,
+                    false => Visibility::Public,
+                }
+            }
+            _ => Visibility::Unknown,
         }
     }
 
-    fn extract_visibility(&self, node: &tree_sitter::Node) -> Visibility {
-        let text = self.get_node_text(node);
-        let first_line = text.lines().next().unwrap_or("");
-
-        if first_line.starts_with("pub ") || first_line.contains(" pub ") {
-            return Visibility::Public;
+    /// Access of a C++ class or struct member: the closest preceding
+    /// `public:`/`private:` label, else the class (private) or struct default
+    fn cpp_member_access(&self, member: &tree_sitter::Node) -> Visibility {
+        let mut sibling = member.prev_named_sibling();
+        while let Some(label) = sibling {
+            if label.kind() == "access_specifier" {
+                return Visibility::parse(self.get_node_text(&label).trim_end_matches(':').trim());
+            }
+            sibling = label.prev_named_sibling();
         }
-        if first_line.starts_with("public ") || first_line.contains(" public ") {
-            return Visibility::Public;
+        let owner = member.parent().and_then(|body| body.parent());
+        match owner.map(|o| o.kind()) {
+            Some("class_specifier") => Visibility::Private,
+            _ => Visibility::Public,
         }
-        if first_line.starts_with("private ") || first_line.contains(" private ") {
-            return Visibility::Private;
+    }
+
+    /// The node carrying a declaration's modifiers: the class field or
+    /// variable a JS/TS function expression is bound to, else the node itself
+    fn declaration_node<'t>(&self, node: &tree_sitter::Node<'t>) -> tree_sitter::Node<'t> {
+        if self.binding_site(node).is_some() {
+            let mut parent = node.parent();
+            while let Some(p) = parent.filter(|p| p.kind() == "parenthesized_expression") {
+                parent = p.parent();
+            }
+            if let Some(p) = parent.filter(|p| p.kind().ends_with("field_definition")) {
+                return p;
+            }
         }
-        if first_line.starts_with("protected ") || first_line.contains(" protected ") {
-            return Visibility::Protected;
+        // C++ template members keep their access label outside the template
+        match node.parent() {
+            Some(p) if p.kind() == "template_declaration" => p,
+            _ => *node,
         }
+    }
 
-        // Check for visibility modifier child
+    /// Modifier keywords written on a declaration, in order: `pub(crate)`,
+    /// `async`, `static`, `private`. Only the declaration's own header is
+    /// read, never its body.
+    fn modifier_keywords(&self, node: &tree_sitter::Node) -> Vec<String> {
+        let mut keywords = Vec::new();
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            let kind = child.kind();
-            if kind == "visibility_modifier" || kind == "access_specifier" {
-                let vis_text = self.get_node_text(&child);
-                return Visibility::parse(&vis_text);
+            match child.kind() {
+                "visibility_modifier" | "accessibility_modifier" | "storage_class_specifier" => {
+                    keywords.push(self.get_node_text(&child).split_whitespace().collect());
+                }
+                // C# modifiers come one at a time
+                "modifier" => keywords.push(self.get_node_text(&child)),
+                // Java modifiers, Rust `async fn`/`const fn`
+                "modifiers" | "function_modifiers" => {
+                    let mut inner = child.walk();
+                    keywords.extend(
+                        child
+                            .children(&mut inner)
+                            .filter(|m| !m.is_named())
+                            .map(|m| m.kind().to_string()),
+                    );
+                }
+                _ if !child.is_named() => keywords.push(child.kind().to_string()),
+                _ => {}
             }
         }
+        keywords
+    }
 
-        // Check for export keyword (JS/TS)
-        if let Some(parent) = node.parent() {
-            if parent.kind() == "export_statement" {
-                return Visibility::Public;
+    fn has_modifier(&self, node: &tree_sitter::Node, keyword: &str) -> bool {
+        self.modifier_keywords(node).iter().any(|k| k == keyword)
+    }
+
+    /// Documentation of a declaration and its structured parts. Python reads
+    /// the docstring at the start of the body; other languages read the doc
+    /// comments directly above the declaration.
+    fn extract_docs(&self, node: &tree_sitter::Node) -> (Option<String>, Option<DocTags>) {
+        let doc = match self.language {
+            Language::Python => self.python_docstring(node),
+            _ => self.doc_comments(node),
+        };
+        let doc = doc.filter(|d| !d.trim().is_empty());
+        let tags = doc
+            .as_deref()
+            .and_then(|d| docs::parse_tags(self.language, d));
+        (doc, tags)
+    }
+
+    fn python_docstring(&self, node: &tree_sitter::Node) -> Option<String> {
+        if !matches!(node.kind(), "function_definition" | "class_definition") {
+            return None;
+        }
+        let body = node.child_by_field_name("body")?;
+        let first = body.named_child(0)?;
+        let string = first.named_child(0)?;
+        (first.kind() == "expression_statement" && string.kind() == "string")
+            .then(|| docs::python_docstring(&self.get_node_text(&string)))
+    }
+
+    /// Doc comments above a declaration. Comments may sit above a wrapper
+    /// (`export`, `const x = () => {}`, `template <...>`, a Go `type` group),
+    /// so wrappers are climbed until comments are found.
+    fn doc_comments(&self, node: &tree_sitter::Node) -> Option<String> {
+        let mut anchor = self.declaration_node(node);
+        loop {
+            let comments = self.preceding_comments(&anchor);
+            if !comments.is_empty() {
+                return self.doc_text(&comments);
+            }
+            let parent = anchor.parent()?;
+            let wraps = match parent.kind() {
+                // The declaration is the value of a binding or follows template parameters
+                "variable_declarator"
+                | "assignment_expression"
+                | "pair"
+                | "template_declaration" => true,
+                "export_statement"
+                | "lexical_declaration"
+                | "variable_declaration"
+                | "expression_statement"
+                | "type_declaration"
+                | "const_declaration"
+                | "var_declaration" => parent.named_child(0) == Some(anchor),
+                _ => false,
+            };
+            if !wraps {
+                return None;
             }
+            anchor = parent;
         }
+    }
 
-        // Language-specific defaults
-        match self.language {
-            Language::Rust => Visibility::Private,
-            Language::Go | Language::Python => Visibility::Public,
-            Language::TypeScript | Language::JavaScript | Language::Tsx | Language::Jsx => {
-                // In JS/TS, top-level functions without export are module-private
-                Visibility::Private
+    /// The unbroken run of comments and attributes ending on the line above
+    /// `node`, in source order. A blank line or any other sibling ends the run,
+    /// as does a comment trailing code on its line.
+    fn preceding_comments<'t>(&self, node: &tree_sitter::Node<'t>) -> Vec<tree_sitter::Node<'t>> {
+        // Line comments may include their newline
+        let last_row = |n: &tree_sitter::Node| match n.end_position() {
+            end if end.column == 0 && end.row > n.start_position().row => end.row - 1,
+            end => end.row,
+        };
+        let mut run = Vec::new();
+        let mut next_row = node.start_position().row;
+        let mut prev = node.prev_sibling();
+        while let Some(sibling) = prev {
+            let is_comment = sibling.kind().contains("comment");
+            let is_attribute = matches!(sibling.kind(), "attribute_item" | "decorator");
+            if !(is_comment || is_attribute) || last_row(&sibling) + 1 < next_row {
+                break;
             }
-            _ => Visibility::Unknown,
+            let trailing = sibling
+                .prev_sibling()
+                .is_some_and(|p| last_row(&p) == sibling.start_position().row);
+            if is_comment && trailing {
+                break;
+            }
+            run.push(sibling);
+            next_row = sibling.start_position().row;
+            prev = sibling.prev_sibling();
+        }
+        run.reverse();
+        run
+    }
+
+    /// The documentation among a run of comments and attributes, by the
+    /// doc-comment convention of the language
+    fn doc_text(&self, run: &[tree_sitter::Node]) -> Option<String> {
+        let texts: Vec<String> = match self.language {
+            // /// and /** */ doc comments and #[doc = "..."] attributes
+            Language::Rust => run
+                .iter()
+                .filter_map(|n| {
+                    let text = self.get_node_text(n);
+                    match n.kind() {
+                        "attribute_item" => doc_attribute(&text),
+                        _ if text.starts_with("///") && !text.starts_with("////") => {
+                            Some(docs::comment_text(&text))
+                        }
+                        _ if text.starts_with("/**") && !text.starts_with("/***") => {
+                            Some(docs::comment_text(&text))
+                        }
+                        _ => None,
+                    }
+                })
+                .collect(),
+            // The closest /** */ block
+            Language::TypeScript
+            | Language::Tsx
+            | Language::JavaScript
+            | Language::Jsx
+            | Language::Java => run
+                .iter()
+                .rev()
+                .map(|n| self.get_node_text(n))
+                .find(|text| text.starts_with("/**") && text != "/**/")
+                .map(|text| docs::comment_text(&text))
+                .into_iter()
+                .collect(),
+            // /// lines, or a /** */ block
+            Language::CSharp => run
+                .iter()
+                .map(|n| self.get_node_text(n))
+                .filter(|text| {
+                    (text.starts_with("///") && !text.starts_with("////"))
+                        || (text.starts_with("/**") && text != "/**/")
+                })
+                .map(|text| docs::comment_text(&text))
+                .collect(),
+            // Every comment line except directives such as //go:generate
+            Language::Go => run
+                .iter()
+                .map(|n| self.get_node_text(n))
+                .filter(|text| !is_go_directive(text))
+                .map(|text| docs::comment_text(&text))
+                .collect(),
+            _ => run
+                .iter()
+                .filter(|n| n.kind().contains("comment"))
+                .map(|n| docs::comment_text(&self.get_node_text(n)))
+                .collect(),
+        };
+        let text = texts.join("\n");
+        (!text.trim().is_empty()).then(|| text.trim_matches('\n').to_string())
+    }
+
+    /// Whether a symbol's source has syntax errors, or it was recovered from
+    /// inside an `ERROR` node
+    fn has_parse_errors(&self, node: &tree_sitter::Node) -> bool {
+        if node.has_error() {
+            return true;
+        }
+        let mut parent = node.parent();
+        while let Some(p) = parent {
+            if p.is_error() {
+                return true;
+            }
+            parent = p.parent();
         }
+        false
     }
 
-    fn extract_docstring(&self, node: &tree_sitter::Node) -> Option<String> {
-        // Look for comment before this node
-        if let Some(prev) = node.prev_sibling() {
-            let kind = prev.kind();
-            if kind.contains("comment") || kind == "doc_comment" || kind == "block_comment" {
-                let text = self.get_node_text(&prev);
-                return Some(self.clean_docstring(&text));
+    /// Decorators, annotations and attributes applied to a declaration, with
+    /// the syntax node each was read from
+    fn extract_decorators<'t>(
+        &self,
+        node: &tree_sitter::Node<'t>,
+    ) -> Vec<(tree_sitter::Node<'t>, Decorator)> {
+        let mut found = Vec::new();
+        match self.language {
+            // #[attr] items precede the item, possibly mixed with doc comments
+            Language::Rust => {
+                let mut prev = node.prev_named_sibling();
+                while let Some(sibling) = prev {
+                    match sibling.kind() {
+                        "attribute_item" => found.push(sibling),
+                        "line_comment" | "block_comment" => {}
+                        _ => break,
+                    }
+                    prev = sibling.prev_named_sibling();
+                }
+                found.reverse();
+            }
+            // @decorator lines are children of the wrapping decorated_definition
+            Language::Python => {
+                if let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") {
+                    let mut cursor = parent.walk();
+                    found.extend(
+                        parent
+                            .named_children(&mut cursor)
+                            .filter(|c| c.kind() == "decorator"),
+                    );
+                }
+            }
+            // Annotations are modifiers
+            Language::Java => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    if child.kind() == "modifiers" {
+                        let mut modifiers = child.walk();
+                        found.extend(
+                            child
+                                .named_children(&mut modifiers)
+                                .filter(|m| matches!(m.kind(), "marker_annotation" | "annotation")),
+                        );
+                    }
+                }
+            }
+            // [Attr] lists open the declaration
+            Language::CSharp => {
+                let mut cursor = node.walk();
+                for list in node.named_children(&mut cursor) {
+                    if list.kind() == "attribute_list" {
+                        let mut attributes = list.walk();
+                        found.extend(
+                            list.named_children(&mut attributes)
+                                .filter(|a| a.kind() == "attribute"),
+                        );
+                    }
+                }
+            }
+            // Decorators precede a class member or the declaration in an export
+            // statement, or are children of the declaration itself
+            Language::TypeScript | Language::Tsx | Language::JavaScript | Language::Jsx => {
+                let mut preceding = Vec::new();
+                let mut prev = node.prev_named_sibling();
+                while let Some(sibling) = prev.filter(|s| s.kind() == "decorator") {
+                    preceding.push(sibling);
+                    prev = sibling.prev_named_sibling();
+                }
+                found.extend(preceding.into_iter().rev());
+                let mut cursor = node.walk();
+                found.extend(
+                    node.named_children(&mut cursor)
+                        .filter(|c| c.kind() == "decorator"),
+                );
             }
+            _ => {}
         }
-        None
+
+        found
+            .into_iter()
+            .filter_map(|at| self.parse_decorator(&at).map(|d| (at, d)))
+            // Doc attributes are documentation, not behaviour
+            .filter(|(_, d)| !(self.language == Language::Rust && d.name == "doc"))
+            .collect()
     }
 
-    fn clean_docstring(&self, text: &str) -> String {
-        text.lines()
-            .map(|line| {
-                line.trim()
-                    .trim_start_matches("///")
-                    .trim_start_matches("//!")
-                    .trim_start_matches("//")
-                    .trim_start_matches("/**")
-                    .trim_start_matches("/*")
-                    .trim_start_matches('*')
-                    .trim_end_matches("*/")
-                    .trim()
-            })
-            .filter(|line| !line.is_empty())
-            .collect::<Vec<_>>()
-            .join("\n")
+    /// Split a decorator, annotation or attribute node into its name and arguments
+    fn parse_decorator(&self, node: &tree_sitter::Node) -> Option<Decorator> {
+        let (name, arguments) = match node.kind() {
+            // #[path(args)] or #[path = value]
+            "attribute_item" => {
+                let attribute = node.named_child(0)?;
+                let arguments = attribute
+                    .child_by_field_name("arguments")
+                    .or_else(|| attribute.child_by_field_name("value"));
+                (attribute.named_child(0)?, arguments)
+            }
+            "marker_annotation" | "annotation" => (
+                node.child_by_field_name("name")?,
+                node.child_by_field_name("arguments"),
+            ),
+            // C#: [Name(args)]
+            "attribute" if self.language == Language::CSharp => {
+                let mut cursor = node.walk();
+                let arguments = node
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "attribute_argument_list");
+                (node.child_by_field_name("name")?, arguments)
+            }
+            // Python and JS/TS: @expr or @expr(args)
+            _ => {
+                let expr = node.named_child(0)?;
+                match expr.child_by_field_name("function") {
+                    Some(function) => (function, expr.child_by_field_name("arguments")),
+                    None => (expr, None),
+                }
+            }
+        };
+        let arguments = arguments
+            .map(|a| strip_delimiters(&self.get_node_text(&a)).to_string())
+            .filter(|a| !a.is_empty());
+        Some(Decorator {
+            name: self.get_node_text(&name),
+            arguments,
+        })
     }
 
     fn check_async(&self, node: &tree_sitter::Node) -> bool {
-        let text = self.get_node_text(node);
-        text.starts_with("async ") || text.contains(" async ")
+        self.has_modifier(node, "async")
     }
 
     fn check_static(&self, node: &tree_sitter::Node) -> bool {
-        let text = self.get_node_text(node);
-        text.starts_with("static ") || text.contains(" static ")
-    }
-
-    fn check_exported(&self, node: &tree_sitter::Node) -> bool {
-        let text = self.get_node_text(node);
-        // Rust pub
-        if text.starts_with("pub ") {
+        let declaration = self.declaration_node(node);
+        if self.has_modifier(&declaration, "static") {
             return true;
         }
-        // JS/TS export
-        if text.starts_with("export ") {
-            return true;
+        match self.language {
+            Language::Python => self
+                .extract_decorators(node)
+                .iter()
+                .any(|(_, d)| d.name == "staticmethod"),
+            // Associated functions take no `self`
+            Language::Rust => {
+                let is_associated = self.external_owner(node).is_some()
+                    || node
+                        .parent()
+                        .and_then(|p| p.parent())
+                        .is_some_and(|p| p.kind() == "trait_item");
+                let takes_self = node
+                    .child_by_field_name("parameters")
+                    .is_some_and(|params| {
+                        let mut cursor = params.walk();
+                        let mut params = params.named_children(&mut cursor);
+                        params.any(|p| p.kind() == "self_parameter")
+                    });
+                is_associated && !takes_self && node.kind().starts_with("function")
+            }
+            _ => false,
         }
-        // Check for export default
-        if let Some(parent) = node.parent() {
-            if parent.kind() == "export_statement" {
-                return true;
+    }
+
+    /// Whether the symbol is visible outside its module: JS/TS `export`,
+    /// Python public module-level names, Go capitalized names, and public
+    /// declarations elsewhere
+    fn check_exported(&self, node: &tree_sitter::Node, name: &str) -> bool {
+        if let Some(binding) = self.binding_site(node) {
+            return binding.exported;
+        }
+        match self.language {
+            Language::TypeScript | Language::JavaScript | Language::Tsx | Language::Jsx => node
+                .parent()
+                .is_some_and(|p| p.kind() == "export_statement"),
+            Language::Python => {
+                let mut ancestor = node.parent();
+                while let Some(a) = ancestor {
+                    if a.kind() == "function_definition" {
+                        return false;
+                    }
+                    ancestor = a.parent();
+                }
+                self.extract_visibility(node, name) == Visibility::Public
             }
+            _ => self.extract_visibility(node, name) == Visibility::Public,
         }
-        false
     }
 
     fn build_qualified_name(&self, name: &str) -> Option<String> {
@@ -390,468 +1396,3833 @@ impl<'a> ExtractionContext<'a> {
             }
         }
         parts.push(name.to_string());
-        Some(parts.join("::"))
-    }
-
-    fn extract_references(&mut self, node: &tree_sitter::Node, source_id: i64) {
-        // Find call expressions within this node
-        self.find_calls(node, source_id);
+        let qualified = parts.join("::");
+        match self.language {
+            // C# namespaces are dotted: `namespace Shop.Orders`
+            Language::CSharp => Some(qualified.replace('.', "::")),
+            _ => Some(qualified),
+        }
     }
 
-    fn find_calls(&mut self, node: &tree_sitter::Node, source_id: i64) {
-        let kind = node.kind();
-
-        if self.config.is_call_node(kind) {
-            if let Some(func_name) = self.extract_call_name(node) {
-                let start = node.start_position();
-                let uref = UnresolvedReference {
-                    source_node_id: source_id,
-                    reference_name: func_name,
-                    kind: EdgeKind::Calls,
-                    file_path: self.file_path.clone(),
-                    line: start.row as u32 + 1,
-                    column: start.column as u32,
-                };
-                self.result.unresolved_refs.push(uref);
-            }
+    /// Emit Extends/Implements references for the base types of a declaration
+    fn extract_inheritance(&mut self, node: &tree_sitter::Node, kind: NodeKind, source_id: i64) {
+        if !kind.is_type() {
+            return;
         }
 
-        // Recurse into children
+        let mut clauses: Vec<(tree_sitter::Node, EdgeKind)> = Vec::new();
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.find_calls(&child, source_id);
+            match (self.language, child.kind()) {
+                // trait A: B + C
+                (Language::Rust, "trait_bounds") => clauses.push((child, EdgeKind::Extends)),
+                // class A extends B implements C / interface I extends J
+                (_, "class_heritage") => {
+                    let mut heritage = child.walk();
+                    for clause in child.named_children(&mut heritage) {
+                        match clause.kind() {
+                            "extends_clause" => clauses.push((clause, EdgeKind::Extends)),
+                            "implements_clause" => clauses.push((clause, EdgeKind::Implements)),
+                            // JavaScript has no extends_clause wrapper
+                            _ => clauses.push((child, EdgeKind::Extends)),
+                        }
+                    }
+                }
+                (_, "extends_type_clause") | (_, "extends_interfaces") | (_, "superclass") => {
+                    clauses.push((child, EdgeKind::Extends))
+                }
+                (_, "super_interfaces") => clauses.push((child, EdgeKind::Implements)),
+                // class A(B, C)
+                (Language::Python, "argument_list") => clauses.push((child, EdgeKind::Extends)),
+                // class A : public B
+                (_, "base_class_clause") => clauses.push((child, EdgeKind::Extends)),
+                // class A : B, IC; resolution tells interfaces from the base
+                // class, which structs don't have
+                (Language::CSharp, "base_list") => match kind {
+                    NodeKind::Struct => clauses.push((child, EdgeKind::Implements)),
+                    _ => clauses.push((child, EdgeKind::Extends)),
+                },
+                // Go embedded struct fields and interface elements
+                (Language::Go, "struct_type") | (Language::Go, "interface_type") => {
+                    clauses.push((child, EdgeKind::Extends))
+                }
+                _ => {}
+            }
         }
-    }
+
+        for (clause, edge_kind) in clauses {
+            for type_node in self.base_type_nodes(&clause) {
+                let name = base_type_name(&self.get_node_text(&type_node));
+                if name.is_empty() {
+                    continue;
+                }
+                self.push_reference(source_id, None, name, edge_kind, &type_node, None);
+            }
+        }
+    }
+
+    /// Collect the type nodes named in an inheritance clause
+    fn base_type_nodes<'t>(&self, clause: &tree_sitter::Node<'t>) -> Vec<tree_sitter::Node<'t>> {
+        let mut types = Vec::new();
+        let mut cursor = clause.walk();
+        for child in clause.named_children(&mut cursor) {
+            match child.kind() {
+                "type_identifier"
+                | "identifier"
+                | "scoped_type_identifier"
+                | "generic_type"
+                | "member_expression"
+                | "nested_type_identifier"
+                | "attribute"
+                | "qualified_identifier"
+                | "template_type"
+                | "qualified_type"
+                | "generic_name"
+                | "qualified_name" => types.push(child),
+                // Java/TS wrap multiple types in a list
+                "type_list" => types.extend(self.base_type_nodes(&child)),
+                // Go embedding: a field without a name, or an interface type element
+                "field_declaration_list" => types.extend(self.base_type_nodes(&child)),
+                "field_declaration" if child.child_by_field_name("name").is_none() => {
+                    if let Some(ty) = child.child_by_field_name("type") {
+                        types.push(ty);
+                    }
+                }
+                "type_elem" => types.extend(self.base_type_nodes(&child)),
+                _ => {}
+            }
+        }
+        types
+    }
+
+    /// Relationships expressed outside of a type declaration
+    fn extract_relationship_hints(&mut self, node: &tree_sitter::Node) {
+        let Some(&anchor_id) = self.node_stack.last() else {
+            return;
+        };
+
+        // `impl Trait for Type`, Go `var _ Iface = (*Type)(nil)`; without a
+        // `@type`, the enclosing symbol is the implementer
+        if let Some(reference) = self
+            .captures
+            .reference(node)
+            .filter(|r| r.kind == ReferenceKind::Implementation)
+        {
+            let Some(iface) = reference.name else {
+                return;
+            };
+            let iface_name = base_type_name(&self.get_node_text(&iface));
+            let type_name = reference
+                .type_node
+                .map(|t| base_type_name(self.get_node_text(&t).trim_matches(['(', ')', '*', '&'])));
+            if !iface_name.is_empty() && type_name.as_ref().is_none_or(|t| !t.is_empty()) {
+                self.push_reference(
+                    anchor_id,
+                    type_name,
+                    iface_name,
+                    EdgeKind::Implements,
+                    &iface,
+                    None,
+                );
+            }
+            return;
+        }
+
+        match (self.language, node.kind()) {
+            (Language::Rust, "use_declaration")
+            | (Language::TypeScript | Language::Tsx, "import_statement")
+            | (Language::JavaScript | Language::Jsx, "import_statement")
+            | (Language::Python, "import_from_statement")
+            | (Language::Java, "import_declaration") => {
+                for (name_node, name, module) in self.import_names(node) {
+                    self.push_reference(
+                        anchor_id,
+                        None,
+                        name,
+                        EdgeKind::Imports,
+                        &name_node,
+                        module,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Names brought into scope by an import statement, with the module they come from
+    fn import_names<'t>(
+        &self,
+        node: &tree_sitter::Node<'t>,
+    ) -> Vec<(tree_sitter::Node<'t>, String, Option<String>)> {
+        let mut names = Vec::new();
+        match node.kind() {
+            "use_declaration" => {
+                if let Some(argument) = node.child_by_field_name("argument") {
+                    self.collect_use_names(&argument, "", &mut names);
+                }
+            }
+            "import_statement" => {
+                let module = node.child_by_field_name("source").map(|s| {
+                    self.get_node_text(&s)
+                        .trim_matches(['"', '\'', '`'])
+                        .to_string()
+                });
+                let mut cursor = node.walk();
+                for clause in node.named_children(&mut cursor) {
+                    if clause.kind() != "import_clause" {
+                        continue;
+                    }
+                    let mut clause_cursor = clause.walk();
+                    for child in clause.named_children(&mut clause_cursor) {
+                        match child.kind() {
+                            // import Default from "..."
+                            "identifier" => {
+                                names.push((child, self.get_node_text(&child), module.clone()))
+                            }
+                            // import { a, b as c } from "..."
+                            "named_imports" => {
+                                let mut specifiers = child.walk();
+                                for spec in child.named_children(&mut specifiers) {
+                                    if let Some(name) = spec.child_by_field_name("name") {
+                                        names.push((
+                                            name,
+                                            self.get_node_text(&name),
+                                            module.clone(),
+                                        ));
+                                    }
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            "import_from_statement" => {
+                let module = node
+                    .child_by_field_name("module_name")
+                    .map(|m| self.get_node_text(&m));
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    // from m import Order as O
+                    let name = match name.kind() {
+                        "aliased_import" => match name.child_by_field_name("name") {
+                            Some(inner) => inner,
+                            None => continue,
+                        },
+                        _ => name,
+                    };
+                    let text = self.get_node_text(&name);
+                    let last = text.rsplit('.').next().unwrap_or(&text).to_string();
+                    names.push((name, last, module.clone()));
+                }
+            }
+            "import_declaration" => {
+                let mut cursor = node.walk();
+                let children: Vec<_> = node.named_children(&mut cursor).collect();
+                // Wildcard imports bring in no specific name
+                if children.iter().any(|c| c.kind() == "asterisk") {
+                    return names;
+                }
+                if let Some(path) = children.iter().find(|c| c.kind() == "scoped_identifier") {
+                    if let Some(name) = path.child_by_field_name("name") {
+                        let scope = path
+                            .child_by_field_name("scope")
+                            .map(|s| self.get_node_text(&s));
+                        names.push((name, self.get_node_text(&name), scope));
+                    }
+                }
+            }
+            _ => {}
+        }
+        names
+    }
+
+    /// Walk a Rust `use` tree, tracking the path prefix of nested lists
+    fn collect_use_names<'t>(
+        &self,
+        node: &tree_sitter::Node<'t>,
+        prefix: &str,
+        names: &mut Vec<(tree_sitter::Node<'t>, String, Option<String>)>,
+    ) {
+        let join = |path: String| {
+            if prefix.is_empty() {
+                path
+            } else {
+                format!("{}::{}", prefix, path)
+            }
+        };
+        let module = (!prefix.is_empty()).then(|| prefix.to_string());
+
+        match node.kind() {
+            "identifier" => names.push((*node, self.get_node_text(node), module)),
+            "scoped_identifier" => {
+                let path = node
+                    .child_by_field_name("path")
+                    .map(|p| join(self.get_node_text(&p)));
+                if let Some(name) = node.child_by_field_name("name") {
+                    names.push((name, self.get_node_text(&name), path.or(module)));
+                }
+            }
+            "use_as_clause" => {
+                if let Some(path) = node.child_by_field_name("path") {
+                    self.collect_use_names(&path, prefix, names);
+                }
+            }
+            "use_list" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.collect_use_names(&child, prefix, names);
+                }
+            }
+            "scoped_use_list" => {
+                let prefix = node
+                    .child_by_field_name("path")
+                    .map(|p| join(self.get_node_text(&p)))
+                    .unwrap_or_else(|| prefix.to_string());
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.collect_use_names(&list, &prefix, names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Display name of an import statement and the module paths it names.
+    /// Wrappers such as Go's `import (...)` get no name; their specs do.
+    fn import_modules(&self, node: &tree_sitter::Node) -> (String, Vec<String>) {
+        let unquote = |n: tree_sitter::Node| {
+            self.get_node_text(&n)
+                .trim_matches(['"', '\'', '`'])
+                .to_string()
+        };
+        let mut modules = Vec::new();
+        let name = match (self.language, node.kind()) {
+            (_, "use_declaration") => {
+                let Some(argument) = node.child_by_field_name("argument") else {
+                    return (String::new(), modules);
+                };
+                self.collect_use_paths(&argument, "", &mut modules);
+                let text = self.get_node_text(&argument);
+                text.split_whitespace().collect::<Vec<_>>().join(" ")
+            }
+            (Language::Python, "import_statement") => {
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    let path = match name.kind() {
+                        "aliased_import" => name.child_by_field_name("name"),
+                        _ => Some(name),
+                    };
+                    modules.extend(path.map(|p| self.get_node_text(&p)));
+                }
+                modules.join(", ")
+            }
+            (_, "import_from_statement") => {
+                let Some(module) = node.child_by_field_name("module_name") else {
+                    return (String::new(), modules);
+                };
+                let module = self.get_node_text(&module);
+                // `from pkg import mod` may name a submodule rather than a symbol
+                let mut cursor = node.walk();
+                for name in node.children_by_field_name("name", &mut cursor) {
+                    let path = match name.kind() {
+                        "aliased_import" => name.child_by_field_name("name"),
+                        _ => Some(name),
+                    };
+                    if let Some(path) = path {
+                        let separator = if module.ends_with('.') { "" } else { "." };
+                        modules.push(format!(
+                            "{}{}{}",
+                            module,
+                            separator,
+                            self.get_node_text(&path)
+                        ));
+                    }
+                }
+                if modules.is_empty() {
+                    modules.push(module.clone());
+                }
+                module
+            }
+            (_, "import_statement") => match node.child_by_field_name("source") {
+                Some(source) => unquote(source),
+                None => String::new(),
+            },
+            (Language::Go, "import_spec") => match node.child_by_field_name("path") {
+                Some(path) => unquote(path),
+                None => String::new(),
+            },
+            (Language::Java, "import_declaration") => {
+                let text = self.get_node_text(node);
+                let path = text
+                    .trim()
+                    .trim_start_matches("import")
+                    .trim()
+                    .trim_start_matches("static ")
+                    .trim_end_matches(';');
+                path.split_whitespace().collect()
+            }
+            // using System.Text; using static System.Math; using Json = System.Text.Json;
+            (Language::CSharp, "using_directive") => {
+                let mut cursor = node.walk();
+                let alias = node.child_by_field_name("name");
+                let path = node
+                    .named_children(&mut cursor)
+                    .find(|c| Some(*c) != alias && c.kind() != "comment");
+                match path {
+                    Some(path) => self.get_node_text(&path).split_whitespace().collect(),
+                    None => String::new(),
+                }
+            }
+            (_, "preproc_include") => match node.child_by_field_name("path") {
+                Some(path) => unquote(path),
+                None => String::new(),
+            },
+            _ => String::new(),
+        };
+        if modules.is_empty() && !name.is_empty() {
+            modules.push(name.clone());
+        }
+        modules.dedup();
+        (name, modules)
+    }
+
+    /// Rewrite a `self`/`super` path written inside inline `mod x { ... }`
+    /// blocks so that it is relative to the module of the file instead
+    fn rust_path_from_file(&self, node: &tree_sitter::Node, path: &str) -> String {
+        let mut inline = Vec::new();
+        let mut current = node.parent();
+        while let Some(ancestor) = current {
+            if ancestor.kind() == "mod_item" {
+                if let Some(name) = ancestor.child_by_field_name("name") {
+                    inline.push(self.get_node_text(&name));
+                }
+            }
+            current = ancestor.parent();
+        }
+        if inline.is_empty() {
+            return path.to_string();
+        }
+        inline.reverse();
+
+        let mut segments: Vec<&str> = path.split("::").collect();
+        let supers = segments.iter().take_while(|s| **s == "super").count();
+        if segments.first() == Some(&"self") {
+            segments.remove(0);
+        } else if supers == 0 {
+            return path.to_string();
+        } else if supers <= inline.len() {
+            inline.truncate(inline.len() - supers);
+            segments.drain(..supers);
+        } else {
+            segments.drain(..inline.len());
+            return segments.join("::");
+        }
+        ["self"]
+            .into_iter()
+            .chain(inline.iter().map(String::as_str))
+            .chain(segments)
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// Walk a Rust `use` tree collecting the full path of every leaf.
+    /// `self` in a list stands for the list's prefix, a glob for its path.
+    fn collect_use_paths(&self, node: &tree_sitter::Node, prefix: &str, paths: &mut Vec<String>) {
+        let join = |path: String| {
+            if prefix.is_empty() {
+                path
+            } else {
+                format!("{}::{}", prefix, path)
+            }
+        };
+
+        match node.kind() {
+            "self" if !prefix.is_empty() => paths.push(prefix.to_string()),
+            "identifier" | "scoped_identifier" | "crate" | "self" | "super" => {
+                paths.push(join(self.get_node_text(node)))
+            }
+            "use_as_clause" => {
+                if let Some(path) = node.child_by_field_name("path") {
+                    self.collect_use_paths(&path, prefix, paths);
+                }
+            }
+            "use_list" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.collect_use_paths(&child, prefix, paths);
+                }
+            }
+            "scoped_use_list" => {
+                let prefix = node
+                    .child_by_field_name("path")
+                    .map(|p| join(self.get_node_text(&p)))
+                    .unwrap_or_else(|| prefix.to_string());
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.collect_use_paths(&list, &prefix, paths);
+                }
+            }
+            "use_wildcard" => match node.named_child(0) {
+                Some(path) => paths.push(join(self.get_node_text(&path))),
+                None if !prefix.is_empty() => paths.push(prefix.to_string()),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn push_reference(
+        &mut self,
+        source_id: i64,
+        source_name: Option<String>,
+        reference_name: String,
+        kind: EdgeKind,
+        at: &tree_sitter::Node,
+        receiver: Option<String>,
+    ) {
+        let start = at.start_position();
+        self.result.unresolved_refs.push(UnresolvedReference {
+            source_node_id: source_id,
+            reference_name,
+            kind,
+            file_path: self.file_path.clone(),
+            line: start.row as u32 + 1,
+            column: start.column as u32,
+            source_name,
+            receiver,
+        });
+    }
+
+    fn extract_references(&mut self, node: &tree_sitter::Node, source_id: i64, kind: NodeKind) {
+        // Find call expressions within this node
+        self.find_calls(node, source_id);
+
+        // Find the types this symbol declares, returns or otherwise mentions
+        let mut seen = HashSet::new();
+        let mut cursor = node.walk();
+        if !cursor.goto_first_child() {
+            return;
+        }
+        loop {
+            let child = cursor.node();
+            let context = match cursor.field_name() {
+                // The symbol's own name, and a Go method's receiver, are not uses
+                Some("name" | "receiver") => None,
+                Some("return_type" | "result" | "returns") => Some(EdgeKind::Returns),
+                Some("type") if matches!(kind, NodeKind::Function | NodeKind::Method) => {
+                    Some(EdgeKind::Returns)
+                }
+                Some("type") if TYPED_DECLARATIONS.contains(&node.kind()) => Some(EdgeKind::TypeOf),
+                _ => Some(EdgeKind::References),
+            };
+            if let Some(context) = context {
+                self.find_type_uses(&child, source_id, context, false, &mut seen);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    /// Record the type names under `node`. `context` is the edge kind for
+    /// the position `node` is in; a parameter, field or variable type makes
+    /// it `TypeOf`. Nested symbols record their own uses.
+    fn find_type_uses(
+        &mut self,
+        node: &tree_sitter::Node,
+        source_id: i64,
+        context: EdgeKind,
+        in_type: bool,
+        seen: &mut HashSet<(String, EdgeKind)>,
+    ) {
+        // Owners keep the types of their fields and parameters as well
+        let nested = self.symbol_kind(node).is_some() && !self.declares_members(node);
+        if nested || NOT_TYPE_USES.contains(&node.kind()) {
+            return;
+        }
+        // Go struct embedding is recorded as inheritance
+        if self.language == Language::Go
+            && node.kind() == "field_declaration"
+            && node.child_by_field_name("name").is_none()
+        {
+            return;
+        }
+
+        let in_type = in_type
+            || match self.language {
+                // Python annotations are ordinary expressions inside a `type` node
+                Language::Python => node.kind() == "type",
+                // C# spells types as plain names; they are what a `type` or
+                // `returns` field holds
+                Language::CSharp => node.parent().is_some_and(|p| {
+                    ["type", "returns"]
+                        .iter()
+                        .any(|field| p.child_by_field_name(field) == Some(*node))
+                }),
+                _ => false,
+            };
+        let named = match (self.language, node.kind()) {
+            (Language::Python, "identifier") if in_type => Some(self.get_node_text(node)),
+            (Language::Python, "attribute") if in_type => Some(self.get_node_text(node)),
+            (Language::Python, _) => None,
+            (Language::CSharp, "identifier" | "qualified_name") if in_type => {
+                Some(self.get_node_text(node))
+            }
+            (Language::CSharp, _) => None,
+            (
+                _,
+                "type_identifier"
+                | "scoped_type_identifier"
+                | "qualified_type"
+                | "nested_type_identifier",
+            ) => Some(self.get_node_text(node)),
+            _ => None,
+        };
+        if let Some(text) = named {
+            // A constructed type is recorded as Instantiates by `find_calls`
+            if self.is_instantiated_type(node) {
+                return;
+            }
+            let (qualifier, name) = split_callee(&text);
+            let name = match name {
+                "Self" => self.enclosing_type_name(node),
+                _ => Some(name.to_string()),
+            };
+            if let Some(name) = name.filter(|n| !is_builtin_type(self.language, n)) {
+                let receiver = qualifier.map(str::to_string);
+                if seen.insert((text, context)) {
+                    self.push_reference(source_id, None, name, context, node, receiver);
+                }
+            }
+            return;
+        }
+
+        let mut cursor = node.walk();
+        if !cursor.goto_first_child() {
+            return;
+        }
+        loop {
+            let child = cursor.node();
+            let context = match cursor.field_name() {
+                Some("type") if TYPED_DECLARATIONS.contains(&node.kind()) => EdgeKind::TypeOf,
+                _ => context,
+            };
+            self.find_type_uses(&child, source_id, context, in_type, seen);
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+
+    fn find_calls(&mut self, node: &tree_sitter::Node, source_id: i64) {
+        let kind = node.kind();
+        // Decorator calls are recorded as Decorates on the decorated symbol,
+        // and Python annotations (`models.Order`) as type uses
+        if kind == "decorator" || (self.language == Language::Python && kind == "type") {
+            return;
+        }
+
+        let reference = self.captures.reference(node).copied();
+        let name = reference
+            .and_then(|r| r.name)
+            .map(|n| self.get_node_text(&n));
+        match reference.map(|r| r.kind) {
+            Some(ReferenceKind::Call) => {
+                let func_name = match name {
+                    Some(name) => Some(split_callee(&name).1.to_string()),
+                    None => self.extract_call_name(node),
+                };
+                if let Some(func_name) = func_name.filter(|n| !n.is_empty()) {
+                    let receiver = self.extract_call_receiver(node);
+                    // Foo::new() and Foo::default() construct a Foo
+                    if self.language == Language::Rust
+                        && matches!(func_name.as_str(), "new" | "default")
+                    {
+                        if let Some(type_name) = receiver
+                            .clone()
+                            .filter(|r| r.starts_with(char::is_uppercase))
+                        {
+                            self.push_reference(
+                                source_id,
+                                None,
+                                type_name,
+                                EdgeKind::Instantiates,
+                                node,
+                                None,
+                            );
+                        }
+                    }
+                    self.push_reference(
+                        source_id,
+                        None,
+                        func_name,
+                        EdgeKind::Calls,
+                        node,
+                        receiver,
+                    );
+                }
+            }
+            Some(ReferenceKind::Member) => self.push_member_access(node, source_id),
+            Some(ReferenceKind::Instantiation) => {
+                let type_name = name.or_else(|| self.instantiated_type(node));
+                if let Some(type_name) = type_name {
+                    let (qualifier, name) = split_callee(&type_name);
+                    let name = match name {
+                        "Self" => self.enclosing_type_name(node),
+                        _ => Some(name.to_string()),
+                    };
+                    if let Some(name) = name.filter(|n| is_identifier(n)) {
+                        let receiver = qualifier.map(str::to_string);
+                        self.push_reference(
+                            source_id,
+                            None,
+                            name,
+                            EdgeKind::Instantiates,
+                            node,
+                            receiver,
+                        );
+                    }
+                }
+            }
+            Some(ReferenceKind::Implementation) | None => {}
+        }
+
+        // Recurse into children; nested methods and types record their own calls
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if self
+                .symbol_kind(&child)
+                .is_some_and(|kind| kind.is_type() || kind == NodeKind::Method)
+            {
+                continue;
+            }
+            self.find_calls(&child, source_id);
+        }
+    }
+
+    /// Record a read or write of a field or property, e.g. `self.balance`,
+    /// as a reference qualified by the object's type
+    fn push_member_access(&mut self, node: &tree_sitter::Node, source_id: i64) {
+        // `obj.method()` is a call, `new pkg.Foo()` an instantiation
+        if let Some(parent) = node.parent() {
+            let callee = parent
+                .child_by_field_name("function")
+                .or_else(|| parent.child_by_field_name("constructor"));
+            if callee == Some(*node) {
+                return;
+            }
+        }
+        let member = ["field", "property", "attribute", "name"]
+            .iter()
+            .find_map(|field| node.child_by_field_name(field));
+        let object = ["value", "object", "argument", "operand", "expression"]
+            .iter()
+            .find_map(|field| node.child_by_field_name(field));
+        let (Some(member), Some(object)) = (member, object) else {
+            return;
+        };
+        let name = self.get_node_text(&member);
+        // Tuple fields (`pair.0`) are not named members; JS `#private` ones are
+        if !is_identifier(name.trim_start_matches('#')) {
+            return;
+        }
+        let receiver = self.receiver_type(node, &self.get_node_text(&object));
+        self.push_reference(source_id, None, name, EdgeKind::References, node, receiver);
+    }
 
     fn extract_call_name(&self, node: &tree_sitter::Node) -> Option<String> {
+        let callee = self.callee_text(node)?;
+        let (_, name) = split_callee(&callee);
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Text of the called expression, e.g. `self.items.push` or `Vec::new`
+    fn callee_text(&self, node: &tree_sitter::Node) -> Option<String> {
         // Look for function name in call expression
-        if let Some(func) = node.child_by_field_name("function") {
-            let text = self.get_node_text(&func);
-            // Handle method calls: obj.method() -> method
-            if let Some(dot_pos) = text.rfind('.') {
-                return Some(text[dot_pos + 1..].to_string());
-            }
-            // Handle path calls: foo::bar() -> bar
-            if let Some(colon_pos) = text.rfind("::") {
-                return Some(text[colon_pos + 2..].to_string());
+        if let Some(mut func) = node.child_by_field_name("function") {
+            // x.y::<T>() -> x.y
+            if func.kind() == "generic_function" {
+                if let Some(inner) = func.child_by_field_name("function") {
+                    func = inner;
+                }
             }
-            return Some(text);
+            return Some(self.get_node_text(&func));
+        }
+
+        // Java method invocations keep the object and method name apart
+        if let Some(name) = node.child_by_field_name("name") {
+            let name = self.get_node_text(&name);
+            return Some(match node.child_by_field_name("object") {
+                Some(object) => format!("{}.{}", self.get_node_text(&object), name),
+                None => name,
+            });
         }
 
         // Try first child as fallback
         if let Some(first) = node.child(0) {
             if first.kind() == "identifier" || first.kind() == "field_expression" {
-                let text = self.get_node_text(&first);
-                if let Some(dot_pos) = text.rfind('.') {
-                    return Some(text[dot_pos + 1..].to_string());
+                return Some(self.get_node_text(&first));
+            }
+        }
+
+        None
+    }
+
+    /// Name of the type constructed by `new Foo()`, `Foo { .. }`, `Foo{}`
+    /// or `(struct Foo){ .. }`
+    fn instantiated_type(&self, node: &tree_sitter::Node) -> Option<String> {
+        let ty = self.instantiated_type_node(node)?;
+        Some(self.get_node_text(&ty))
+    }
+
+    fn instantiated_type_node<'t>(
+        &self,
+        node: &tree_sitter::Node<'t>,
+    ) -> Option<tree_sitter::Node<'t>> {
+        let mut ty = ["constructor", "type", "name"]
+            .iter()
+            .find_map(|field| node.child_by_field_name(field))?;
+        // C compound literals: (struct Foo){ .. }
+        if ty.kind() == "type_descriptor" {
+            ty = ty.child_by_field_name("type")?;
+        }
+        if ty.kind() == "struct_specifier" {
+            ty = ty.child_by_field_name("name")?;
+        }
+        Some(ty)
+    }
+
+    /// Whether `node` is the type named by the nearest enclosing constructor
+    /// call or literal, e.g. `Circle` in `Circle { r }` or `new Circle(r)`
+    fn is_instantiated_type(&self, node: &tree_sitter::Node) -> bool {
+        let mut ancestor = node.parent();
+        while let Some(site) = ancestor {
+            if let Some(reference) = self.captures.reference(&site) {
+                return reference.kind == ReferenceKind::Instantiation
+                    && reference
+                        .name
+                        .or_else(|| self.instantiated_type_node(&site))
+                        == Some(*node);
+            }
+            if self.symbol_kind(&site).is_some() {
+                return false;
+            }
+            ancestor = site.parent();
+        }
+        false
+    }
+
+    /// Qualifier of a call, resolved to the owning type for `self`/`this`
+    /// and for Go method receivers
+    fn extract_call_receiver(&self, node: &tree_sitter::Node) -> Option<String> {
+        let callee = self.callee_text(node)?;
+        let (qualifier, _) = split_callee(&callee);
+        self.receiver_type(node, qualifier?)
+    }
+
+    /// Type (or module) that the qualifier of a call or member access at
+    /// `node` refers to: `self` is the enclosing type, a parameter or Go
+    /// receiver its declared type, anything else its last segment
+    fn receiver_type(&self, node: &tree_sitter::Node, qualifier: &str) -> Option<String> {
+        let last = qualifier.rsplit(['.', ':', '>']).next()?;
+        if !is_identifier(last) {
+            return None;
+        }
+
+        match last {
+            "self" | "this" | "Self" | "cls" => self.enclosing_type_name(node),
+            "super" => None,
+            _ => self
+                .parameter_type(node, last)
+                .or_else(|| Some(last.to_string())),
+        }
+    }
+
+    /// Trait named by the `impl Trait for Type` block that defines `node`
+    fn implemented_trait(&self, node: &tree_sitter::Node) -> Option<String> {
+        if self.language != Language::Rust || node.kind() != "function_item" {
+            return None;
+        }
+        let block = node.parent().filter(|p| p.kind() == "declaration_list")?;
+        let impl_item = block.parent().filter(|p| p.kind() == "impl_item")?;
+        let trait_node = impl_item.child_by_field_name("trait")?;
+        Some(base_type_name(&self.get_node_text(&trait_node)))
+    }
+
+    /// Name of the type whose body or impl block contains `node`
+    fn enclosing_type_name(&self, node: &tree_sitter::Node) -> Option<String> {
+        let mut current = node.parent();
+        while let Some(ancestor) = current {
+            if ancestor.kind() == "impl_item" {
+                let ty = ancestor.child_by_field_name("type")?;
+                return Some(base_type_name(&self.get_node_text(&ty)));
+            }
+            if let Some(kind) = self.symbol_kind(&ancestor) {
+                if kind.is_type() {
+                    let name = self.extract_name(&ancestor, kind);
+                    if !name.is_empty() {
+                        return Some(name);
+                    }
+                }
+            }
+            current = ancestor.parent();
+        }
+        None
+    }
+
+    /// Type of the Go method receiver named `var`, e.g. `Server` for `s` in `func (s *Server)`
+    /// Declared type of the parameter (or Go receiver) named `var` of a
+    /// function enclosing `node`
+    fn parameter_type(&self, node: &tree_sitter::Node, var: &str) -> Option<String> {
+        let mut current = node.parent();
+        while let Some(ancestor) = current {
+            let lists = [
+                ancestor.child_by_field_name("receiver"),
+                ancestor.child_by_field_name("parameters"),
+                self.function_declarator(&ancestor)
+                    .and_then(|d| d.child_by_field_name("parameters")),
+            ];
+            for list in lists.into_iter().flatten() {
+                let mut cursor = list.walk();
+                for param in list.named_children(&mut cursor) {
+                    let names = self.member_names(&param);
+                    if names.iter().any(|n| self.get_node_text(n) == var) {
+                        let ty = param.child_by_field_name("type")?;
+                        let ty = base_type_name(&self.get_node_text(&ty));
+                        // `&mut Cart`, `struct cart`
+                        return ty.split_whitespace().last().map(str::to_string);
+                    }
                 }
-                return Some(text);
             }
+            current = ancestor.parent();
+        }
+        None
+    }
+
+    fn get_node_text(&self, node: &tree_sitter::Node) -> String {
+        let start = node.start_byte();
+        let end = node.end_byte();
+        if start < self.content.len() && end <= self.content.len() {
+            self.content[start..end].to_string()
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// Declarations whose `type` field is the type of what they declare:
+/// parameters, fields and variables
+const TYPED_DECLARATIONS: &[&str] = &[
+    // Rust
+    "parameter",
+    "let_declaration",
+    "field_declaration",
+    "const_item",
+    "static_item",
+    // TypeScript
+    "required_parameter",
+    "optional_parameter",
+    "variable_declarator",
+    "public_field_definition",
+    "property_signature",
+    // Python
+    "typed_parameter",
+    "typed_default_parameter",
+    "assignment",
+    // Go
+    "parameter_declaration",
+    "variadic_parameter_declaration",
+    "var_spec",
+    "const_spec",
+    // Java
+    "formal_parameter",
+    "spread_parameter",
+    "local_variable_declaration",
+    "constant_declaration",
+    // C/C++
+    "optional_parameter_declaration",
+    "declaration",
+    // C#
+    "variable_declaration",
+    "property_declaration",
+];
+
+/// Parts of a declaration that name types without using them: base type
+/// lists (recorded as inheritance) and generic parameter declarations
+const NOT_TYPE_USES: &[&str] = &[
+    "class_heritage",
+    "extends_clause",
+    "implements_clause",
+    "superclass",
+    "super_interfaces",
+    "extends_interfaces",
+    "base_class_clause",
+    "type_parameters",
+    "base_list",
+    "type_parameter_list",
+    "type_parameter_constraints_clause",
+];
+
+/// Built-in types that Go and Python spell as ordinary identifiers
+fn is_builtin_type(language: Language, name: &str) -> bool {
+    match language {
+        Language::Go => matches!(
+            name,
+            "bool"
+                | "byte"
+                | "complex64"
+                | "complex128"
+                | "error"
+                | "float32"
+                | "float64"
+                | "int"
+                | "int8"
+                | "int16"
+                | "int32"
+                | "int64"
+                | "rune"
+                | "string"
+                | "uint"
+                | "uint8"
+                | "uint16"
+                | "uint32"
+                | "uint64"
+                | "uintptr"
+                | "any"
+                | "comparable"
+        ),
+        Language::Python => matches!(
+            name,
+            "int"
+                | "float"
+                | "complex"
+                | "str"
+                | "bytes"
+                | "bool"
+                | "list"
+                | "dict"
+                | "set"
+                | "frozenset"
+                | "tuple"
+                | "object"
+                | "type"
+        ),
+        _ => false,
+    }
+}
+
+/// The identifier inside a declarator or pattern: `*p`, `a[4]`, `x = 1`, `mut x`
+fn declared_name<'t>(node: &tree_sitter::Node<'t>) -> Option<tree_sitter::Node<'t>> {
+    match node.kind() {
+        "identifier"
+        | "field_identifier"
+        | "property_identifier"
+        | "private_property_identifier"
+        | "type_identifier" => Some(*node),
+        "variable_declarator" => declared_name(&node.child_by_field_name("name")?),
+        "rest_pattern" => declared_name(&node.named_child(0)?),
+        "mut_pattern" | "array_declarator" | "init_declarator" | "bitfield_clause" => {
+            declared_name(&inner_declarator(node)?)
+        }
+        kind if is_declarator_wrapper(kind) => declared_name(&inner_declarator(node)?),
+        _ => None,
+    }
+}
+
+/// C/C++ declarators that wrap the declarator naming a symbol
+fn is_declarator_wrapper(kind: &str) -> bool {
+    matches!(
+        kind,
+        "pointer_declarator" | "reference_declarator" | "function_declarator"
+    )
+}
+
+/// The name a function or class expression is bound to
+struct Binding {
+    name: String,
+    exported: bool,
+    /// Bound to a class field, which makes a function a method
+    in_class: bool,
+}
+
+fn is_function_value(kind: &str) -> bool {
+    matches!(kind, "arrow_function" | "function_expression")
+}
+
+/// Scope and member name of a C++ qualified declarator: `ns::Foo::bar` ->
+/// (`Foo`, `bar`)
+fn split_qualified(node: tree_sitter::Node) -> Option<(tree_sitter::Node, tree_sitter::Node)> {
+    let mut qualified = node;
+    if qualified.kind() != "qualified_identifier" {
+        return None;
+    }
+    loop {
+        let name = qualified.child_by_field_name("name")?;
+        if name.kind() != "qualified_identifier" {
+            return Some((qualified.child_by_field_name("scope")?, name));
+        }
+        qualified = name;
+    }
+}
+
+/// The declarator wrapped by a pointer, reference or function declarator
+fn inner_declarator<'t>(node: &tree_sitter::Node<'t>) -> Option<tree_sitter::Node<'t>> {
+    // Reference declarators keep their declarator in an unnamed position
+    node.child_by_field_name("declarator")
+        .or_else(|| node.named_child(0))
+}
+
+/// Decorators and attributes provided by the language or its standard library
+fn is_builtin_decorator(language: Language, name: &str) -> bool {
+    match language {
+        Language::Rust => matches!(
+            name,
+            "derive"
+                | "cfg"
+                | "cfg_attr"
+                | "test"
+                | "ignore"
+                | "should_panic"
+                | "allow"
+                | "warn"
+                | "deny"
+                | "forbid"
+                | "expect"
+                | "inline"
+                | "cold"
+                | "must_use"
+                | "deprecated"
+                | "non_exhaustive"
+                | "repr"
+                | "path"
+                | "macro_export"
+                | "macro_use"
+                | "no_mangle"
+                | "track_caller"
+                | "automatically_derived"
+                | "doc"
+        ),
+        Language::Python => matches!(name, "property" | "staticmethod" | "classmethod"),
+        Language::Java => matches!(
+            name,
+            "Override" | "Deprecated" | "SuppressWarnings" | "FunctionalInterface" | "SafeVarargs"
+        ),
+        Language::CSharp => matches!(
+            name.strip_suffix("Attribute").unwrap_or(name),
+            "Serializable"
+                | "NonSerialized"
+                | "Obsolete"
+                | "Flags"
+                | "Conditional"
+                | "DllImport"
+                | "StructLayout"
+                | "AttributeUsage"
+                | "MethodImpl"
+                | "DebuggerDisplay"
+                | "DebuggerStepThrough"
+        ),
+        _ => false,
+    }
+}
+
+/// Syntax errors in a parse tree: each outermost `ERROR` node, and each
+/// token the parser assumed `MISSING`, in source order
+fn syntax_errors(root: tree_sitter::Node, content: &str, file_path: &str) -> Vec<ExtractionError> {
+    let mut errors = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if !node.has_error() {
+            continue;
         }
+        let message = if node.is_missing() {
+            match node.is_named() {
+                true => format!("Missing {}", node.kind()),
+                false => format!("Missing `{}`", node.kind()),
+            }
+        } else if node.is_error() {
+            let text = &content[node.byte_range()];
+            let snippet: String = text
+                .trim()
+                .lines()
+                .next()
+                .unwrap_or("")
+                .chars()
+                .take(40)
+                .collect();
+            match snippet.is_empty() {
+                true => "Syntax error".to_string(),
+                false => format!("Syntax error at `{}`", snippet.trim()),
+            }
+        } else {
+            let mut cursor = node.walk();
+            let children: Vec<_> = node.children(&mut cursor).collect();
+            stack.extend(children.into_iter().rev());
+            continue;
+        };
+        let start = node.start_position();
+        errors.push(ExtractionError {
+            message,
+            file_path: file_path.to_string(),
+            line: Some(start.row as u32 + 1),
+            column: Some(start.column as u32),
+        });
+    }
+    errors
+}
+
+/// Text of a `#[doc = "..."]` attribute
+fn doc_attribute(attribute: &str) -> Option<String> {
+    let inner = attribute.strip_prefix("#[")?.strip_suffix(']')?;
+    let value = inner
+        .strip_prefix("doc")?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+    let text = value.strip_prefix('"')?.strip_suffix('"')?;
+    let text = text.replace("\\\"", "\"");
+    Some(text.strip_prefix(' ').unwrap_or(&text).to_string())
+}
+
+/// Go comment directives, which are not documentation: `//go:generate`,
+/// `//go:build`, `// +build`, `//line`, `//export`
+fn is_go_directive(comment: &str) -> bool {
+    let Some(body) = comment.strip_prefix("//") else {
+        return false;
+    };
+    body.starts_with("go:")
+        || body.trim_start().starts_with("+build")
+        || ["line ", "export ", "extern ", "nolint"]
+            .iter()
+            .any(|directive| body.starts_with(directive))
+}
+
+/// Drop the brackets around an argument list: `(a, b)` -> `a, b`
+fn strip_delimiters(text: &str) -> &str {
+    text.strip_prefix(['(', '[', '{'])
+        .and_then(|t| t.strip_suffix([')', ']', '}']))
+        .unwrap_or(text)
+        .trim()
+}
+
+/// Reduce a type reference such as `fmt::Display`, `B.C<T>` or `*Base` to its bare name
+fn base_type_name(text: &str) -> String {
+    let text = text.split('<').next().unwrap_or(text);
+    let text = text.rsplit(['.', ':']).next().unwrap_or(text);
+    text.trim()
+        .trim_start_matches(['*', '&'])
+        .trim()
+        .to_string()
+}
+
+/// Split a callee such as `a.b.c`, `Foo::<T>::new` or `ns.Foo<T>` into
+/// its qualifier and the called name
+fn split_callee(callee: &str) -> (Option<&str>, &str) {
+    let callee = strip_generics(callee);
+    let cut = [callee.rfind('.'), callee.rfind("::").map(|p| p + 1)]
+        .into_iter()
+        .flatten()
+        .max();
+    match cut {
+        Some(pos) => {
+            let qualifier = strip_generics(callee[..pos].trim_end_matches(':'));
+            (Some(qualifier), &callee[pos + 1..])
+        }
+        None => (None, callee),
+    }
+}
+
+/// Drop trailing generic arguments: `Foo::<T>` or `Foo<T>` -> `Foo`
+fn strip_generics(text: &str) -> &str {
+    if !text.ends_with('>') {
+        return text;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices().rev() {
+        match c {
+            '>' => depth += 1,
+            '<' => {
+                depth -= 1;
+                if depth == 0 {
+                    return text[..i].trim_end_matches("::");
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl Default for Extractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SignatureParts;
+
+    #[test]
+    fn test_extractor_creation() {
+        let extractor = Extractor::new();
+        assert!(std::mem::size_of_val(&extractor) > 0);
+    }
+
+    #[test]
+    fn test_extract_unsupported_extension() {
+        let mut extractor = Extractor::new();
+        let result = extractor.extract_file("test.xyz", "some content");
+        assert!(!result.errors.is_empty());
+        assert!(result.errors[0]
+            .message
+            .contains("Unsupported file extension"));
+    }
+
+    // Rust extraction tests
+    #[test]
+    fn test_extract_rust_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+fn hello_world() {
+    println!("Hello, world!");
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        assert!(result.errors.is_empty());
+
+        // Should have file node + function node
+        assert!(result.nodes.len() >= 2);
+
+        let func = result.nodes.iter().find(|n| n.name == "hello_world");
+        assert!(func.is_some());
+        let func = func.unwrap();
+        assert_eq!(func.kind, NodeKind::Function);
+        assert_eq!(func.language, Language::Rust);
+    }
+
+    #[test]
+    fn test_extract_rust_pub_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+pub fn public_function() -> i32 {
+    42
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        let func = result
+            .nodes
+            .iter()
+            .find(|n| n.name == "public_function")
+            .unwrap();
+        assert_eq!(func.visibility, Visibility::Public);
+        assert!(func.signature.is_some());
+    }
+
+    #[test]
+    fn test_extract_rust_struct() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+pub struct MyStruct {
+    field1: i32,
+    field2: String,
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        let strukt = result.nodes.iter().find(|n| n.name == "MyStruct");
+        assert!(strukt.is_some());
+        assert_eq!(strukt.unwrap().kind, NodeKind::Struct);
+    }
+
+    #[test]
+    fn test_extract_rust_impl_methods() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+struct Foo {}
+
+impl Foo {
+    fn bar(&self) {
+    }
+
+    pub fn baz() -> Self {
+        Foo {}
+    }
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+
+        // Should find struct and methods
+        assert!(result.nodes.iter().any(|n| n.name == "Foo"));
+        assert!(result.nodes.iter().any(|n| n.name == "bar"));
+        assert!(result.nodes.iter().any(|n| n.name == "baz"));
+    }
+
+    #[test]
+    fn test_extract_rust_enum() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        let enum_node = result.nodes.iter().find(|n| n.name == "Color");
+        assert!(enum_node.is_some());
+        assert_eq!(enum_node.unwrap().kind, NodeKind::Enum);
+    }
+
+    #[test]
+    fn test_extract_rust_function_calls() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+fn caller() {
+    helper();
+    other_func();
+}
+
+fn helper() {}
+fn other_func() {}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+
+        // Should have unresolved references for the calls
+        assert!(!result.unresolved_refs.is_empty());
+        assert!(result
+            .unresolved_refs
+            .iter()
+            .any(|r| r.reference_name == "helper"));
+        assert!(result
+            .unresolved_refs
+            .iter()
+            .any(|r| r.reference_name == "other_func"));
+    }
+
+    #[test]
+    fn test_extract_rust_async_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+async fn async_handler() {
+    do_something().await;
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        let func = result
+            .nodes
+            .iter()
+            .find(|n| n.name == "async_handler")
+            .unwrap();
+        assert!(func.is_async);
+    }
+
+    // TypeScript extraction tests
+    #[test]
+    fn test_extract_typescript_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+function greet(name: string): string {
+    return `Hello, ${name}!`;
+}
+"#;
+        let result = extractor.extract_file("test.ts", code);
+        assert!(result.errors.is_empty());
+
+        let func = result.nodes.iter().find(|n| n.name == "greet");
+        assert!(func.is_some());
+        assert_eq!(func.unwrap().language, Language::TypeScript);
+    }
+
+    #[test]
+    fn test_extract_typescript_class() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class MyClass {
+    private value: number;
+
+    constructor(val: number) {
+        this.value = val;
+    }
+
+    getValue(): number {
+        return this.value;
+    }
+}
+"#;
+        let result = extractor.extract_file("test.ts", code);
+        let class = result.nodes.iter().find(|n| n.name == "MyClass");
+        assert!(class.is_some());
+        assert_eq!(class.unwrap().kind, NodeKind::Class);
+    }
+
+    #[test]
+    fn test_extract_typescript_interface() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+interface User {
+    name: string;
+    age: number;
+}
+"#;
+        let result = extractor.extract_file("test.ts", code);
+        let iface = result.nodes.iter().find(|n| n.name == "User");
+        assert!(iface.is_some());
+        assert_eq!(iface.unwrap().kind, NodeKind::Interface);
+    }
+
+    #[test]
+    fn test_extract_typescript_arrow_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+const add = (a: number, b: number): number => a + b;
+"#;
+        let result = extractor.extract_file("test.ts", code);
+        // Arrow functions are typically extracted as constants or variables
+        assert!(!result.nodes.is_empty());
+    }
+
+    // Python extraction tests
+    #[test]
+    fn test_extract_python_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+def hello_world():
+    print("Hello, world!")
+"#;
+        let result = extractor.extract_file("test.py", code);
+        assert!(result.errors.is_empty());
+
+        let func = result.nodes.iter().find(|n| n.name == "hello_world");
+        assert!(func.is_some());
+        assert_eq!(func.unwrap().language, Language::Python);
+    }
+
+    #[test]
+    fn test_extract_python_class() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class MyClass:
+    def __init__(self, value):
+        self.value = value
+
+    def get_value(self):
+        return self.value
+"#;
+        let result = extractor.extract_file("test.py", code);
+        let class = result.nodes.iter().find(|n| n.name == "MyClass");
+        assert!(class.is_some());
+        assert_eq!(class.unwrap().kind, NodeKind::Class);
+
+        // Should also find methods
+        assert!(result.nodes.iter().any(|n| n.name == "__init__"));
+        assert!(result.nodes.iter().any(|n| n.name == "get_value"));
+    }
+
+    #[test]
+    fn test_extract_python_async_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+async def fetch_data():
+    await some_async_call()
+"#;
+        let result = extractor.extract_file("test.py", code);
+        let func = result
+            .nodes
+            .iter()
+            .find(|n| n.name == "fetch_data")
+            .unwrap();
+        assert!(func.is_async);
+    }
+
+    // JavaScript extraction tests
+    #[test]
+    fn test_extract_javascript_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+function processData(data) {
+    return data.map(x => x * 2);
+}
+"#;
+        let result = extractor.extract_file("test.js", code);
+        let func = result.nodes.iter().find(|n| n.name == "processData");
+        assert!(func.is_some());
+        assert_eq!(func.unwrap().language, Language::JavaScript);
+    }
+
+    // Go extraction tests
+    #[test]
+    fn test_extract_go_function() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+func main() {
+    fmt.Println("Hello, World!")
+}
+
+func helper(x int) int {
+    return x * 2
+}
+"#;
+        let result = extractor.extract_file("test.go", code);
+        assert!(result.nodes.iter().any(|n| n.name == "main"));
+        assert!(result.nodes.iter().any(|n| n.name == "helper"));
+    }
+
+    #[test]
+    fn test_extract_go_struct() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+type Person struct {
+    Name string
+    Age  int
+}
+"#;
+        let result = extractor.extract_file("test.go", code);
+        // Go type declarations may be extracted differently depending on grammar
+        // At minimum we should have the file node and no errors
+        assert!(result.errors.is_empty());
+        assert!(result.nodes.iter().any(|n| n.kind == NodeKind::File));
+    }
+
+    #[test]
+    fn test_extract_csharp_types() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+namespace Shop.Orders
+{
+    public class Order
+    {
+        public Order(int id) { }
+        public decimal Total() { return 0; }
+        static void Helper() { int Local() => 1; }
+    }
+    public interface IPriced { decimal Price(); }
+    public struct Money { }
+    public record Line(string Sku, int Quantity);
+    public record struct Point(int X, int Y);
+    public enum Status { Open, Closed }
+    public delegate void Changed(object sender);
+}
+"#;
+        let result = extractor.extract_file("Order.cs", code);
+        assert!(result.errors.is_empty());
+
+        let kinds: Vec<(NodeKind, &str, Option<&str>)> = result
+            .nodes
+            .iter()
+            .filter(|n| !n.kind.is_member() && n.kind != NodeKind::File)
+            .map(|n| (n.kind, n.name.as_str(), n.qualified_name.as_deref()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (NodeKind::Module, "Shop.Orders", Some("Shop::Orders")),
+                (NodeKind::Class, "Order", Some("Shop::Orders::Order")),
+                (
+                    NodeKind::Method,
+                    "Order",
+                    Some("Shop::Orders::Order::Order")
+                ),
+                (
+                    NodeKind::Method,
+                    "Total",
+                    Some("Shop::Orders::Order::Total")
+                ),
+                (
+                    NodeKind::Method,
+                    "Helper",
+                    Some("Shop::Orders::Order::Helper")
+                ),
+                (
+                    NodeKind::Function,
+                    "Local",
+                    Some("Shop::Orders::Order::Helper::Local")
+                ),
+                (
+                    NodeKind::Interface,
+                    "IPriced",
+                    Some("Shop::Orders::IPriced")
+                ),
+                (
+                    NodeKind::Method,
+                    "Price",
+                    Some("Shop::Orders::IPriced::Price")
+                ),
+                (NodeKind::Struct, "Money", Some("Shop::Orders::Money")),
+                (NodeKind::Class, "Line", Some("Shop::Orders::Line")),
+                (NodeKind::Struct, "Point", Some("Shop::Orders::Point")),
+                (NodeKind::Enum, "Status", Some("Shop::Orders::Status")),
+                (
+                    NodeKind::TypeAlias,
+                    "Changed",
+                    Some("Shop::Orders::Changed")
+                ),
+            ]
+        );
+        let total = result.nodes.iter().find(|n| n.name == "Total").unwrap();
+        assert_eq!(total.signature.as_deref(), Some("public decimal Total()"));
+    }
+
+    #[test]
+    fn test_csharp_file_scoped_namespace() {
+        let mut extractor = Extractor::new();
+        let code = "using System;\n\nnamespace Shop.Billing;\n\npublic class Invoice\n{\n    void Send() { }\n}\n";
+        let result = extractor.extract_file("Invoice.cs", code);
+
+        let namespace = result
+            .nodes
+            .iter()
+            .find(|n| n.name == "Shop.Billing")
+            .unwrap();
+        assert_eq!(namespace.kind, NodeKind::Module);
+        assert_eq!((namespace.start_line, namespace.end_line), (3, 9));
+        let send = result.nodes.iter().find(|n| n.name == "Send").unwrap();
+        assert_eq!(
+            send.qualified_name.as_deref(),
+            Some("Shop::Billing::Invoice::Send")
+        );
+        let invoice = result.nodes.iter().find(|n| n.name == "Invoice").unwrap();
+        assert!(result.edges.iter().any(|e| e.kind == EdgeKind::Contains
+            && e.source_id == namespace.id
+            && e.target_id == invoice.id));
+    }
+
+    // Contains edge tests
+    #[test]
+    fn test_contains_edges() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+fn outer() {
+    fn inner() {}
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+
+        // Should have contains edges
+        let contains_edges: Vec<_> = result
+            .edges
+            .iter()
+            .filter(|e| e.kind == EdgeKind::Contains)
+            .collect();
+        assert!(!contains_edges.is_empty());
+    }
+
+    // File node tests
+    #[test]
+    fn test_file_node_created() {
+        let mut extractor = Extractor::new();
+        let result = extractor.extract_file("mymodule.rs", "fn foo() {}");
+
+        let file_node = result.nodes.iter().find(|n| n.kind == NodeKind::File);
+        assert!(file_node.is_some());
+        assert_eq!(file_node.unwrap().name, "mymodule.rs");
+    }
+
+    // Empty file test
+    #[test]
+    fn test_extract_empty_file() {
+        let mut extractor = Extractor::new();
+        let result = extractor.extract_file("empty.rs", "");
+        assert!(result.errors.is_empty());
+        // Should still have a file node
+        assert!(result.nodes.iter().any(|n| n.kind == NodeKind::File));
+    }
+
+    // Line number tests
+    #[test]
+    fn test_line_numbers() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+fn first() {}
+
+fn second() {}
+
+fn third() {}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+
+        let first = result.nodes.iter().find(|n| n.name == "first").unwrap();
+        let second = result.nodes.iter().find(|n| n.name == "second").unwrap();
+        let third = result.nodes.iter().find(|n| n.name == "third").unwrap();
+
+        assert!(first.start_line < second.start_line);
+        assert!(second.start_line < third.start_line);
+    }
+
+    // Inheritance tests
+    fn inheritance_refs(result: &ExtractionResult, kind: EdgeKind) -> Vec<String> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == kind)
+            .map(|r| r.reference_name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_impl_trait_for_type() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+trait Shape: fmt::Display + Clone {}
+struct Circle;
+impl Shape for Circle {}
+impl Circle {}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+
+        let implements: Vec<_> = result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Implements)
+            .collect();
+        assert_eq!(implements.len(), 1);
+        assert_eq!(implements[0].reference_name, "Shape");
+        assert_eq!(implements[0].source_name.as_deref(), Some("Circle"));
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Display", "Clone"]
+        );
+    }
+
+    #[test]
+    fn test_typescript_extends_implements() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+interface Named extends Base<string> {}
+class Dog extends Animal implements Named, Pet {}
+"#;
+        let result = extractor.extract_file("test.ts", code);
+
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Base", "Animal"]
+        );
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Implements),
+            vec!["Named", "Pet"]
+        );
+    }
+
+    #[test]
+    fn test_javascript_extends() {
+        let mut extractor = Extractor::new();
+        let result = extractor.extract_file("test.js", "class Dog extends Animal {}");
+        assert_eq!(inheritance_refs(&result, EdgeKind::Extends), vec!["Animal"]);
+    }
+
+    #[test]
+    fn test_java_extends_implements() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+interface Pet extends Named {}
+class Dog extends Animal implements Pet, java.io.Serializable {}
+"#;
+        let result = extractor.extract_file("Dog.java", code);
+
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Named", "Animal"]
+        );
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Implements),
+            vec!["Pet", "Serializable"]
+        );
+    }
+
+    #[test]
+    fn test_python_base_classes() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Dog(Animal, abc.ABC, metaclass=Meta):
+    pass
+"#;
+        let result = extractor.extract_file("test.py", code);
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Animal", "ABC"]
+        );
+    }
+
+    #[test]
+    fn test_cpp_base_classes() {
+        let mut extractor = Extractor::new();
+        let code = "class Dog : public Animal, private ns::Pet {};";
+        let result = extractor.extract_file("test.cpp", code);
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Animal", "Pet"]
+        );
+    }
+
+    #[test]
+    fn test_csharp_base_lists() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+interface IPet : INamed, System.IComparable<IPet> {}
+class Dog : Animal, IPet {}
+record Puppy(string Name) : Dog;
+struct Tag : IEquatable<Tag> {}
+"#;
+        let result = extractor.extract_file("Dog.cs", code);
+        // Resolution turns the interfaces of a class into Implements
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["INamed", "IComparable", "Animal", "IPet", "Dog"]
+        );
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Implements),
+            vec!["IEquatable"]
+        );
+        // Base types are not also recorded as type uses
+        let uses = type_uses(&result);
+        assert!(
+            !uses.iter().any(|(source, _, _)| source != "Name"),
+            "{:?}",
+            uses
+        );
+    }
+
+    #[test]
+    fn test_go_embedding_and_assertions() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+package main
+
+type Reader interface { Read() }
+type ReadCloser interface {
+    Reader
+    Close()
+}
+type File struct {
+    *Base
+    name string
+}
+
+var _ Reader = (*File)(nil)
+var _ ReadCloser = &File{}
+"#;
+        let result = extractor.extract_file("test.go", code);
+
+        let kind_of = |name: &str| result.nodes.iter().find(|n| n.name == name).unwrap().kind;
+        assert_eq!(kind_of("Reader"), NodeKind::Interface);
+        assert_eq!(kind_of("File"), NodeKind::Struct);
+
+        assert_eq!(
+            inheritance_refs(&result, EdgeKind::Extends),
+            vec!["Reader", "Base"]
+        );
+        let implements: Vec<_> = result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Implements)
+            .map(|r| (r.source_name.as_deref(), r.reference_name.as_str()))
+            .collect();
+        assert_eq!(
+            implements,
+            vec![(Some("File"), "Reader"), (Some("File"), "ReadCloser")]
+        );
+    }
+
+    #[test]
+    fn test_base_type_name() {
+        assert_eq!(base_type_name("fmt::Display"), "Display");
+        assert_eq!(base_type_name("Base<T>"), "Base");
+        assert_eq!(base_type_name("pkg.Reader"), "Reader");
+        assert_eq!(base_type_name("*Base"), "Base");
+        assert_eq!(base_type_name("std::collections::HashMap<K, V>"), "HashMap");
+    }
+
+    // Resolution hint tests
+    fn call_receivers(result: &ExtractionResult) -> Vec<(String, Option<String>)> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Calls)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_call_receivers() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+impl Order {
+    fn total(&self) {
+        self.sum();
+        Self::new();
+        Vec::<u8>::with_capacity(1);
+        crate::util::round();
+        helper();
+    }
+}
+"#;
+        let result = extractor.extract_file("test.rs", code);
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            call_receivers(&result),
+            vec![
+                ("sum".to_string(), some("Order")),
+                ("new".to_string(), some("Order")),
+                ("with_capacity".to_string(), some("Vec")),
+                ("round".to_string(), some("util")),
+                ("helper".to_string(), None),
+            ]
+        );
+    }
+
+    fn instantiations(result: &ExtractionResult) -> Vec<(String, Option<String>)> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Instantiates)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_instantiated_type_is_not_also_referenced() {
+        let mut extractor = Extractor::new();
+        let sources = [
+            ("shapes.rs", "fn make(r: f64) { let c = Circle { r }; }"),
+            (
+                "Shapes.java",
+                "class Shapes { void make() { new Circle(); } }",
+            ),
+            (
+                "Shapes.cs",
+                "class Shapes { void Make() { new Circle(); } }",
+            ),
+            ("shapes.ts", "function make() { return new Circle(); }"),
+        ];
+        for (path, code) in sources {
+            let result = extractor.extract_file(path, code);
+            let uses: Vec<_> = result
+                .unresolved_refs
+                .iter()
+                .filter(|r| r.reference_name == "Circle")
+                .map(|r| r.kind)
+                .collect();
+            assert_eq!(uses, [EdgeKind::Instantiates], "{}", path);
+        }
+
+        // Type arguments are still uses of their types
+        let code = "class Shapes { void make() { new Box<Circle>(); } }";
+        let result = extractor.extract_file("Shapes.java", code);
+        assert!(result
+            .unresolved_refs
+            .iter()
+            .any(|r| r.reference_name == "Circle" && r.kind == EdgeKind::References));
+    }
+
+    #[test]
+    fn test_instantiations() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+struct Cart;
+impl Cart {
+    fn make() -> Self {
+        let a = Self { items: vec![] };
+        let b = orders::Order { id: 1 };
+        let c = Vec::new();
+        let d = Config::default();
+        Cart::new()
+    }
+}
+"#;
+        let result = extractor.extract_file("lib.rs", code);
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            instantiations(&result),
+            vec![
+                ("Cart".to_string(), None),
+                ("Order".to_string(), some("orders")),
+                ("Vec".to_string(), None),
+                ("Config".to_string(), None),
+                ("Cart".to_string(), None),
+            ]
+        );
 
-        None
+        let result =
+            extractor.extract_file("app.ts", "function f() { return new shop.Cart<Item>(1); }");
+        assert_eq!(
+            instantiations(&result),
+            vec![("Cart".to_string(), some("shop"))]
+        );
+        assert!(call_receivers(&result).is_empty());
+
+        let code = "package main\nfunc f() { s := &Server{}; xs := []Item{} }";
+        let result = extractor.extract_file("main.go", code);
+        assert_eq!(instantiations(&result), vec![("Server".to_string(), None)]);
+
+        let result = extractor.extract_file(
+            "a.cpp",
+            "void f() { auto p = new Point(1, 2); auto q = Point{3, 4}; }",
+        );
+        assert_eq!(
+            instantiations(&result),
+            vec![("Point".to_string(), None), ("Point".to_string(), None)]
+        );
     }
 
-    fn get_node_text(&self, node: &tree_sitter::Node) -> String {
-        let start = node.start_byte();
-        let end = node.end_byte();
-        if start < self.content.len() && end <= self.content.len() {
-            self.content[start..end].to_string()
-        } else {
-            String::new()
-        }
+    #[test]
+    fn test_java_call_receivers() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Cart {
+    void checkout() {
+        this.validate();
+        repo.save();
+        new Order();
     }
 }
+"#;
+        let mut result = extractor.extract_file("Cart.java", code);
+        // Only the calls attributed to the method itself
+        let checkout = result
+            .nodes
+            .iter()
+            .find(|n| n.name == "checkout")
+            .unwrap()
+            .id;
+        result
+            .unresolved_refs
+            .retain(|r| r.source_node_id == checkout);
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            call_receivers(&result),
+            vec![
+                ("validate".to_string(), some("Cart")),
+                ("save".to_string(), some("repo")),
+            ]
+        );
+        // Constructors are instantiations, not calls
+        assert_eq!(instantiations(&result), vec![("Order".to_string(), None)]);
+    }
 
-impl Default for Extractor {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_csharp_calls_and_instantiations() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Cart
+{
+    void Checkout(Payment payment)
+    {
+        var items = new List<Item>();
+        var order = new Shop.Order(items);
+        this.Validate();
+        payment.Charge(Total());
+        Log.Write<string>("done");
+        payment.Amount = items.Count;
+    }
+}
+"#;
+        let result = extractor.extract_file("Cart.cs", code);
+        assert_eq!(
+            call_receivers(&result),
+            vec![
+                ("Validate".to_string(), Some("Cart".to_string())),
+                ("Charge".to_string(), Some("Payment".to_string())),
+                ("Total".to_string(), None),
+                ("Write".to_string(), Some("Log".to_string())),
+            ]
+        );
+        assert_eq!(
+            instantiations(&result),
+            vec![
+                ("List".to_string(), None),
+                ("Order".to_string(), Some("Shop".to_string())),
+            ]
+        );
+        let members: Vec<(String, Option<String>)> = result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::References && r.receiver.is_some())
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect();
+        // The constructed type is only recorded as instantiated
+        assert_eq!(
+            members,
+            vec![
+                ("Amount".to_string(), Some("Payment".to_string())),
+                ("Count".to_string(), Some("items".to_string())),
+            ]
+        );
     }
+
+    #[test]
+    fn test_go_method_receiver() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+package main
+
+func (s *Server) Start() {
+    s.listen()
+    fmt.Println()
 }
+"#;
+        let result = extractor.extract_file("test.go", code);
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            call_receivers(&result),
+            vec![
+                ("listen".to_string(), some("Server")),
+                ("Println".to_string(), some("fmt")),
+            ]
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn import_refs(result: &ExtractionResult) -> Vec<(String, Option<String>)> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Imports)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect()
+    }
 
     #[test]
-    fn test_extractor_creation() {
-        let extractor = Extractor::new();
-        assert!(std::mem::size_of_val(&extractor) > 0);
+    fn test_rust_use_imports() {
+        let mut extractor = Extractor::new();
+        let code = "use crate::db::{Database, schema::SCHEMA as S, self};\nuse std::fmt;";
+        let result = extractor.extract_file("test.rs", code);
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            import_refs(&result),
+            vec![
+                ("Database".to_string(), some("crate::db")),
+                ("SCHEMA".to_string(), some("crate::db::schema")),
+                ("fmt".to_string(), some("std")),
+            ]
+        );
     }
 
     #[test]
-    fn test_extract_unsupported_extension() {
+    fn test_typescript_imports() {
         let mut extractor = Extractor::new();
-        let result = extractor.extract_file("test.xyz", "some content");
-        assert!(!result.errors.is_empty());
-        assert!(result.errors[0]
-            .message
-            .contains("Unsupported file extension"));
+        let code = r#"import Client, { fetch, post as send } from "./http";"#;
+        let result = extractor.extract_file("test.ts", code);
+        let module = Some("./http".to_string());
+        assert_eq!(
+            import_refs(&result),
+            vec![
+                ("Client".to_string(), module.clone()),
+                ("fetch".to_string(), module.clone()),
+                ("post".to_string(), module),
+            ]
+        );
     }
 
-    // Rust extraction tests
     #[test]
-    fn test_extract_rust_function() {
+    fn test_python_and_java_imports() {
+        let mut extractor = Extractor::new();
+        let result = extractor.extract_file("test.py", "from .models import User, Order as O");
+        let module = Some(".models".to_string());
+        assert_eq!(
+            import_refs(&result),
+            vec![
+                ("User".to_string(), module.clone()),
+                ("Order".to_string(), module)
+            ]
+        );
+
+        let result = extractor.extract_file("A.java", "import com.shop.Cart;\nimport com.shop.*;");
+        assert_eq!(
+            import_refs(&result),
+            vec![("Cart".to_string(), Some("com.shop".to_string()))]
+        );
+    }
+
+    /// Type uses as (source symbol, edge kind, type name)
+    fn type_uses(result: &ExtractionResult) -> Vec<(String, EdgeKind, String)> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| {
+                matches!(
+                    r.kind,
+                    EdgeKind::TypeOf | EdgeKind::Returns | EdgeKind::References
+                )
+            })
+            .map(|r| {
+                let source = result.nodes.iter().find(|n| n.id == r.source_node_id);
+                (
+                    source.unwrap().name.clone(),
+                    r.kind,
+                    r.reference_name.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_type_uses() {
         let mut extractor = Extractor::new();
         let code = r#"
-fn hello_world() {
-    println!("Hello, world!");
+struct Cart<T: Item> { items: Vec<Order>, owner: auth::User }
+fn total(cart: &Cart<Line>) -> Result<Money, Error> {
+    let tax: Rate = rate();
+    let n = cart.len() as Count;
+    parse::<Amount>(n)
 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-        assert!(result.errors.is_empty());
+        let result = extractor.extract_file("lib.rs", code);
+        let uses = type_uses(&result);
+        let names = |kind: EdgeKind| -> Vec<String> {
+            uses.iter()
+                .filter(|u| u.1 == kind && ["Cart", "total"].contains(&u.0.as_str()))
+                .map(|u| u.2.clone())
+                .collect()
+        };
+        assert_eq!(
+            names(EdgeKind::TypeOf),
+            ["Vec", "Order", "User", "Cart", "Line", "Rate"]
+        );
+        // Fields and parameters carry their own declared types
+        let member_types: Vec<_> = uses
+            .iter()
+            .filter(|u| !["Cart", "total"].contains(&u.0.as_str()))
+            .map(|u| (u.0.as_str(), u.2.as_str()))
+            .collect();
+        assert_eq!(
+            member_types,
+            [
+                ("items", "Vec"),
+                ("items", "Order"),
+                ("owner", "User"),
+                ("cart", "Cart"),
+                ("cart", "Line")
+            ]
+        );
+        assert_eq!(names(EdgeKind::Returns), ["Result", "Money", "Error"]);
+        assert_eq!(names(EdgeKind::References), ["Count", "Amount"]);
+        let user = result
+            .unresolved_refs
+            .iter()
+            .find(|r| r.reference_name == "User")
+            .unwrap();
+        assert_eq!(user.receiver.as_deref(), Some("auth"));
+    }
 
-        // Should have file node + function node
-        assert!(result.nodes.len() >= 2);
+    #[test]
+    fn test_type_uses_in_other_languages() {
+        let mut extractor = Extractor::new();
+        let code = "function ship(order: Order, to?: Address): Promise<Receipt> { return send(order as Parcel); }";
+        let result = extractor.extract_file("app.ts", code);
+        let uses = type_uses(&result);
+        assert_eq!(
+            uses[..5],
+            [
+                ("ship".to_string(), EdgeKind::TypeOf, "Order".to_string()),
+                ("ship".to_string(), EdgeKind::TypeOf, "Address".to_string()),
+                ("ship".to_string(), EdgeKind::Returns, "Promise".to_string()),
+                ("ship".to_string(), EdgeKind::Returns, "Receipt".to_string()),
+                (
+                    "ship".to_string(),
+                    EdgeKind::References,
+                    "Parcel".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            uses[5..],
+            [
+                ("order".to_string(), EdgeKind::TypeOf, "Order".to_string()),
+                ("to".to_string(), EdgeKind::TypeOf, "Address".to_string()),
+            ]
+        );
 
-        let func = result.nodes.iter().find(|n| n.name == "hello_world");
-        assert!(func.is_some());
-        let func = func.unwrap();
-        assert_eq!(func.kind, NodeKind::Function);
-        assert_eq!(func.language, Language::Rust);
+        let code = "def ship(order: models.Order, count: int) -> Receipt:\n    pass";
+        let result = extractor.extract_file("app.py", code);
+        let names: Vec<_> = type_uses(&result).into_iter().map(|u| (u.1, u.2)).collect();
+        assert_eq!(
+            names,
+            [
+                (EdgeKind::TypeOf, "Order".to_string()),
+                (EdgeKind::Returns, "Receipt".to_string()),
+                (EdgeKind::TypeOf, "Order".to_string()),
+            ]
+        );
+
+        let code = "package main\ntype Server struct { Base\n cfg *Config }\nfunc (s *Server) Run(ctx Context) error { return nil }";
+        let result = extractor.extract_file("main.go", code);
+        assert_eq!(
+            type_uses(&result),
+            [
+                ("Server".to_string(), EdgeKind::TypeOf, "Config".to_string()),
+                ("cfg".to_string(), EdgeKind::TypeOf, "Config".to_string()),
+                ("Run".to_string(), EdgeKind::TypeOf, "Context".to_string()),
+                ("ctx".to_string(), EdgeKind::TypeOf, "Context".to_string()),
+            ]
+        );
+
+        let code = "class Shop extends Base { Order place(Cart cart) { Item i = (Item) cart.first(); return null; } }";
+        let result = extractor.extract_file("Shop.java", code);
+        let names: Vec<_> = type_uses(&result).into_iter().map(|u| (u.1, u.2)).collect();
+        assert_eq!(
+            names,
+            [
+                (EdgeKind::Returns, "Order".to_string()),
+                (EdgeKind::TypeOf, "Cart".to_string()),
+                (EdgeKind::TypeOf, "Item".to_string()),
+                (EdgeKind::References, "Item".to_string()),
+                (EdgeKind::TypeOf, "Cart".to_string()),
+            ]
+        );
     }
 
     #[test]
-    fn test_extract_rust_pub_function() {
+    fn test_csharp_type_uses() {
         let mut extractor = Extractor::new();
         let code = r#"
-pub fn public_function() -> i32 {
-    42
+class Ledger<T> where T : IEntry
+{
+    private Dictionary<string, Account> accounts;
+    public Money Balance { get; }
+    public Shop.Receipt Post(Entry entry, int count) { Audit log = null; return null; }
 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-        let func = result
+        let result = extractor.extract_file("Ledger.cs", code);
+        let uses = type_uses(&result);
+        let has = |source: &str, kind: EdgeKind, name: &str| {
+            uses.contains(&(source.to_string(), kind, name.to_string()))
+        };
+        assert!(
+            has("accounts", EdgeKind::TypeOf, "Dictionary"),
+            "{:?}",
+            uses
+        );
+        assert!(has("accounts", EdgeKind::TypeOf, "Account"));
+        assert!(has("Balance", EdgeKind::TypeOf, "Money"));
+        assert!(has("Post", EdgeKind::Returns, "Receipt"));
+        assert!(has("entry", EdgeKind::TypeOf, "Entry"));
+        assert!(has("Post", EdgeKind::TypeOf, "Audit"));
+        // Built-in types, names and generic constraints are not type uses
+        assert!(!uses.iter().any(|(_, _, name)| matches!(
+            name.as_str(),
+            "string" | "int" | "IEntry" | "T" | "log" | "Ledger"
+        )));
+    }
+
+    fn module_imports(result: &ExtractionResult) -> Vec<(String, String)> {
+        result
+            .imports
+            .iter()
+            .map(|i| {
+                let node = result.nodes.iter().find(|n| n.id == i.source_node_id);
+                (node.unwrap().name.clone(), i.module.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_module_imports() {
+        let mut extractor = Extractor::new();
+        let code = "mod db;\nmod inline {}\nuse crate::db::{self, search::*, Node as N};";
+        let result = extractor.extract_file("lib.rs", code);
+        let modules: Vec<_> = module_imports(&result)
+            .into_iter()
+            .map(|(_, m)| m)
+            .collect();
+        assert_eq!(
+            modules,
+            [
+                "self::db",
+                "crate::db",
+                "crate::db::search",
+                "crate::db::Node"
+            ]
+        );
+
+        let code = "import os, shop.models as m\nfrom . import views\nfrom ..api import *";
+        let result = extractor.extract_file("app.py", code);
+        assert_eq!(
+            module_imports(&result),
+            [
+                ("os, shop.models".to_string(), "os".to_string()),
+                ("os, shop.models".to_string(), "shop.models".to_string()),
+                (".".to_string(), ".views".to_string()),
+                ("..api".to_string(), "..api".to_string()),
+            ]
+        );
+
+        let code = "import Client from \"./http\";\nimport \"./styles.css\";";
+        let result = extractor.extract_file("app.ts", code);
+        let names: Vec<_> = module_imports(&result)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names, ["./http", "./styles.css"]);
+
+        let code = "package main\nimport (\n\t\"fmt\"\n\tc \"example.com/shop/cart\"\n)";
+        let result = extractor.extract_file("main.go", code);
+        let names: Vec<_> = module_imports(&result)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names, ["fmt", "example.com/shop/cart"]);
+
+        let result = extractor.extract_file("A.java", "import static com.shop.Cart.EMPTY;");
+        let names: Vec<_> = module_imports(&result)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names, ["com.shop.Cart.EMPTY"]);
+
+        let result = extractor.extract_file("main.c", "#include <stdio.h>\n#include \"util.h\"");
+        let names: Vec<_> = module_imports(&result)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(names, ["<stdio.h>", "util.h"]);
+    }
+
+    #[test]
+    fn test_csharp_using_directives() {
+        let mut extractor = Extractor::new();
+        let code = "using System;\nusing static System.Math;\nusing Json = System.Text.Json;\n";
+        let result = extractor.extract_file("App.cs", code);
+        assert_eq!(
+            module_imports(&result),
+            vec![
+                ("System".to_string(), "System".to_string()),
+                ("System.Math".to_string(), "System.Math".to_string()),
+                (
+                    "System.Text.Json".to_string(),
+                    "System.Text.Json".to_string()
+                ),
+            ]
+        );
+    }
+
+    fn decorators(result: &ExtractionResult, name: &str) -> Vec<String> {
+        let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+        node.decorators
+            .iter()
+            .map(|d| match &d.arguments {
+                Some(args) => format!("{}({})", d.name, args),
+                None => d.name.clone(),
+            })
+            .collect()
+    }
+
+    fn decorates_refs(result: &ExtractionResult) -> Vec<(String, Option<String>)> {
+        result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Decorates)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_decorators() {
+        let mut extractor = Extractor::new();
+
+        let code = r#"
+#[derive(Debug, Clone)]
+/// A handler
+#[doc = "more docs"]
+struct Handler;
+
+#[tokio::main]
+async fn main() {}
+"#;
+        let result = extractor.extract_file("main.rs", code);
+        assert_eq!(decorators(&result, "Handler"), ["derive(Debug, Clone)"]);
+        assert_eq!(decorators(&result, "main"), ["tokio::main"]);
+        // derive is built in; tokio::main may be defined elsewhere
+        assert_eq!(
+            decorates_refs(&result),
+            [("main".to_string(), Some("tokio".to_string()))]
+        );
+
+        let code = r#"
+@dataclass
+class User:
+    @property
+    def name(self): pass
+
+    @app.route("/users", methods=["GET"])
+    def list(self): pass
+"#;
+        let result = extractor.extract_file("models.py", code);
+        assert_eq!(decorators(&result, "User"), ["dataclass"]);
+        assert_eq!(decorators(&result, "name"), ["property"]);
+        assert_eq!(
+            decorators(&result, "list"),
+            [r#"app.route("/users", methods=["GET"])"#]
+        );
+        // Decorator calls are not calls made by the class
+        assert!(!result
+            .unresolved_refs
+            .iter()
+            .any(|r| r.kind == EdgeKind::Calls));
+
+        let code = r#"
+@Service
+public class Users {
+    @Transactional(readOnly = true)
+    @Override
+    public void find() {}
+}
+@interface Audited {}
+"#;
+        let result = extractor.extract_file("Users.java", code);
+        assert_eq!(decorators(&result, "Users"), ["Service"]);
+        assert_eq!(
+            decorators(&result, "find"),
+            ["Transactional(readOnly = true)", "Override"]
+        );
+        assert!(result
             .nodes
             .iter()
-            .find(|n| n.name == "public_function")
-            .unwrap();
-        assert_eq!(func.visibility, Visibility::Public);
-        assert!(func.signature.is_some());
+            .any(|n| n.name == "Audited" && n.kind == NodeKind::Interface));
+        assert_eq!(
+            decorates_refs(&result),
+            [
+                ("Service".to_string(), None),
+                ("Transactional".to_string(), None)
+            ]
+        );
+
+        let code = r#"
+@Component({ selector: "app-user" })
+export class UserComponent {
+    @HostListener("click")
+    onClick() {}
+}
+"#;
+        let result = extractor.extract_file("user.component.ts", code);
+        assert_eq!(
+            decorators(&result, "UserComponent"),
+            [r#"Component({ selector: "app-user" })"#]
+        );
+        assert_eq!(decorators(&result, "onClick"), [r#"HostListener("click")"#]);
     }
 
     #[test]
-    fn test_extract_rust_struct() {
+    fn test_csharp_attributes() {
         let mut extractor = Extractor::new();
         let code = r#"
-pub struct MyStruct {
-    field1: i32,
-    field2: String,
+[Serializable]
+[Table("orders"), Audited]
+public class Order
+{
+    [Required, MaxLength(40)] public string Name { get; set; }
+    [HttpGet("{id}")]
+    public Order Get(int id) => null;
 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-        let strukt = result.nodes.iter().find(|n| n.name == "MyStruct");
-        assert!(strukt.is_some());
-        assert_eq!(strukt.unwrap().kind, NodeKind::Struct);
+        let result = extractor.extract_file("Order.cs", code);
+        assert_eq!(
+            decorators(&result, "Order"),
+            vec!["Serializable", "Table(\"orders\")", "Audited"]
+        );
+        assert_eq!(
+            decorators(&result, "Name"),
+            vec!["Required", "MaxLength(40)"]
+        );
+        assert_eq!(decorators(&result, "Get"), vec!["HttpGet(\"{id}\")"]);
+        // Attributes are not part of the signature
+        let get = result.nodes.iter().find(|n| n.name == "Get").unwrap();
+        assert_eq!(get.signature.as_deref(), Some("public Order Get(int id)"));
+        // `[Table]` is declared as `TableAttribute`; built-in ones are skipped
+        let refs: Vec<String> = decorates_refs(&result)
+            .into_iter()
+            .map(|(n, _)| n)
+            .collect();
+        assert_eq!(
+            refs,
+            vec!["TableAttribute", "AuditedAttribute", "HttpGetAttribute"]
+        );
+    }
+
+    fn arity(result: &ExtractionResult, name: &str) -> Option<u32> {
+        result.nodes.iter().find(|n| n.name == name).unwrap().arity
     }
 
     #[test]
-    fn test_extract_rust_impl_methods() {
+    fn test_arity() {
         let mut extractor = Extractor::new();
+
         let code = r#"
-struct Foo {}
+struct S;
+impl S {
+    fn method(&self, a: i32, b: i32) {}
+    fn assoc(a: i32) {}
+}
+trait T {
+    fn required(&mut self, x: u8);
+}
+"#;
+        let result = extractor.extract_file("a.rs", code);
+        assert_eq!(arity(&result, "method"), Some(2));
+        assert_eq!(arity(&result, "assoc"), Some(1));
+        assert_eq!(arity(&result, "required"), Some(1));
+        assert_eq!(arity(&result, "S"), None);
 
-impl Foo {
-    fn bar(&self) {
+        let code = "class A:\n    def f(self, a, *args, b=1, *, c, **kw): pass\ndef g(): pass\n";
+        let result = extractor.extract_file("a.py", code);
+        assert_eq!(arity(&result, "f"), Some(5));
+        assert_eq!(arity(&result, "g"), Some(0));
+
+        let code = "package a\nfunc (s *S) f(a, b int, c ...string) {}\n";
+        let result = extractor.extract_file("a.go", code);
+        assert_eq!(arity(&result, "f"), Some(3));
+
+        let code = "int main(void) { return 0; }\nint add(int a, int b) { return a + b; }\n";
+        let result = extractor.extract_file("a.c", code);
+        assert_eq!(arity(&result, "main"), Some(0));
+        assert_eq!(arity(&result, "add"), Some(2));
+
+        let code = "const inc = x => x + 1;\nfunction f(a, b = 2, ...rest) {}\n";
+        let result = extractor.extract_file("a.ts", code);
+        assert_eq!(arity(&result, "f"), Some(3));
     }
 
-    pub fn baz() -> Self {
-        Foo {}
+    #[test]
+    fn test_cpp_method_declarations() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Handler : public Base {
+public:
+    virtual Foo* make(int a) = 0;
+    const Bar& get() const override;
+    void run(int a, int b) override {}
+    int count;
+};
+"#;
+        let result = extractor.extract_file("handler.hpp", code);
+        let methods: Vec<(&str, Option<u32>)> = result
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.kind, NodeKind::Function | NodeKind::Method))
+            .map(|n| (n.name.as_str(), n.arity))
+            .collect();
+        assert_eq!(
+            methods,
+            [("make", Some(1)), ("get", Some(0)), ("run", Some(2))]
+        );
+        // Data members are fields, not methods
+        let count = result.nodes.iter().find(|n| n.name == "count").unwrap();
+        assert_eq!(count.kind, NodeKind::Field);
     }
+
+    #[test]
+    fn test_rust_trait_impl_overrides() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Ok(()) }
+}
+impl Money {
+    fn new() -> Self { Money }
 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
+        let result = extractor.extract_file("money.rs", code);
+        let overrides: Vec<(String, Option<String>)> = result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::Overrides)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect();
+        assert_eq!(
+            overrides,
+            [("fmt".to_string(), Some("Display".to_string()))]
+        );
+    }
 
-        // Should find struct and methods
-        assert!(result.nodes.iter().any(|n| n.name == "Foo"));
-        assert!(result.nodes.iter().any(|n| n.name == "bar"));
-        assert!(result.nodes.iter().any(|n| n.name == "baz"));
+    /// (kind, name, owner) of every field, property, enum member and parameter
+    fn members(result: &ExtractionResult) -> Vec<(NodeKind, String, String)> {
+        result
+            .nodes
+            .iter()
+            .filter(|n| n.kind.is_member() || n.kind == NodeKind::Constant)
+            .map(|n| {
+                let owner = result
+                    .edges
+                    .iter()
+                    .find(|e| e.kind == EdgeKind::Contains && e.target_id == n.id)
+                    .and_then(|e| result.nodes.iter().find(|p| p.id == e.source_id))
+                    .map(|p| p.name.clone())
+                    .unwrap_or_default();
+                (n.kind, n.name.clone(), owner)
+            })
+            .collect()
+    }
+
+    fn member(kind: NodeKind, name: &str, owner: &str) -> (NodeKind, String, String) {
+        (kind, name.to_string(), owner.to_string())
     }
 
     #[test]
-    fn test_extract_rust_enum() {
+    fn test_members() {
+        use NodeKind::{Constant, EnumMember, Field, Parameter, Property};
         let mut extractor = Extractor::new();
+
         let code = r#"
-enum Color {
-    Red,
-    Green,
-    Blue,
+pub struct Account { pub balance: i64, owner: String }
+enum Event { Closed, Moved { to: Place } }
+fn deposit(&mut self, amount: i64) {}
+"#;
+        let result = extractor.extract_file("account.rs", code);
+        assert_eq!(
+            members(&result),
+            [
+                member(Field, "balance", "Account"),
+                member(Field, "owner", "Account"),
+                member(EnumMember, "Closed", "Event"),
+                member(EnumMember, "Moved", "Event"),
+                member(Field, "to", "Moved"),
+                member(Parameter, "amount", "deposit"),
+            ]
+        );
+        let balance = result.nodes.iter().find(|n| n.name == "balance").unwrap();
+        assert_eq!(balance.qualified_name.as_deref(), Some("Account::balance"));
+        assert_eq!(balance.visibility, Visibility::Public);
+        assert_eq!(balance.signature.as_deref(), Some("pub balance: i64"));
+
+        let code = r#"
+class Account { private balance: number = 0; deposit(amount: number, ...notes: string[]) {} }
+interface Shape { area: number }
+enum Color { Red, Green = 2 }
+"#;
+        assert_eq!(
+            members(&extractor.extract_file("account.ts", code)),
+            [
+                member(Property, "balance", "Account"),
+                member(Parameter, "amount", "deposit"),
+                member(Parameter, "notes", "deposit"),
+                member(Property, "area", "Shape"),
+                member(EnumMember, "Red", "Color"),
+                member(EnumMember, "Green", "Color"),
+            ]
+        );
+
+        let code = "class Account:\n    balance: int = 0\n    def __init__(self, owner, *args):\n        self.owner = owner\n    @property\n    def total(self):\n        return self.balance\n";
+        let result = extractor.extract_file("account.py", code);
+        assert_eq!(
+            members(&result),
+            [
+                member(Field, "balance", "Account"),
+                member(Parameter, "owner", "__init__"),
+                member(Parameter, "args", "__init__"),
+                member(Property, "total", "Account"),
+            ]
+        );
+
+        let code = "package bank\ntype Account struct { Base\n balance, limit int64 }";
+        assert_eq!(
+            members(&extractor.extract_file("account.go", code)),
+            [
+                member(Field, "balance", "Account"),
+                member(Field, "limit", "Account"),
+            ]
+        );
+
+        let code = "class Account { private long balance; static final int MAX = 10; }\nenum Color { RED, GREEN(2) }";
+        assert_eq!(
+            members(&extractor.extract_file("Account.java", code)),
+            [
+                member(Field, "balance", "Account"),
+                member(Constant, "MAX", "Account"),
+                member(EnumMember, "RED", "Color"),
+                member(EnumMember, "GREEN", "Color"),
+            ]
+        );
+
+        let code = "struct account { long balance; char *owner; };\nenum color { RED };";
+        assert_eq!(
+            members(&extractor.extract_file("account.c", code)),
+            [
+                member(Field, "balance", "account"),
+                member(Field, "owner", "account"),
+                member(EnumMember, "RED", "color"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csharp_members() {
+        use NodeKind::{Constant, EnumMember, Field, Parameter, Property};
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Account
+{
+    private decimal balance, limit;
+    public const int MaxOwners = 2;
+    public event EventHandler Changed;
+    public string Owner { get; private set; }
+    public void Deposit(decimal amount, params string[] notes) { }
 }
+record Point(int X, int Y);
+enum Status { Open, Closed = 2 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-        let enum_node = result.nodes.iter().find(|n| n.name == "Color");
-        assert!(enum_node.is_some());
-        assert_eq!(enum_node.unwrap().kind, NodeKind::Enum);
+        let result = extractor.extract_file("Account.cs", code);
+        assert_eq!(
+            members(&result),
+            vec![
+                member(Field, "balance", "Account"),
+                member(Field, "limit", "Account"),
+                member(Constant, "MaxOwners", "Account"),
+                member(Field, "Changed", "Account"),
+                member(Property, "Owner", "Account"),
+                member(Parameter, "amount", "Deposit"),
+                member(Parameter, "notes", "Deposit"),
+                member(Property, "X", "Point"),
+                member(Property, "Y", "Point"),
+                member(EnumMember, "Open", "Status"),
+                member(EnumMember, "Closed", "Status"),
+            ]
+        );
+        let owner = result.nodes.iter().find(|n| n.name == "Owner").unwrap();
+        assert_eq!(
+            owner.signature.as_deref(),
+            Some("public string Owner { get; private set; }")
+        );
+        let x = result.nodes.iter().find(|n| n.name == "X").unwrap();
+        assert_eq!(x.visibility, Visibility::Public);
+        assert!(result
+            .unresolved_refs
+            .iter()
+            .any(|r| r.kind == EdgeKind::TypeOf
+                && r.reference_name == "EventHandler"
+                && result.nodes[r.source_node_id as usize - 1].name == "Changed"));
+    }
+
+    #[test]
+    fn test_member_access() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+impl Account {
+    fn deposit(&mut self, amount: i64) { self.balance += amount; self.log.push(amount); other.limit; }
+}
+"#;
+        let result = extractor.extract_file("account.rs", code);
+        let accesses: Vec<(String, Option<String>)> = result
+            .unresolved_refs
+            .iter()
+            .filter(|r| r.kind == EdgeKind::References)
+            .map(|r| (r.reference_name.clone(), r.receiver.clone()))
+            .collect();
+        assert_eq!(
+            accesses,
+            [
+                ("balance".to_string(), Some("Account".to_string())),
+                ("log".to_string(), Some("Account".to_string())),
+                ("limit".to_string(), Some("other".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_out_of_body_methods() {
+        let mut extractor = Extractor::new();
+        let methods = |result: &ExtractionResult| -> Vec<(NodeKind, String, String)> {
+            result
+                .nodes
+                .iter()
+                .filter(|n| matches!(n.kind, NodeKind::Function | NodeKind::Method))
+                .map(|n| {
+                    let owner = result
+                        .unresolved_refs
+                        .iter()
+                        .find(|r| r.kind == EdgeKind::Contains && r.source_node_id == n.id)
+                        .map(|r| r.reference_name.clone())
+                        .unwrap_or_default();
+                    (n.kind, n.qualified_name.clone().unwrap(), owner)
+                })
+                .collect()
+        };
+        let method = |kind: NodeKind, qualified: &str, owner: &str| {
+            (kind, qualified.to_string(), owner.to_string())
+        };
+
+        let code = r#"
+impl<T> Cart<T> { fn total(&self) -> u32 { 0 } }
+impl fmt::Display for Cart<u8> { fn fmt(&self) {} }
+trait Priced { fn price(&self) -> u32 { 0 } }
+fn helper() {}
+"#;
+        assert_eq!(
+            methods(&extractor.extract_file("cart.rs", code)),
+            [
+                method(NodeKind::Method, "Cart::total", "Cart"),
+                method(NodeKind::Method, "Cart::fmt", "Cart"),
+                method(NodeKind::Method, "Priced::price", ""),
+                method(NodeKind::Function, "helper", ""),
+            ]
+        );
+
+        let code =
+            "package main\nfunc (s *Server) Run() {}\nfunc (c Cache[K]) Get() {}\nfunc main() {}";
+        assert_eq!(
+            methods(&extractor.extract_file("server.go", code)),
+            [
+                method(NodeKind::Method, "Server::Run", "Server"),
+                method(NodeKind::Method, "Cache::Get", "Cache"),
+                method(NodeKind::Function, "main", ""),
+            ]
+        );
+
+        let code = r#"
+namespace util { int clamp(int x); }
+int util::clamp(int x) { return x; }
+void Stream::read(char* buf) {}
+Stream::~Stream() {}
+class Buffer { void flush() {} };
+"#;
+        assert_eq!(
+            methods(&extractor.extract_file("stream.cpp", code)),
+            [
+                method(NodeKind::Function, "clamp", ""),
+                method(NodeKind::Method, "Stream::read", "Stream"),
+                method(NodeKind::Method, "Stream::~Stream", "Stream"),
+                method(NodeKind::Method, "Buffer::flush", ""),
+            ]
+        );
     }
 
     #[test]
-    fn test_extract_rust_function_calls() {
+    fn test_function_expressions_named_by_binding() {
         let mut extractor = Extractor::new();
         let code = r#"
-fn caller() {
-    helper();
-    other_func();
-}
-
-fn helper() {}
-fn other_func() {}
+export const handleSubmit = async (event) => { save(event); };
+const local = function named() {};
+module.exports.load = function () {};
+exports.parse = text => text;
+const api = { post: () => send(), "remove": function () {} };
+class Form { onClick = () => { this.submit(); }; }
+late = function () {};
+export default () => 1;
+function render(items) { items.forEach((item) => { draw(item); }); }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-
-        // Should have unresolved references for the calls
-        assert!(!result.unresolved_refs.is_empty());
-        assert!(result
-            .unresolved_refs
+        let result = extractor.extract_file("form.js", code);
+        let functions: Vec<(NodeKind, &str, bool)> = result
+            .nodes
             .iter()
-            .any(|r| r.reference_name == "helper"));
-        assert!(result
+            .filter(|n| matches!(n.kind, NodeKind::Function | NodeKind::Method))
+            .map(|n| (n.kind, n.qualified_name.as_deref().unwrap(), n.is_exported))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                (NodeKind::Function, "handleSubmit", true),
+                (NodeKind::Function, "local", false),
+                (NodeKind::Function, "load", true),
+                (NodeKind::Function, "parse", true),
+                (NodeKind::Function, "post", false),
+                (NodeKind::Function, "remove", false),
+                (NodeKind::Method, "Form::onClick", false),
+                (NodeKind::Function, "late", false),
+                (NodeKind::Function, "default", true),
+                (NodeKind::Function, "render", false),
+            ]
+        );
+
+        // Calls in anonymous callbacks belong to the enclosing function
+        let calls: Vec<(&str, &str)> = result
             .unresolved_refs
             .iter()
-            .any(|r| r.reference_name == "other_func"));
+            .filter(|r| r.kind == EdgeKind::Calls)
+            .map(|r| {
+                let source = result.nodes.iter().find(|n| n.id == r.source_node_id);
+                (source.unwrap().name.as_str(), r.reference_name.as_str())
+            })
+            .collect();
+        assert_eq!(
+            calls,
+            [
+                ("handleSubmit", "save"),
+                ("post", "send"),
+                ("onClick", "submit"),
+                ("render", "forEach"),
+                ("render", "draw"),
+            ]
+        );
+        // Parameters of anonymous callbacks are not symbols
+        assert!(!result.nodes.iter().any(|n| n.name == "item"));
+
+        // Bound functions are signed with the binding they are named after
+        let signature = |name: &str| {
+            let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+            node.signature.as_deref()
+        };
+        assert_eq!(
+            signature("handleSubmit"),
+            Some("handleSubmit = async (event)")
+        );
+        assert_eq!(signature("local"), Some("local = function named()"));
+        assert_eq!(signature("load"), Some("module.exports.load = function ()"));
+        assert_eq!(signature("parse"), Some("exports.parse = text"));
+        assert_eq!(signature("post"), Some("post: ()"));
+        assert_eq!(signature("remove"), Some("\"remove\": function ()"));
+        assert_eq!(signature("onClick"), Some("onClick = ()"));
+        assert_eq!(signature("late"), Some("late = function ()"));
+
+        let result = extractor.extract_file(
+            "form.ts",
+            "const add = (a: number, b: number): number => a + b;",
+        );
+        let add = result.nodes.iter().find(|n| n.name == "add").unwrap();
+        assert_eq!(
+            add.signature.as_deref(),
+            Some("add = (a: number, b: number): number")
+        );
+    }
+
+    /// (visibility, is_async, is_static, is_exported) of the symbol `name`
+    fn modifiers(result: &ExtractionResult, name: &str) -> (Visibility, bool, bool, bool) {
+        let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+        (
+            node.visibility,
+            node.is_async,
+            node.is_static,
+            node.is_exported,
+        )
     }
 
     #[test]
-    fn test_extract_rust_async_function() {
+    fn test_rust_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
         let code = r#"
-async fn async_handler() {
-    do_something().await;
+pub struct Cart { pub(crate) items: Vec<u32> }
+impl Cart {
+    pub async fn checkout(&self) { log("static async pub"); }
+    pub fn new() -> Self { Cart { items: vec![] } }
 }
+pub(super) fn helper() {}
+pub trait Priced { fn price(&self) -> u32; }
+enum Size { Small }
 "#;
-        let result = extractor.extract_file("test.rs", code);
-        let func = result
-            .nodes
-            .iter()
-            .find(|n| n.name == "async_handler")
-            .unwrap();
-        assert!(func.is_async);
+        let result = extractor.extract_file("cart.rs", code);
+        assert_eq!(modifiers(&result, "Cart"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "items"), (Internal, false, false, false));
+        assert_eq!(modifiers(&result, "checkout"), (Public, true, false, true));
+        assert_eq!(modifiers(&result, "new"), (Public, false, true, true));
+        assert_eq!(
+            modifiers(&result, "helper"),
+            (Internal, false, false, false)
+        );
+        // Trait items and variants are as visible as their owner
+        assert_eq!(modifiers(&result, "price"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "Small"), (Private, false, false, false));
     }
 
-    // TypeScript extraction tests
     #[test]
-    fn test_extract_typescript_function() {
+    fn test_typescript_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
         let code = r#"
-function greet(name: string): string {
-    return `Hello, ${name}!`;
+export class Store {
+    private static count = 0;
+    protected async load() { const s = " static "; }
+    save() { /* async export */ }
+    #secret = 1;
 }
+function local() { return "export async"; }
+export const fetchAll = async () => {};
 "#;
-        let result = extractor.extract_file("test.ts", code);
-        assert!(result.errors.is_empty());
-
-        let func = result.nodes.iter().find(|n| n.name == "greet");
-        assert!(func.is_some());
-        assert_eq!(func.unwrap().language, Language::TypeScript);
+        let result = extractor.extract_file("store.ts", code);
+        // One async method does not make its class async
+        assert_eq!(modifiers(&result, "Store"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "count"), (Private, false, true, false));
+        assert_eq!(modifiers(&result, "load"), (Protected, true, false, false));
+        assert_eq!(modifiers(&result, "save"), (Public, false, false, false));
+        assert_eq!(
+            modifiers(&result, "#secret"),
+            (Private, false, false, false)
+        );
+        assert_eq!(modifiers(&result, "local"), (Private, false, false, false));
+        assert_eq!(modifiers(&result, "fetchAll"), (Public, true, false, true));
     }
 
     #[test]
-    fn test_extract_typescript_class() {
+    fn test_python_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
-        let code = r#"
-class MyClass {
-    private value: number;
-
-    constructor(val: number) {
-        this.value = val;
+        let code = "class Cache:\n    _store = None\n    def __init__(self): pass\n    def _evict(self): return ' static async '\n    def __mangled(self): pass\n    @staticmethod\n    def build(): pass\n    async def fetch(self): pass\n\ndef _helper(): pass\n";
+        let result = extractor.extract_file("cache.py", code);
+        assert_eq!(modifiers(&result, "Cache"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "_store"), (Private, false, false, false));
+        assert_eq!(modifiers(&result, "__init__"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "_evict"), (Private, false, false, false));
+        assert_eq!(
+            modifiers(&result, "__mangled"),
+            (Private, false, false, false)
+        );
+        assert_eq!(modifiers(&result, "build"), (Public, false, true, true));
+        assert_eq!(modifiers(&result, "fetch"), (Public, true, false, true));
+        assert_eq!(
+            modifiers(&result, "_helper"),
+            (Private, false, false, false)
+        );
     }
 
-    getValue(): number {
-        return this.value;
-    }
-}
-"#;
-        let result = extractor.extract_file("test.ts", code);
-        let class = result.nodes.iter().find(|n| n.name == "MyClass");
-        assert!(class.is_some());
-        assert_eq!(class.unwrap().kind, NodeKind::Class);
+    #[test]
+    fn test_go_modifiers() {
+        use Visibility::*;
+        let mut extractor = Extractor::new();
+        let code = "package web\ntype Server struct { Port int\n host string }\nfunc (s *Server) Run() {}\nfunc helper() { _ = \"static\" }";
+        let result = extractor.extract_file("server.go", code);
+        assert_eq!(modifiers(&result, "Server"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "Port"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "host"), (Private, false, false, false));
+        assert_eq!(modifiers(&result, "Run"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "helper"), (Private, false, false, false));
     }
 
     #[test]
-    fn test_extract_typescript_interface() {
+    fn test_java_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
         let code = r#"
-interface User {
-    name: string;
-    age: number;
+public class Registry {
+    int size;
+    private static int count;
+    protected void load() { String s = "public static"; }
 }
+interface Source { void read(); }
 "#;
-        let result = extractor.extract_file("test.ts", code);
-        let iface = result.nodes.iter().find(|n| n.name == "User");
-        assert!(iface.is_some());
-        assert_eq!(iface.unwrap().kind, NodeKind::Interface);
+        let result = extractor.extract_file("Registry.java", code);
+        assert_eq!(modifiers(&result, "Registry"), (Public, false, false, true));
+        // Package-private by default
+        assert_eq!(modifiers(&result, "size"), (Internal, false, false, false));
+        assert_eq!(modifiers(&result, "count"), (Private, false, true, false));
+        assert_eq!(modifiers(&result, "load"), (Protected, false, false, false));
+        assert_eq!(
+            modifiers(&result, "Source"),
+            (Internal, false, false, false)
+        );
+        assert_eq!(modifiers(&result, "read"), (Public, false, false, true));
     }
 
     #[test]
-    fn test_extract_typescript_arrow_function() {
+    fn test_c_and_cpp_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
+        let code =
+            "static int helper(void) { return 0; }\nint api(void) { /* static */ return 0; }";
+        let result = extractor.extract_file("api.c", code);
+        assert_eq!(modifiers(&result, "helper"), (Private, false, true, false));
+        assert_eq!(modifiers(&result, "api"), (Public, false, false, true));
+
         let code = r#"
-const add = (a: number, b: number): number => a + b;
+class Widget {
+    int hidden;
+public:
+    static int count;
+    void draw();
+protected:
+    virtual void layout() {}
+};
+struct Point { int x; };
 "#;
-        let result = extractor.extract_file("test.ts", code);
-        // Arrow functions are typically extracted as constants or variables
-        assert!(!result.nodes.is_empty());
+        let result = extractor.extract_file("widget.hpp", code);
+        assert_eq!(modifiers(&result, "hidden"), (Private, false, false, false));
+        assert_eq!(modifiers(&result, "count"), (Public, false, true, true));
+        assert_eq!(modifiers(&result, "draw"), (Public, false, false, true));
+        assert_eq!(
+            modifiers(&result, "layout"),
+            (Protected, false, false, false)
+        );
+        assert_eq!(modifiers(&result, "x"), (Public, false, false, true));
     }
 
-    // Python extraction tests
     #[test]
-    fn test_extract_python_function() {
+    fn test_csharp_modifiers() {
+        use Visibility::*;
         let mut extractor = Extractor::new();
         let code = r#"
-def hello_world():
-    print("Hello, world!")
+public static class Registry
+{
+    int size;
+    protected internal static async Task LoadAsync() { var s = "public static"; }
+    public Registry() { }
+    class Entry { }
+}
+interface ISource { void Read(); }
+enum Mode { Fast }
 "#;
-        let result = extractor.extract_file("test.py", code);
-        assert!(result.errors.is_empty());
+        let result = extractor.extract_file("Registry.cs", code);
+        assert_eq!(modifiers(&result, "Registry"), (Public, false, true, true));
+        // Members are private, top-level types internal by default
+        assert_eq!(modifiers(&result, "size"), (Private, false, false, false));
+        assert_eq!(
+            modifiers(&result, "LoadAsync"),
+            (Protected, true, true, false)
+        );
+        assert_eq!(modifiers(&result, "Entry"), (Private, false, false, false));
+        assert_eq!(
+            modifiers(&result, "ISource"),
+            (Internal, false, false, false)
+        );
+        assert_eq!(modifiers(&result, "Read"), (Public, false, false, true));
+        assert_eq!(modifiers(&result, "Fast"), (Public, false, false, true));
+    }
 
-        let func = result.nodes.iter().find(|n| n.name == "hello_world");
-        assert!(func.is_some());
-        assert_eq!(func.unwrap().language, Language::Python);
+    #[test]
+    fn test_split_callee() {
+        assert_eq!(split_callee("helper"), (None, "helper"));
+        assert_eq!(split_callee("a.b.c"), (Some("a.b"), "c"));
+        assert_eq!(split_callee("Foo::<T>::new"), (Some("Foo"), "new"));
+        assert_eq!(split_callee("ns.Foo<Map<K, V>>"), (Some("ns"), "Foo"));
+        assert_eq!(
+            split_callee("crate::util::round"),
+            (Some("crate::util"), "round")
+        );
+    }
+
+    fn docs(result: &ExtractionResult, name: &str) -> (Option<String>, DocTags) {
+        let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+        (
+            node.docstring.clone(),
+            node.doc_tags.clone().unwrap_or_default(),
+        )
     }
 
     #[test]
-    fn test_extract_python_class() {
+    fn test_rust_docs() {
         let mut extractor = Extractor::new();
         let code = r#"
-class MyClass:
-    def __init__(self, value):
-        self.value = value
+/// Total price of the cart.
+///
+/// # Arguments
+///
+/// * `discount` - Fraction taken off
+///
+/// # Errors
+///
+/// Fails when the cart is empty.
+///
+/// # Examples
+///
+/// ```
+/// let total = cart.total(0.1)?;
+/// ```
+#[inline]
+pub fn total(discount: f64) -> Result<f64> { Ok(0.0) }
 
-    def get_value(self):
-        return self.value
+#[doc = "Most items in a cart"]
+pub const MAX_ITEMS: usize = 10;
+
+// Not documentation
+fn helper() {}
+
+/// Detached from the function
+
+fn detached() {}
 "#;
-        let result = extractor.extract_file("test.py", code);
-        let class = result.nodes.iter().find(|n| n.name == "MyClass");
-        assert!(class.is_some());
-        assert_eq!(class.unwrap().kind, NodeKind::Class);
+        let result = extractor.extract_file("cart.rs", code);
+        let (doc, tags) = docs(&result, "total");
+        let doc = doc.unwrap();
+        assert!(doc.starts_with("Total price of the cart.\n\n# Arguments\n"));
+        assert!(doc.ends_with("```"));
+        assert_eq!(tags.params[0].name, "discount");
+        assert_eq!(tags.params[0].description, "Fraction taken off");
+        assert_eq!(tags.throws[0].description, "Fails when the cart is empty.");
+        assert_eq!(tags.examples, vec!["let total = cart.total(0.1)?;"]);
 
-        // Should also find methods
-        assert!(result.nodes.iter().any(|n| n.name == "__init__"));
-        assert!(result.nodes.iter().any(|n| n.name == "get_value"));
+        let (doc, tags) = docs(&result, "MAX_ITEMS");
+        assert_eq!(doc.as_deref(), Some("Most items in a cart"));
+        assert_eq!(tags, DocTags::default());
+        assert_eq!(docs(&result, "helper").0, None);
+        assert_eq!(docs(&result, "detached").0, None);
     }
 
     #[test]
-    fn test_extract_python_async_function() {
+    fn test_python_docs() {
         let mut extractor = Extractor::new();
         let code = r#"
-async def fetch_data():
-    await some_async_call()
+class Cart:
+    """A shopping cart."""
+
+    def total(self, discount):
+        """Total price of the cart.
+
+        Args:
+            discount (float): Fraction taken off,
+                between 0 and 1.
+
+        Returns:
+            float: The discounted total.
+
+        Raises:
+            ValueError: If the cart is empty.
+
+        Example:
+            >>> Cart().total(0.1)
+            0.0
+        """
+
+    def add(self, item, count=1):
+        """Add an item.
+
+        Parameters
+        ----------
+        item : str
+            Name of the item.
+        count : int
+            How many to add.
+        """
+
+    def remove(self, item):
+        """Remove an item.
+
+        :param item: Name of the item.
+        :returns: Whether it was present.
+        :raises KeyError: If it is unknown.
+        """
+
+# Not a docstring
+def helper():
+    return "not a docstring either"
 "#;
-        let result = extractor.extract_file("test.py", code);
-        let func = result
-            .nodes
+        let result = extractor.extract_file("cart.py", code);
+        assert_eq!(docs(&result, "Cart").0.as_deref(), Some("A shopping cart."));
+
+        let (doc, tags) = docs(&result, "total");
+        assert!(doc
+            .unwrap()
+            .starts_with("Total price of the cart.\n\nArgs:\n    discount"));
+        assert_eq!(tags.params[0].name, "discount");
+        assert_eq!(
+            tags.params[0].description,
+            "Fraction taken off, between 0 and 1."
+        );
+        assert_eq!(
+            tags.returns.as_deref(),
+            Some("float: The discounted total.")
+        );
+        assert_eq!(tags.throws[0].name, "ValueError");
+        assert_eq!(tags.examples, vec![">>> Cart().total(0.1)\n0.0"]);
+
+        let (_, tags) = docs(&result, "add");
+        let params: Vec<_> = tags
+            .params
             .iter()
-            .find(|n| n.name == "fetch_data")
-            .unwrap();
-        assert!(func.is_async);
+            .map(|p| (p.name.as_str(), p.description.as_str()))
+            .collect();
+        assert_eq!(
+            params,
+            vec![("item", "Name of the item."), ("count", "How many to add.")]
+        );
+
+        let (_, tags) = docs(&result, "remove");
+        assert_eq!(tags.params[0].name, "item");
+        assert_eq!(tags.returns.as_deref(), Some("Whether it was present."));
+        assert_eq!(tags.throws[0].name, "KeyError");
+
+        assert_eq!(docs(&result, "helper").0, None);
     }
 
-    // JavaScript extraction tests
     #[test]
-    fn test_extract_javascript_function() {
+    fn test_typescript_docs() {
         let mut extractor = Extractor::new();
         let code = r#"
-function processData(data) {
-    return data.map(x => x * 2);
+/**
+ * Load a user by id.
+ *
+ * @param {string} id - The user's id
+ * @returns The user, if found
+ * @throws {NotFoundError} When there is no such user
+ * @example
+ *   const user = await load("42");
+ */
+export async function load(id: string): Promise<User> {}
+
+/** Format a user's name. */
+export const format = (user: User) => user.name;
+
+class Store {
+    /** Items in the store */
+    items: Item[] = [];
+
+    // Not documentation
+    save() {}
 }
 "#;
-        let result = extractor.extract_file("test.js", code);
-        let func = result.nodes.iter().find(|n| n.name == "processData");
-        assert!(func.is_some());
-        assert_eq!(func.unwrap().language, Language::JavaScript);
+        let result = extractor.extract_file("users.ts", code);
+        let (doc, tags) = docs(&result, "load");
+        assert!(doc.unwrap().starts_with("Load a user by id.\n\n@param"));
+        assert_eq!(tags.params[0].name, "id");
+        assert_eq!(tags.params[0].description, "The user's id");
+        assert_eq!(tags.returns.as_deref(), Some("The user, if found"));
+        assert_eq!(tags.throws[0].name, "NotFoundError");
+        assert_eq!(tags.examples, vec!["const user = await load(\"42\");"]);
+
+        assert_eq!(
+            docs(&result, "format").0.as_deref(),
+            Some("Format a user's name.")
+        );
+        assert_eq!(
+            docs(&result, "items").0.as_deref(),
+            Some("Items in the store")
+        );
+        assert_eq!(docs(&result, "save").0, None);
     }
 
-    // Go extraction tests
     #[test]
-    fn test_extract_go_function() {
+    fn test_go_and_cpp_docs() {
         let mut extractor = Extractor::new();
         let code = r#"
-func main() {
-    fmt.Println("Hello, World!")
-}
+package shop
 
-func helper(x int) int {
-    return x * 2
+// Handler serves requests.
+//
+//go:noinline
+func Handler() {}
+
+//go:generate stringer -type=Kind
+// Kind of an item.
+type Kind int
+
+var x = 1 // trailing
+func Trailing() {}
+"#;
+        let result = extractor.extract_file("shop.go", code);
+        assert_eq!(
+            docs(&result, "Handler").0.as_deref(),
+            Some("Handler serves requests.")
+        );
+        assert_eq!(docs(&result, "Kind").0.as_deref(), Some("Kind of an item."));
+        assert_eq!(docs(&result, "Trailing").0, None);
+
+        let code = r#"
+/**
+ * Largest of two values.
+ * \param a First value
+ * \param b Second value
+ * \return The larger one
+ */
+template <typename T>
+T max(T a, T b) { return a > b ? a : b; }
+"#;
+        let result = extractor.extract_file("max.cpp", code);
+        let (doc, tags) = docs(&result, "max");
+        assert!(doc.unwrap().starts_with("Largest of two values."));
+        let params: Vec<_> = tags.params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(params, vec!["a", "b"]);
+        assert_eq!(tags.returns.as_deref(), Some("The larger one"));
+    }
+
+    #[test]
+    fn test_csharp_docs() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+class Pricing
+{
+    /// <summary>Total price of the cart.</summary>
+    /// <param name="discount">Fraction taken off.</param>
+    /// <returns>The total.</returns>
+    /// <exception cref="ArgumentException">When negative.</exception>
+    [Pure]
+    public decimal Total(decimal discount) => 0;
+
+    //// Not documentation
+    public void Helper() { }
+
+    /** <summary>Block docs.</summary> */
+    public int Count { get; }
 }
 "#;
-        let result = extractor.extract_file("test.go", code);
-        assert!(result.nodes.iter().any(|n| n.name == "main"));
-        assert!(result.nodes.iter().any(|n| n.name == "helper"));
+        let result = extractor.extract_file("Pricing.cs", code);
+        let (doc, tags) = docs(&result, "Total");
+        assert_eq!(
+            doc.as_deref(),
+            Some("<summary>Total price of the cart.</summary>\n<param name=\"discount\">Fraction taken off.</param>\n<returns>The total.</returns>\n<exception cref=\"ArgumentException\">When negative.</exception>")
+        );
+        assert_eq!(tags.params[0].name, "discount");
+        assert_eq!(tags.params[0].description, "Fraction taken off.");
+        assert_eq!(tags.returns.as_deref(), Some("The total."));
+        assert_eq!(tags.throws[0].name, "ArgumentException");
+        assert_eq!(docs(&result, "Helper").0, None);
+        assert_eq!(
+            docs(&result, "Count").0.as_deref(),
+            Some("<summary>Block docs.</summary>")
+        );
     }
 
     #[test]
-    fn test_extract_go_struct() {
+    fn test_parse_errors() {
         let mut extractor = Extractor::new();
         let code = r#"
-type Person struct {
-    Name string
-    Age  int
+fn valid(x: i32) -> i32 {
+    x + 1
+}
+
+fn broken(y: i32) -> i32 {
+    let z = y +;
+    z
 }
 "#;
-        let result = extractor.extract_file("test.go", code);
-        // Go type declarations may be extracted differently depending on grammar
-        // At minimum we should have the file node and no errors
+        let result = extractor.extract_file("broken.rs", code);
+        let file = result
+            .nodes
+            .iter()
+            .find(|n| n.kind == NodeKind::File)
+            .unwrap();
+        assert!(file.has_parse_errors);
+        let valid = result.nodes.iter().find(|n| n.name == "valid").unwrap();
+        assert!(!valid.has_parse_errors);
+        let broken = result.nodes.iter().find(|n| n.name == "broken").unwrap();
+        assert!(broken.has_parse_errors);
+
+        assert_eq!(result.errors.len(), 1);
+        let error = &result.errors[0];
+        assert_eq!(error.file_path, "broken.rs");
+        assert_eq!((error.line, error.column), (Some(7), Some(15)));
+        assert_eq!(error.message, "Missing identifier");
+
+        let result = extractor.extract_file("valid.rs", "fn ok() {}\n");
         assert!(result.errors.is_empty());
-        assert!(result.nodes.iter().any(|n| n.kind == NodeKind::File));
+        assert!(result.nodes.iter().all(|n| !n.has_parse_errors));
     }
 
-    // Contains edge tests
     #[test]
-    fn test_contains_edges() {
+    fn test_tsx_parses_jsx() {
         let mut extractor = Extractor::new();
         let code = r#"
-fn outer() {
-    fn inner() {}
+import React from "react";
+
+interface Props { title: string }
+
+export function Header({ title }: Props) {
+    const onClick = () => console.log(title);
+    return <h1 className="title" onClick={onClick}>{title}</h1>;
 }
 "#;
-        let result = extractor.extract_file("test.rs", code);
+        let result = extractor.extract_file("header.tsx", code);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert!(result.nodes.iter().all(|n| !n.has_parse_errors));
+        let header = result.nodes.iter().find(|n| n.name == "Header").unwrap();
+        assert_eq!(header.kind, NodeKind::Function);
+        assert_eq!(header.language, Language::Tsx);
+        assert!(result.nodes.iter().any(|n| n.name == "Props"));
+    }
 
-        // Should have contains edges
-        let contains_edges: Vec<_> = result
-            .edges
+    fn signature(result: &ExtractionResult, name: &str) -> SignatureParts {
+        let node = result.nodes.iter().find(|n| n.name == name).unwrap();
+        node.signature_parts.clone().unwrap()
+    }
+
+    /// `name: type = default` of each parameter
+    fn params(parts: &SignatureParts) -> Vec<String> {
+        parts
+            .params
             .iter()
-            .filter(|e| e.kind == EdgeKind::Contains)
-            .collect();
-        assert!(!contains_edges.is_empty());
+            .map(|p| {
+                let mut param = p.name.clone();
+                if p.variadic {
+                    param.insert_str(0, "...");
+                }
+                if let Some(ref ty) = p.type_name {
+                    param.push_str(&format!(": {}", ty));
+                }
+                if let Some(ref default) = p.default {
+                    param.push_str(&format!(" = {}", default));
+                }
+                param
+            })
+            .collect()
+    }
+
+    fn type_params(parts: &SignatureParts) -> Vec<(String, Vec<String>)> {
+        parts
+            .type_params
+            .iter()
+            .map(|p| (p.name.clone(), p.bounds.clone()))
+            .collect()
     }
 
-    // File node tests
     #[test]
-    fn test_file_node_created() {
+    fn test_rust_signature_parts() {
         let mut extractor = Extractor::new();
-        let result = extractor.extract_file("mymodule.rs", "fn foo() {}");
+        let code = r#"
+impl Pool {
+    pub fn run<'a, T: Clone + Send>(
+        &mut self,
+        conn: &mut Connection,
+        mut retries: u32,
+    ) -> Result<(), AuthError>
+    where
+        T: Debug,
+    {
+    }
+}
+"#;
+        let result = extractor.extract_file("pool.rs", code);
+        let parts = signature(&result, "run");
+        assert_eq!(parts.receiver.as_deref(), Some("&mut self"));
+        assert_eq!(params(&parts), ["conn: &mut Connection", "retries: u32"]);
+        assert_eq!(parts.returns.as_deref(), Some("Result<(), AuthError>"));
+        assert_eq!(
+            type_params(&parts),
+            [
+                ("'a".to_string(), vec![]),
+                (
+                    "T".to_string(),
+                    vec!["Clone".to_string(), "Send".to_string(), "Debug".to_string()]
+                )
+            ]
+        );
 
-        let file_node = result.nodes.iter().find(|n| n.kind == NodeKind::File);
-        assert!(file_node.is_some());
-        assert_eq!(file_node.unwrap().name, "mymodule.rs");
+        // The text signature spans every line of the header
+        let run = result.nodes.iter().find(|n| n.name == "run").unwrap();
+        assert_eq!(
+            run.signature.as_deref(),
+            Some("pub fn run<'a, T: Clone + Send>( &mut self, conn: &mut Connection, mut retries: u32, ) -> Result<(), AuthError> where T: Debug,")
+        );
+        assert_eq!(run.arity, Some(2));
     }
 
-    // Empty file test
     #[test]
-    fn test_extract_empty_file() {
+    fn test_typescript_and_python_signature_parts() {
         let mut extractor = Extractor::new();
-        let result = extractor.extract_file("empty.rs", "");
-        assert!(result.errors.is_empty());
-        // Should still have a file node
-        assert!(result.nodes.iter().any(|n| n.kind == NodeKind::File));
+        let code = r#"
+function load<T extends Base, U>(this: Window, id: string, retries = 3, ...rest: T[]): Promise<T> {}
+"#;
+        let result = extractor.extract_file("load.ts", code);
+        let parts = signature(&result, "load");
+        assert_eq!(parts.receiver.as_deref(), Some("this: Window"));
+        assert_eq!(
+            params(&parts),
+            ["id: string", "retries = 3", "...rest: T[]"]
+        );
+        assert_eq!(parts.returns.as_deref(), Some("Promise<T>"));
+        assert_eq!(
+            type_params(&parts),
+            [
+                ("T".to_string(), vec!["Base".to_string()]),
+                ("U".to_string(), vec![])
+            ]
+        );
+
+        let code = r#"
+class Repo:
+    def find(self, key: str, limit: int = 10, *args, **kwargs) -> list[str]:
+        pass
+"#;
+        let result = extractor.extract_file("repo.py", code);
+        let parts = signature(&result, "find");
+        assert_eq!(parts.receiver.as_deref(), Some("self"));
+        assert_eq!(
+            params(&parts),
+            ["key: str", "limit: int = 10", "...args", "...kwargs"]
+        );
+        assert_eq!(parts.returns.as_deref(), Some("list[str]"));
     }
 
-    // Line number tests
     #[test]
-    fn test_line_numbers() {
+    fn test_go_java_and_cpp_signature_parts() {
         let mut extractor = Extractor::new();
         let code = r#"
-fn first() {}
+package server
 
-fn second() {}
+func (s *Server) Serve(host, port string, opts ...Option) (int, error) {}
 
-fn third() {}
+func Map[K comparable, V any](m map[K]V) []V {}
 "#;
-        let result = extractor.extract_file("test.rs", code);
+        let result = extractor.extract_file("server.go", code);
+        let parts = signature(&result, "Serve");
+        assert_eq!(parts.receiver.as_deref(), Some("*Server"));
+        assert_eq!(
+            params(&parts),
+            ["host: string", "port: string", "...opts: Option"]
+        );
+        assert_eq!(parts.returns.as_deref(), Some("(int, error)"));
+        let parts = signature(&result, "Map");
+        assert_eq!(
+            type_params(&parts),
+            [
+                ("K".to_string(), vec!["comparable".to_string()]),
+                ("V".to_string(), vec!["any".to_string()])
+            ]
+        );
 
-        let first = result.nodes.iter().find(|n| n.name == "first").unwrap();
-        let second = result.nodes.iter().find(|n| n.name == "second").unwrap();
-        let third = result.nodes.iter().find(|n| n.name == "third").unwrap();
+        let code = r#"
+class Store {
+    @Override
+    public <T extends Comparable<T>> List<T> sort(final List<T> items, String... keys)
+            throws IOException, SQLException {
+    }
+}
+"#;
+        let result = extractor.extract_file("Store.java", code);
+        let sort = result.nodes.iter().find(|n| n.name == "sort").unwrap();
+        assert_eq!(
+            sort.signature.as_deref(),
+            Some("public <T extends Comparable<T>> List<T> sort(final List<T> items, String... keys) throws IOException, SQLException")
+        );
+        let parts = signature(&result, "sort");
+        assert_eq!(params(&parts), ["items: List<T>", "...keys: String"]);
+        assert_eq!(parts.returns.as_deref(), Some("List<T>"));
+        assert_eq!(parts.throws, ["IOException", "SQLException"]);
+        assert_eq!(
+            type_params(&parts),
+            [("T".to_string(), vec!["Comparable<T>".to_string()])]
+        );
 
-        assert!(first.start_line < second.start_line);
-        assert!(second.start_line < third.start_line);
+        let code = r#"
+template <typename T, int N = 3>
+const T* find(const char *name, int count = 2, ...) { return nullptr; }
+"#;
+        let result = extractor.extract_file("find.cpp", code);
+        let parts = signature(&result, "find");
+        assert_eq!(
+            params(&parts),
+            ["name: const char *", "count: int = 2", "..."]
+        );
+        assert_eq!(parts.returns.as_deref(), Some("const T*"));
+        assert_eq!(
+            type_params(&parts),
+            [
+                ("T".to_string(), vec![]),
+                ("N".to_string(), vec!["int".to_string()])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csharp_signature_parts() {
+        let mut extractor = Extractor::new();
+        let code = r#"
+static class Queries
+{
+    public static IList<T> Find<T, K>(this IQueryable<T> source, K key, int limit = 10, params string[] fields)
+        where T : class, IEntity
+        where K : IComparable<K>
+    {
+        return null;
+    }
+}
+"#;
+        let result = extractor.extract_file("Queries.cs", code);
+        let parts = signature(&result, "Find");
+        assert_eq!(
+            params(&parts),
+            vec![
+                "source: IQueryable<T>",
+                "key: K",
+                "limit: int = 10",
+                "...fields: string[]"
+            ]
+        );
+        assert_eq!(parts.returns.as_deref(), Some("IList<T>"));
+        assert_eq!(
+            type_params(&parts),
+            vec![
+                (
+                    "T".to_string(),
+                    vec!["class".to_string(), "IEntity".to_string()]
+                ),
+                ("K".to_string(), vec!["IComparable<K>".to_string()])
+            ]
+        );
     }
 }
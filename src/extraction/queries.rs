@@ -0,0 +1,528 @@
+//! Tree-sitter queries that drive extraction
+//!
+//! Each grammar has a `.scm` query whose captures say which syntax nodes are
+//! symbols and which are references:
+//!
+//! - `@definition.<kind>` - a symbol of that kind (`function`, `struct`, ...)
+//! - `@reference.call`, `@reference.instantiation`, `@reference.member` - a
+//!   call, a construction of a type, or a field or property access
+//! - `@reference.implementation` - the type `@type` (or the enclosing symbol)
+//!   implements the interface `@name`
+//! - `@name` - the name of a definition, or the callee of a reference, where
+//!   the node does not give it
+//!
+//! Captures starting with `_` are left for predicates. When several patterns
+//! capture the same node, the last one wins, so general patterns come first.
+//!
+//! The built-in queries are embedded in the binary. A project can replace
+//! them with the `.scm` files in `.codemap/queries/<grammar>/`; a file that
+//! starts with `; extends` is added to the built-in query instead. TSX files
+//! use the TypeScript queries, compiled for the TSX grammar, followed by any
+//! in `.codemap/queries/tsx/`.
+//!
+//! A grammar loaded at runtime has no built-in query: its `.scm` files come
+//! from the directory named in the project config, see [`super::grammars`].
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{anyhow, bail, Context, Result};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use tree_sitter::{Query, QueryCursor, StreamingIterator};
+
+use super::{grammars, languages};
+use crate::types::{Language, NodeKind};
+
+/// Project query overrides, relative to the project root
+pub const QUERIES_DIR: &str = ".codemap/queries";
+
+/// Marks a project query file that extends the built-in query
+const EXTENDS: &str = "; extends";
+
+/// Built-in query sources, by grammar
+const BUILTIN: &[(&str, &str)] = &[
+    ("rust", include_str!("queries/rust.scm")),
+    ("typescript", include_str!("queries/typescript.scm")),
+    ("tsx", include_str!("queries/typescript.scm")),
+    ("javascript", include_str!("queries/javascript.scm")),
+    ("python", include_str!("queries/python.scm")),
+    ("go", include_str!("queries/go.scm")),
+    ("java", include_str!("queries/java.scm")),
+    ("c", include_str!("queries/c.scm")),
+    ("cpp", include_str!("queries/cpp.scm")),
+    ("csharp", include_str!("queries/csharp.scm")),
+];
+
+/// Grammars that take the project queries of another grammar before their own
+const INHERITS: &[(&str, &str)] = &[("tsx", "typescript")];
+
+/// Name of the grammar, and of its query directory, that parses a language
+pub fn grammar_name(language: Language) -> Option<&'static str> {
+    match language {
+        Language::Rust => Some("rust"),
+        Language::TypeScript => Some("typescript"),
+        Language::Tsx => Some("tsx"),
+        Language::JavaScript | Language::Jsx => Some("javascript"),
+        Language::Python => Some("python"),
+        Language::Go => Some("go"),
+        Language::Java => Some("java"),
+        Language::C => Some("c"),
+        Language::Cpp => Some("cpp"),
+        Language::CSharp => Some("csharp"),
+        _ => grammars::by_name(language.as_str()).map(|g| g.name),
+    }
+}
+
+/// A language that a grammar parses, to compile its query against
+fn grammar_language(grammar: &str) -> Option<Language> {
+    [
+        Language::Rust,
+        Language::TypeScript,
+        Language::Tsx,
+        Language::JavaScript,
+        Language::Python,
+        Language::Go,
+        Language::Java,
+        Language::C,
+        Language::Cpp,
+        Language::CSharp,
+    ]
+    .into_iter()
+    .find(|&language| grammar_name(language) == Some(grammar))
+    .or_else(|| grammars::by_name(grammar).map(|g| g.language()))
+}
+
+/// What a reference capture records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Call,
+    Instantiation,
+    Member,
+    Implementation,
+}
+
+/// What a capture of the query means
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Definition(NodeKind),
+    Reference(ReferenceKind),
+    Name,
+    Type,
+    Ignored,
+}
+
+impl Role {
+    fn parse(capture: &str) -> Option<Self> {
+        if capture.starts_with('_') {
+            return Some(Role::Ignored);
+        }
+        if let Some(kind) = capture.strip_prefix("definition.") {
+            return NodeKind::parse(kind)
+                .filter(|&k| k != NodeKind::File)
+                .map(Role::Definition);
+        }
+        Some(match capture {
+            "reference.call" => Role::Reference(ReferenceKind::Call),
+            "reference.instantiation" => Role::Reference(ReferenceKind::Instantiation),
+            "reference.member" => Role::Reference(ReferenceKind::Member),
+            "reference.implementation" => Role::Reference(ReferenceKind::Implementation),
+            "name" => Role::Name,
+            "type" => Role::Type,
+            _ => return None,
+        })
+    }
+}
+
+/// A compiled query and the meaning of each of its captures
+struct GrammarQuery {
+    query: Query,
+    roles: Vec<Role>,
+    source: String,
+}
+
+impl GrammarQuery {
+    fn compile(language: Language, source: &str) -> Result<Self> {
+        let ts_language = languages::get_language(language)
+            .ok_or_else(|| anyhow!("No tree-sitter grammar for {:?}", language))?;
+        let query = Query::new(&ts_language, source)
+            .map_err(|e| anyhow!("line {}, column {}: {}", e.row + 1, e.column + 1, e.message))?;
+        let roles = query
+            .capture_names()
+            .iter()
+            .map(|name| Role::parse(name).ok_or_else(|| anyhow!("unknown capture `@{}`", name)))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            query,
+            roles,
+            source: source.to_string(),
+        })
+    }
+}
+
+/// The queries for every grammar
+pub struct Queries {
+    grammars: HashMap<&'static str, Arc<GrammarQuery>>,
+}
+
+impl Queries {
+    /// The queries embedded in the binary
+    pub fn builtin() -> Arc<Self> {
+        static BUILTIN_QUERIES: OnceLock<Arc<Queries>> = OnceLock::new();
+        BUILTIN_QUERIES
+            .get_or_init(|| {
+                let grammars = BUILTIN
+                    .iter()
+                    .map(|&(grammar, source)| {
+                        let language = grammar_language(grammar).expect("built-in grammar");
+                        let query = GrammarQuery::compile(language, source)
+                            .unwrap_or_else(|e| panic!("built-in {} query: {}", grammar, e));
+                        (grammar, Arc::new(query))
+                    })
+                    .collect();
+                Arc::new(Queries { grammars })
+            })
+            .clone()
+    }
+
+    /// The built-in queries with a project's overrides from `.codemap/queries`,
+    /// and the queries of the grammars its config loads, if `allow_grammars`
+    /// (see [`grammars::load`])
+    pub fn load(root: &Path, allow_grammars: bool) -> Result<Arc<Self>> {
+        let builtin = Self::builtin();
+        let plugins = grammars::load(root, allow_grammars)?;
+        let dir = root.join(QUERIES_DIR);
+        if !dir.is_dir() && plugins.is_empty() {
+            return Ok(builtin);
+        }
+
+        let mut grammars = builtin.grammars.clone();
+        for &(grammar, source) in BUILTIN {
+            let grammar_dir = dir.join(grammar);
+            let mut files = Vec::new();
+            for &(_, parent) in INHERITS.iter().filter(|(child, _)| *child == grammar) {
+                files.extend(query_files(&dir.join(parent)));
+            }
+            files.extend(query_files(&grammar_dir));
+            if files.is_empty() {
+                continue;
+            }
+
+            let language = grammar_language(grammar).expect("built-in grammar");
+            let mut replaced = String::new();
+            let mut extended = String::new();
+            for file in &files {
+                let text = fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                // Check each file on its own, for errors that point into it
+                GrammarQuery::compile(language, &text)
+                    .with_context(|| format!("Invalid query {}", file.display()))?;
+                let buffer = match text.trim_start().starts_with(EXTENDS) {
+                    true => &mut extended,
+                    false => &mut replaced,
+                };
+                buffer.push_str(&text);
+                buffer.push('\n');
+            }
+            let base = match replaced.is_empty() {
+                true => source,
+                false => replaced.as_str(),
+            };
+            let query = GrammarQuery::compile(language, &format!("{}\n{}", base, extended))
+                .with_context(|| format!("Invalid queries in {}", grammar_dir.display()))?;
+            grammars.insert(grammar, Arc::new(query));
+        }
+
+        for plugin in plugins {
+            let files = query_files(&plugin.queries);
+            if files.is_empty() {
+                warn!(
+                    "No queries for the {} grammar in {}, its files will have no symbols",
+                    plugin.name,
+                    plugin.queries.display()
+                );
+            }
+            let mut source = String::new();
+            for file in &files {
+                let text = fs::read_to_string(file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                GrammarQuery::compile(plugin.language(), &text)
+                    .with_context(|| format!("Invalid query {}", file.display()))?;
+                source.push_str(&text);
+                source.push('\n');
+            }
+            let query = GrammarQuery::compile(plugin.language(), &source)
+                .with_context(|| format!("Invalid queries in {}", plugin.queries.display()))?;
+            grammars.insert(plugin.name, Arc::new(query));
+        }
+
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if grammar_language(&name).is_none() {
+                    bail!(
+                        "No grammar named `{}` for the queries in {}",
+                        name,
+                        entry.path().display()
+                    );
+                }
+            }
+        }
+
+        Ok(Arc::new(Self { grammars }))
+    }
+
+    /// Hash of every query, which changes whenever extraction may
+    pub fn fingerprint(&self) -> String {
+        let mut grammars: Vec<_> = self.grammars.iter().collect();
+        grammars.sort_by_key(|(grammar, _)| **grammar);
+        let mut hasher = Sha256::new();
+        for (grammar, query) in grammars {
+            hasher.update(grammar.as_bytes());
+            hasher.update([0]);
+            hasher.update(query.source.as_bytes());
+            hasher.update([0]);
+            // A rebuilt grammar may parse the same files differently
+            if let Some(plugin) = grammars::by_name(grammar) {
+                hasher.update(fs::read(&plugin.library).unwrap_or_default());
+                hasher.update([0]);
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Run the query for `language` over a parsed file
+    pub fn capture<'t>(
+        &self,
+        language: Language,
+        root: tree_sitter::Node<'t>,
+        source: &str,
+    ) -> Captures<'t> {
+        let mut captures = Captures::default();
+        let Some(grammar) = grammar_name(language).and_then(|g| self.grammars.get(g)) else {
+            return captures;
+        };
+
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&grammar.query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            let mut target = None;
+            let (mut name, mut type_node) = (None, None);
+            for capture in m.captures {
+                match grammar.roles[capture.index as usize] {
+                    Role::Definition(kind) => target = Some((capture.node, Ok(kind))),
+                    Role::Reference(kind) => target = Some((capture.node, Err(kind))),
+                    Role::Name => name = Some(capture.node),
+                    Role::Type => type_node = Some(capture.node),
+                    Role::Ignored => {}
+                }
+            }
+            let Some((node, kind)) = target else {
+                continue;
+            };
+            let pattern = m.pattern_index;
+            match kind {
+                Ok(kind) => {
+                    let definition = Definition {
+                        kind,
+                        name,
+                        pattern,
+                    };
+                    let previous = captures.definitions.get(&node.id());
+                    if previous.is_none_or(|p| p.pattern <= pattern) {
+                        captures.definitions.insert(node.id(), definition);
+                    }
+                }
+                Err(kind) => {
+                    let reference = Reference {
+                        kind,
+                        name,
+                        type_node,
+                        pattern,
+                    };
+                    let previous = captures.references.get(&node.id());
+                    if previous.is_none_or(|p| p.pattern <= pattern) {
+                        captures.references.insert(node.id(), reference);
+                    }
+                }
+            }
+        }
+        captures
+    }
+}
+
+/// The `.scm` files of a query directory, in name order
+fn query_files(dir: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "scm"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// A symbol captured by `@definition.<kind>`
+#[derive(Debug, Clone, Copy)]
+pub struct Definition<'t> {
+    pub kind: NodeKind,
+    /// The `@name` capture, when the pattern has one
+    pub name: Option<tree_sitter::Node<'t>>,
+    pattern: usize,
+}
+
+/// A reference captured by `@reference.<kind>`
+#[derive(Debug, Clone, Copy)]
+pub struct Reference<'t> {
+    pub kind: ReferenceKind,
+    /// The `@name` capture: the callee, the constructed or implemented type
+    pub name: Option<tree_sitter::Node<'t>>,
+    /// The `@type` capture: the implementing type
+    pub type_node: Option<tree_sitter::Node<'t>>,
+    pattern: usize,
+}
+
+/// What the query captured in one file, by syntax node
+#[derive(Debug, Default)]
+pub struct Captures<'t> {
+    definitions: HashMap<usize, Definition<'t>>,
+    references: HashMap<usize, Reference<'t>>,
+}
+
+impl<'t> Captures<'t> {
+    pub fn definition(&self, node: &tree_sitter::Node) -> Option<&Definition<'t>> {
+        self.definitions.get(&node.id())
+    }
+
+    pub fn reference(&self, node: &tree_sitter::Node) -> Option<&Reference<'t>> {
+        self.references.get(&node.id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use tree_sitter::Parser;
+
+    /// Definitions with a `@name`, by name
+    fn captured_kinds(
+        queries: &Queries,
+        language: Language,
+        code: &str,
+    ) -> Vec<(String, NodeKind)> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&languages::get_language(language).unwrap())
+            .unwrap();
+        let tree = parser.parse(code, None).unwrap();
+        let captures = queries.capture(language, tree.root_node(), code);
+        let mut kinds: Vec<_> = captures
+            .definitions
+            .values()
+            .filter_map(|d| {
+                let name = d.name?.utf8_text(code.as_bytes()).unwrap().to_string();
+                Some((name, d.kind))
+            })
+            .collect();
+        kinds.sort_by(|a, b| a.0.cmp(&b.0));
+        kinds
+    }
+
+    #[test]
+    fn test_builtin_queries_compile() {
+        let queries = Queries::builtin();
+        for &(grammar, _) in BUILTIN {
+            assert!(queries.grammars.contains_key(grammar), "{}", grammar);
+        }
+    }
+
+    #[test]
+    fn test_later_patterns_win() {
+        let code = "package p\n\ntype A struct{}\ntype B interface{}\ntype C int\n";
+        let kinds = captured_kinds(&Queries::builtin(), Language::Go, code);
+        assert_eq!(
+            kinds,
+            vec![
+                ("A".to_string(), NodeKind::Struct),
+                ("B".to_string(), NodeKind::Interface),
+                ("C".to_string(), NodeKind::TypeAlias),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project_queries() {
+        let dir = tempdir().unwrap();
+        let go = dir.path().join(QUERIES_DIR).join("go");
+        fs::create_dir_all(&go).unwrap();
+        let code = "package p\n\ntype A struct{}\n";
+
+        // A file without `; extends` replaces the built-in query
+        fs::write(
+            go.join("types.scm"),
+            "(type_spec name: (type_identifier) @name) @definition.class\n",
+        )
+        .unwrap();
+        let queries = Queries::load(dir.path(), false).unwrap();
+        let kinds = captured_kinds(&queries, Language::Go, code);
+        assert_eq!(kinds, vec![("A".to_string(), NodeKind::Class)]);
+
+        // One with it adds to the built-in query
+        fs::write(
+            go.join("types.scm"),
+            "; extends\n(package_clause (package_identifier) @name) @definition.namespace\n",
+        )
+        .unwrap();
+        let queries = Queries::load(dir.path(), false).unwrap();
+        let kinds = captured_kinds(&queries, Language::Go, code);
+        assert!(kinds.contains(&("A".to_string(), NodeKind::Struct)));
+        assert!(kinds.contains(&("p".to_string(), NodeKind::Namespace)));
+
+        // Other grammars keep their built-in queries
+        assert!(Arc::ptr_eq(
+            &queries.grammars["rust"],
+            &Queries::builtin().grammars["rust"]
+        ));
+    }
+
+    #[test]
+    fn test_tsx_takes_typescript_project_queries() {
+        let dir = tempdir().unwrap();
+        let typescript = dir.path().join(QUERIES_DIR).join("typescript");
+        fs::create_dir_all(&typescript).unwrap();
+        fs::write(
+            typescript.join("types.scm"),
+            "; extends\n(type_alias_declaration name: (type_identifier) @name) @definition.struct\n",
+        )
+        .unwrap();
+        let queries = Queries::load(dir.path(), false).unwrap();
+        let code = "type Props = { title: string };\nconst view = <div>{1}</div>;\n";
+        let kinds = captured_kinds(&queries, Language::Tsx, code);
+        assert!(kinds.contains(&("Props".to_string(), NodeKind::Struct)));
+    }
+
+    #[test]
+    fn test_invalid_project_queries() {
+        let dir = tempdir().unwrap();
+        let go = dir.path().join(QUERIES_DIR).join("go");
+        fs::create_dir_all(&go).unwrap();
+
+        fs::write(go.join("bad.scm"), "(no_such_node) @definition.function\n").unwrap();
+        let err = Queries::load(dir.path(), false).err().unwrap();
+        assert!(format!("{:#}", err).contains("bad.scm"));
+
+        fs::write(go.join("bad.scm"), "(type_spec) @definition.gadget\n").unwrap();
+        let err = Queries::load(dir.path(), false).err().unwrap();
+        assert!(format!("{:#}", err).contains("unknown capture `@definition.gadget`"));
+
+        fs::remove_file(go.join("bad.scm")).unwrap();
+        fs::create_dir_all(dir.path().join(QUERIES_DIR).join("cobol")).unwrap();
+        let err = Queries::load(dir.path(), false).err().unwrap();
+        assert!(err.to_string().contains("No grammar named `cobol`"));
+    }
+}
@@ -0,0 +1,17 @@
+; Definitions
+
+(function_definition) @definition.function
+(struct_specifier) @definition.struct
+(enum_specifier) @definition.enum
+(enumerator) @definition.enum_member
+(field_declaration) @definition.field
+(parameter_declaration) @definition.parameter
+(type_definition) @definition.type_alias
+(preproc_def) @definition.constant
+(preproc_include) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(compound_literal_expression) @reference.instantiation
+(field_expression) @reference.member
@@ -0,0 +1,32 @@
+; Definitions
+
+(function_definition) @definition.function
+
+; Methods defined in the class body; out-of-line `Foo::bar` definitions are
+; recognized by their qualified name
+(field_declaration_list (function_definition) @definition.method)
+(field_declaration_list
+  (template_declaration (function_definition) @definition.method))
+
+(class_specifier) @definition.class
+(struct_specifier) @definition.struct
+(enum_specifier) @definition.enum
+(enumerator) @definition.enum_member
+
+; Member declarations that declare a function are methods
+(field_declaration) @definition.field
+
+(parameter_declaration) @definition.parameter
+(optional_parameter_declaration) @definition.parameter
+(type_definition) @definition.type_alias
+(alias_declaration) @definition.type_alias
+(preproc_def) @definition.constant
+(namespace_definition) @definition.module
+(preproc_include) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(new_expression) @reference.instantiation
+(compound_literal_expression) @reference.instantiation
+(field_expression) @reference.member
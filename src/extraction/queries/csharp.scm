@@ -0,0 +1,35 @@
+; Definitions
+
+(method_declaration) @definition.method
+(constructor_declaration) @definition.method
+(local_function_statement) @definition.function
+(class_declaration) @definition.class
+(record_declaration) @definition.class
+(record_declaration "struct") @definition.struct
+(struct_declaration) @definition.struct
+(interface_declaration) @definition.interface
+(enum_declaration) @definition.enum
+(enum_member_declaration) @definition.enum_member
+(delegate_declaration) @definition.type_alias
+
+; `const` fields are constants
+(field_declaration) @definition.field
+(event_field_declaration) @definition.field
+(property_declaration) @definition.property
+
+(parameter) @definition.parameter
+; `params int[] rest` is not wrapped in a parameter
+(parameter_list name: (identifier) @definition.parameter)
+; Positional record parameters are properties
+(record_declaration (parameter_list (parameter) @definition.property))
+
+; File-scoped namespaces hold the rest of the file
+(namespace_declaration) @definition.module
+(file_scoped_namespace_declaration) @definition.module
+(using_directive) @definition.import
+
+; References
+
+(invocation_expression) @reference.call
+(object_creation_expression) @reference.instantiation
+(member_access_expression) @reference.member
@@ -0,0 +1,37 @@
+; Definitions
+
+(function_declaration) @definition.function
+(method_declaration) @definition.method
+(type_spec name: (type_identifier) @name) @definition.type_alias
+(type_spec name: (type_identifier) @name type: (struct_type)) @definition.struct
+(type_spec name: (type_identifier) @name type: (interface_type)) @definition.interface
+(type_alias) @definition.type_alias
+
+; Embedded fields are recorded as inheritance
+(field_declaration name: (_)) @definition.field
+
+(parameter_declaration) @definition.parameter
+(variadic_parameter_declaration) @definition.parameter
+(const_declaration) @definition.constant
+(package_clause) @definition.module
+(import_declaration) @definition.import
+(import_spec) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(composite_literal) @reference.instantiation
+(selector_expression) @reference.member
+
+; Compile-time interface assertions: var _ Iface = (*Type)(nil), &Type{} or Type{}
+(var_spec
+  name: (identifier) @_blank
+  type: (_) @name
+  value: (expression_list
+    [
+      (call_expression
+        function: (parenthesized_expression (unary_expression operand: (_) @type)))
+      (unary_expression operand: (composite_literal type: (_) @type))
+      (composite_literal type: (_) @type)
+    ])
+  (#eq? @_blank "_")) @reference.implementation
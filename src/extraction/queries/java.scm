@@ -0,0 +1,23 @@
+; Definitions
+
+(method_declaration) @definition.method
+(constructor_declaration) @definition.method
+(class_declaration) @definition.class
+(interface_declaration) @definition.interface
+(annotation_type_declaration) @definition.interface
+(enum_declaration) @definition.enum
+(enum_constant) @definition.enum_member
+
+; `static final` fields are constants
+(field_declaration) @definition.field
+
+(formal_parameter) @definition.parameter
+(spread_parameter) @definition.parameter
+(package_declaration) @definition.module
+(import_declaration) @definition.import
+
+; References
+
+(method_invocation) @reference.call
+(object_creation_expression) @reference.instantiation
+(field_access) @reference.member
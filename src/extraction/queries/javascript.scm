@@ -0,0 +1,24 @@
+; Definitions
+
+(function_declaration) @definition.function
+(generator_function_declaration) @definition.function
+(function_expression) @definition.function
+(arrow_function) @definition.function
+(method_definition) @definition.method
+(class_declaration) @definition.class
+(class) @definition.class
+(field_definition) @definition.property
+(formal_parameters
+  [
+    (identifier)
+    (assignment_pattern)
+    (rest_pattern)
+  ] @definition.parameter)
+(arrow_function parameter: (identifier) @definition.parameter)
+(import_statement) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(new_expression) @reference.instantiation
+(member_expression) @reference.member
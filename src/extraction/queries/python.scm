@@ -0,0 +1,29 @@
+; Definitions
+
+(function_definition) @definition.function
+(class_definition) @definition.class
+
+; Attributes assigned in the class body
+(class_definition
+  body: (block
+    (expression_statement
+      (assignment left: (identifier)) @definition.field)))
+
+(parameters
+  [
+    (identifier)
+    (typed_parameter)
+    (default_parameter)
+    (typed_default_parameter)
+    (list_splat_pattern)
+    (dictionary_splat_pattern)
+  ] @definition.parameter)
+
+(import_statement) @definition.import
+(import_from_statement) @definition.import
+
+; References
+
+; Calls to classes are told apart from calls to functions during resolution
+(call) @reference.call
+(attribute) @reference.member
@@ -0,0 +1,29 @@
+; Definitions
+
+(function_item) @definition.function
+(impl_item body: (declaration_list (function_item) @definition.method))
+(trait_item body: (declaration_list (function_item) @definition.method))
+(function_signature_item) @definition.method
+(struct_item) @definition.struct
+(trait_item) @definition.interface
+(enum_item) @definition.enum
+(enum_variant) @definition.enum_member
+(field_declaration) @definition.field
+(parameter) @definition.parameter
+(type_item) @definition.type_alias
+(const_item) @definition.constant
+(static_item) @definition.constant
+(mod_item) @definition.module
+(use_declaration) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(macro_invocation) @reference.call
+(struct_expression) @reference.instantiation
+(field_expression) @reference.member
+
+; impl Trait for Type
+(impl_item
+  trait: (_) @name
+  type: (_) @type) @reference.implementation
@@ -0,0 +1,29 @@
+; Definitions
+
+(function_declaration) @definition.function
+(generator_function_declaration) @definition.function
+(function_expression) @definition.function
+(arrow_function) @definition.function
+(method_definition) @definition.method
+(method_signature) @definition.method
+(class_declaration) @definition.class
+(class) @definition.class
+(interface_declaration) @definition.interface
+(enum_declaration) @definition.enum
+(enum_body (property_identifier) @definition.enum_member)
+(enum_body (enum_assignment) @definition.enum_member)
+(public_field_definition) @definition.property
+(property_signature) @definition.property
+(required_parameter) @definition.parameter
+(optional_parameter) @definition.parameter
+(type_alias_declaration) @definition.type_alias
+(module) @definition.module
+(internal_module) @definition.module
+(import_statement) @definition.import
+(import_clause) @definition.import
+
+; References
+
+(call_expression) @reference.call
+(new_expression) @reference.instantiation
+(member_expression) @reference.member
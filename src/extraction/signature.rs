@@ -0,0 +1,430 @@
+//! Structured signatures of functions and methods
+//!
+//! Parameters, return types, type parameters, receivers and `throws` clauses
+//! are read from the syntax tree, keeping each type as written with its
+//! whitespace collapsed.
+
+use super::{declared_name, ExtractionContext};
+use crate::types::{Language, Param, SignatureParts, TypeParam};
+
+impl ExtractionContext<'_> {
+    /// Signature parts of a function or method declaration
+    pub(super) fn extract_signature_parts(&self, node: &tree_sitter::Node) -> SignatureParts {
+        let mut parts = SignatureParts::default();
+
+        // x => x + 1
+        if let Some(param) = node.child_by_field_name("parameter") {
+            parts.params.push(Param {
+                name: self.get_node_text(&param),
+                ..Default::default()
+            });
+        }
+        let params = node.child_by_field_name("parameters").or_else(|| {
+            self.function_declarator(node)?
+                .child_by_field_name("parameters")
+        });
+        if let Some(params) = params {
+            self.push_params(&params, &mut parts);
+        }
+        // Go: func (s *Server) Serve()
+        if let Some(receiver) = node.child_by_field_name("receiver") {
+            parts.receiver = receiver
+                .named_child(0)
+                .and_then(|p| p.child_by_field_name("type"))
+                .map(|t| self.type_text(&t));
+        }
+
+        parts.returns = self.return_type(node);
+        parts.type_params = self.type_params(node);
+        parts.throws = self.throws(node);
+        parts
+    }
+
+    fn push_params(&self, params: &tree_sitter::Node, parts: &mut SignatureParts) {
+        let mut cursor = params.walk();
+        for (i, param) in params.named_children(&mut cursor).enumerate() {
+            match param.kind() {
+                "self_parameter" | "receiver_parameter" => {
+                    parts.receiver = Some(self.type_text(&param));
+                }
+                // Python's bare `*` and `/` markers
+                "keyword_separator" | "positional_separator" => {}
+                kind if kind.contains("comment") => {}
+                // Python methods name their receiver
+                "identifier"
+                    if i == 0
+                        && self.language == Language::Python
+                        && matches!(self.get_node_text(&param).as_str(), "self" | "cls") =>
+                {
+                    parts.receiver = Some(self.get_node_text(&param));
+                }
+                // TypeScript: function f(this: Window)
+                "required_parameter"
+                    if param
+                        .child_by_field_name("pattern")
+                        .is_some_and(|p| p.kind() == "this") =>
+                {
+                    parts.receiver = Some(self.type_text(&param));
+                }
+                // C: `f(void)`
+                "parameter_declaration" if self.get_node_text(&param) == "void" => {}
+                // C#: the type and name of `params int[] rest` sit in the list
+                _ if self.language == Language::CSharp && param.kind() != "parameter" => {}
+                _ => parts.params.extend(self.params_of(&param)),
+            }
+        }
+        if self.language == Language::CSharp {
+            if let Some(name) = params.child_by_field_name("name") {
+                parts.params.push(Param {
+                    name: self.get_node_text(&name),
+                    type_name: params
+                        .child_by_field_name("type")
+                        .map(|t| self.type_text(&t)),
+                    default: None,
+                    variadic: true,
+                });
+            }
+        }
+        // C/C++: `f(const char *format, ...)`
+        let mut cursor = params.walk();
+        if params.children(&mut cursor).any(|c| c.kind() == "...") {
+            parts.params.push(Param {
+                variadic: true,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// The parameters one parameter node declares; Go's `a, b int` declares two
+    fn params_of(&self, param: &tree_sitter::Node) -> Vec<Param> {
+        let type_name = param
+            .child_by_field_name("type")
+            .map(|t| self.type_text(&t));
+        let default = param
+            .child_by_field_name("value")
+            .or_else(|| param.child_by_field_name("default_value"))
+            .or_else(|| param.child_by_field_name("right"))
+            // C#: `int limit = 5` leaves the value unnamed after the name
+            .or_else(|| match self.language {
+                Language::CSharp => param.child_by_field_name("name")?.next_named_sibling(),
+                _ => None,
+            })
+            .map(|v| self.type_text(&v));
+        let variadic = param.kind().starts_with("variadic") || param.kind() == "spread_parameter";
+
+        match param.kind() {
+            "parameter_declaration" | "variadic_parameter_declaration"
+                if self.language == Language::Go =>
+            {
+                let mut cursor = param.walk();
+                let names: Vec<Param> = param
+                    .children_by_field_name("name", &mut cursor)
+                    .map(|name| Param {
+                        name: self.get_node_text(&name),
+                        type_name: type_name.clone(),
+                        default: None,
+                        variadic,
+                    })
+                    .collect();
+                match names.is_empty() {
+                    true => vec![Param {
+                        type_name,
+                        variadic,
+                        ..Default::default()
+                    }],
+                    false => names,
+                }
+            }
+            // C/C++: the type is the declaration without its name and default
+            "parameter_declaration" | "optional_parameter_declaration" => {
+                let name = param
+                    .child_by_field_name("declarator")
+                    .and_then(|d| declared_name(&d));
+                let end = param
+                    .child_by_field_name("default_value")
+                    .map_or(param.end_byte(), |v| v.start_byte());
+                let declaration = match name {
+                    Some(name) => format!(
+                        "{}{}",
+                        &self.content[param.start_byte()..name.start_byte()],
+                        &self.content[name.end_byte()..end]
+                    ),
+                    None => self.content[param.start_byte()..end].to_string(),
+                };
+                let declaration = collapse(declaration.trim().trim_end_matches('='));
+                vec![Param {
+                    name: name.map(|n| self.get_node_text(&n)).unwrap_or_default(),
+                    type_name: Some(declaration),
+                    default,
+                    variadic: false,
+                }]
+            }
+            // Java: String... names
+            "spread_parameter" => {
+                let mut cursor = param.walk();
+                let children: Vec<_> = param.named_children(&mut cursor).collect();
+                let name = children
+                    .iter()
+                    .find(|c| c.kind() == "variable_declarator")
+                    .and_then(|d| d.child_by_field_name("name"));
+                let type_name = children
+                    .iter()
+                    .find(|c| !matches!(c.kind(), "modifiers" | "variable_declarator"))
+                    .map(|t| self.type_text(t));
+                vec![Param {
+                    name: name.map(|n| self.get_node_text(&n)).unwrap_or_default(),
+                    type_name,
+                    default: None,
+                    variadic: true,
+                }]
+            }
+            _ => {
+                let pattern = param
+                    .child_by_field_name("pattern")
+                    .or_else(|| param.child_by_field_name("name"))
+                    .or_else(|| param.child_by_field_name("left"))
+                    .or_else(|| match param.kind() {
+                        // Python: typed_parameter's name is its first child
+                        "typed_parameter" => param.named_child(0),
+                        _ => None,
+                    })
+                    .unwrap_or(*param);
+                // ...rest, *args, **kwargs
+                let (pattern, splat) = match pattern.kind() {
+                    "rest_pattern" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+                        (pattern.named_child(0).unwrap_or(pattern), true)
+                    }
+                    _ => (pattern, false),
+                };
+                let name = match pattern.kind() {
+                    "variadic_parameter" => String::new(),
+                    _ => self.get_node_text(&pattern),
+                };
+                vec![Param {
+                    name,
+                    type_name,
+                    default,
+                    variadic: variadic || splat || param.kind() == "variadic_parameter",
+                }]
+            }
+        }
+    }
+
+    fn return_type(&self, node: &tree_sitter::Node) -> Option<String> {
+        if let Some(ret) = node
+            .child_by_field_name("return_type")
+            .or_else(|| node.child_by_field_name("result"))
+            .or_else(|| node.child_by_field_name("returns"))
+        {
+            return Some(self.type_text(&ret));
+        }
+        let ty = node.child_by_field_name("type")?;
+        match self.function_declarator(node) {
+            // C/C++: `const T* f()` returns everything between the start of the
+            // type and the name, including qualifiers and pointers
+            Some(declarator) => {
+                let mut start = ty.start_byte();
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    if child.start_byte() >= ty.start_byte() {
+                        break;
+                    }
+                    if child.kind() == "type_qualifier" {
+                        start = start.min(child.start_byte());
+                    }
+                }
+                let name_start = declarator
+                    .child_by_field_name("declarator")
+                    .map_or(declarator.start_byte(), |d| d.start_byte());
+                let text = &self.content[start..name_start.max(ty.end_byte())];
+                Some(collapse(text.trim().trim_end_matches(['(', ' ']))).filter(|t| !t.is_empty())
+            }
+            // Java: the `type` of a method is its return type
+            None if self.language == Language::Java => Some(self.type_text(&ty)),
+            None => None,
+        }
+    }
+
+    fn type_params(&self, node: &tree_sitter::Node) -> Vec<TypeParam> {
+        let list = node.child_by_field_name("type_parameters").or_else(|| {
+            node.parent()
+                .filter(|p| p.kind() == "template_declaration")?
+                .child_by_field_name("parameters")
+        });
+        let mut type_params = Vec::new();
+        if let Some(list) = list {
+            let mut cursor = list.walk();
+            for param in list.named_children(&mut cursor) {
+                self.push_type_param(&param, &mut type_params);
+            }
+        }
+
+        // Rust: where T: Debug
+        let mut cursor = node.walk();
+        let where_clause = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "where_clause");
+        if let Some(where_clause) = where_clause {
+            let mut cursor = where_clause.walk();
+            for predicate in where_clause.named_children(&mut cursor) {
+                let (Some(left), Some(bounds)) = (
+                    predicate.child_by_field_name("left"),
+                    predicate.child_by_field_name("bounds"),
+                ) else {
+                    continue;
+                };
+                let name = self.type_text(&left);
+                let bounds = self.bounds(&bounds);
+                match type_params.iter_mut().find(|p| p.name == name) {
+                    Some(param) => param.bounds.extend(bounds),
+                    None => type_params.push(TypeParam { name, bounds }),
+                }
+            }
+        }
+
+        // C#: where T : class, IEntity, new()
+        let mut cursor = node.walk();
+        let clauses: Vec<_> = node
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() == "type_parameter_constraints_clause")
+            .collect();
+        for clause in clauses {
+            let mut cursor = clause.walk();
+            let mut children = clause.named_children(&mut cursor);
+            let Some(name) = children.next().map(|n| self.type_text(&n)) else {
+                continue;
+            };
+            let bounds: Vec<String> = children
+                .filter(|c| c.kind() == "type_parameter_constraint")
+                .map(|c| self.type_text(&c))
+                .collect();
+            match type_params.iter_mut().find(|p| p.name == name) {
+                Some(param) => param.bounds.extend(bounds),
+                None => type_params.push(TypeParam { name, bounds }),
+            }
+        }
+        type_params
+    }
+
+    fn push_type_param(&self, param: &tree_sitter::Node, type_params: &mut Vec<TypeParam>) {
+        let bounds = param
+            .child_by_field_name("bounds")
+            .or_else(|| param.child_by_field_name("constraint"))
+            .map(|b| self.bounds(&b))
+            .or_else(|| {
+                // Java: T extends A & B
+                let mut cursor = param.walk();
+                let bound = param
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "type_bound")?;
+                Some(self.bounds(&bound))
+            })
+            .unwrap_or_default();
+
+        match param.kind() {
+            // Go: [K comparable, V any], [A, B any]
+            "type_parameter_declaration" if self.language == Language::Go => {
+                let constraint = param
+                    .child_by_field_name("type")
+                    .map(|t| self.type_text(&t));
+                let mut cursor = param.walk();
+                for name in param.children_by_field_name("name", &mut cursor) {
+                    type_params.push(TypeParam {
+                        name: self.get_node_text(&name),
+                        bounds: constraint.iter().cloned().collect(),
+                    });
+                }
+            }
+            "lifetime_parameter" => type_params.push(TypeParam {
+                name: param
+                    .child_by_field_name("name")
+                    .map(|n| self.get_node_text(&n))
+                    .unwrap_or_else(|| self.get_node_text(param)),
+                bounds,
+            }),
+            kind if kind.contains("comment") => {}
+            _ => {
+                let name = param
+                    .child_by_field_name("name")
+                    .or_else(|| {
+                        param
+                            .child_by_field_name("declarator")
+                            .and_then(|d| declared_name(&d))
+                    })
+                    .or_else(|| {
+                        // C++ `typename T`, Java `T extends ...`
+                        let mut cursor = param.walk();
+                        let names: Vec<_> = param
+                            .named_children(&mut cursor)
+                            .filter(|c| matches!(c.kind(), "type_identifier" | "identifier"))
+                            .collect();
+                        names.last().copied()
+                    });
+                if let Some(name) = name {
+                    // C++ non-type template parameters: `int N`
+                    let bounds = match param.child_by_field_name("type") {
+                        Some(ty) if bounds.is_empty() && self.language == Language::Cpp => {
+                            vec![self.type_text(&ty)]
+                        }
+                        _ => bounds,
+                    };
+                    type_params.push(TypeParam {
+                        name: self.get_node_text(&name),
+                        bounds,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Bounds listed by a `trait_bounds`, `constraint` or `type_bound` node
+    fn bounds(&self, node: &tree_sitter::Node) -> Vec<String> {
+        let mut cursor = node.walk();
+        let bounds: Vec<String> = node
+            .named_children(&mut cursor)
+            .filter(|c| !c.kind().contains("comment"))
+            .map(|c| self.type_text(&c))
+            .collect();
+        match bounds.is_empty() {
+            true => vec![self.type_text(node)],
+            false => bounds,
+        }
+    }
+
+    /// Types of a Java `throws` or C++ `throw(...)` clause
+    fn throws(&self, node: &tree_sitter::Node) -> Vec<String> {
+        let clause = {
+            let mut cursor = node.walk();
+            let own = node
+                .named_children(&mut cursor)
+                .find(|c| matches!(c.kind(), "throws" | "throw_specifier"));
+            own.or_else(|| {
+                let declarator = self.function_declarator(node)?;
+                let mut cursor = declarator.walk();
+                let found = declarator
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "throw_specifier");
+                found
+            })
+        };
+        let Some(clause) = clause else {
+            return Vec::new();
+        };
+        let mut cursor = clause.walk();
+        clause
+            .named_children(&mut cursor)
+            .map(|t| self.type_text(&t))
+            .collect()
+    }
+
+    /// A type as written, without a leading `:` annotation marker and with
+    /// whitespace collapsed
+    fn type_text(&self, node: &tree_sitter::Node) -> String {
+        let text = self.get_node_text(node);
+        collapse(text.trim_start_matches(':').trim())
+    }
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
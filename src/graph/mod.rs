@@ -46,27 +46,45 @@ impl<'a> Graph<'a> {
     /// Analyze the impact of changing a symbol
     /// Returns all symbols that could be affected by the change
     pub fn analyze_impact(&self, symbol_name: &str, depth: u32) -> Result<ImpactAnalysis> {
-        let root = match self.db.find_node_by_name(symbol_name)? {
-            Some(node) => node,
-            None => {
-                return Ok(ImpactAnalysis {
-                    root: None,
-                    direct_callers: Vec::new(),
-                    indirect_callers: Vec::new(),
-                    total_impact: 0,
-                })
-            }
-        };
+        match self.db.find_node_by_name(symbol_name)? {
+            Some(node) => self.analyze_impact_of(node, depth),
+            None => Ok(ImpactAnalysis {
+                root: None,
+                overrides: Vec::new(),
+                direct_callers: Vec::new(),
+                indirect_callers: Vec::new(),
+                total_impact: 0,
+            }),
+        }
+    }
 
+    /// Analyze the impact of changing an already resolved symbol
+    ///
+    /// Methods that override the symbol change with it, so their callers
+    /// count as callers of the symbol.
+    pub fn analyze_impact_of(&self, root: Node, depth: u32) -> Result<ImpactAnalysis> {
         let mut visited: HashSet<i64> = HashSet::new();
+        let mut overrides = Vec::new();
         let mut direct_callers = Vec::new();
         let mut indirect_callers = Vec::new();
 
         visited.insert(root.id);
 
+        // Overrides at any depth below the root, and the root itself
+        let mut variants = vec![root.id];
+        let mut pending = vec![root.id];
+        while let Some(node_id) = pending.pop() {
+            for method in self.db.get_overriding(node_id)? {
+                if visited.insert(method.id) {
+                    variants.push(method.id);
+                    pending.push(method.id);
+                    overrides.push(method);
+                }
+            }
+        }
+
         // BFS to find all callers up to depth
-        let mut queue: VecDeque<(i64, u32)> = VecDeque::new();
-        queue.push_back((root.id, 0));
+        let mut queue: VecDeque<(i64, u32)> = variants.into_iter().map(|id| (id, 0)).collect();
 
         while let Some((node_id, current_depth)) = queue.pop_front() {
             if current_depth >= depth {
@@ -92,10 +110,11 @@ impl<'a> Graph<'a> {
             }
         }
 
-        let total_impact = direct_callers.len() + indirect_callers.len();
+        let total_impact = overrides.len() + direct_callers.len() + indirect_callers.len();
 
         Ok(ImpactAnalysis {
             root: Some(root),
+            overrides,
             direct_callers,
             indirect_callers,
             total_impact,
@@ -250,6 +269,8 @@ impl<'a> Graph<'a> {
 #[derive(Debug, Clone)]
 pub struct ImpactAnalysis {
     pub root: Option<Node>,
+    /// Methods that override the root, directly or through other overrides
+    pub overrides: Vec<Node>,
     pub direct_callers: Vec<Node>,
     pub indirect_callers: Vec<Node>,
     pub total_impact: usize,
@@ -279,12 +300,17 @@ mod tests {
             start_column: 0,
             end_column: 1,
             signature: Some(format!("fn {}()", name)),
+            signature_parts: None,
+            arity: None,
             visibility: Visibility::Public,
             docstring: None,
+            doc_tags: None,
             is_async: false,
             is_static: false,
             is_exported: true,
+            has_parse_errors: false,
             language: Language::Rust,
+            decorators: Vec::new(),
         }
     }
 
@@ -349,6 +375,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -380,6 +407,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -416,6 +444,7 @@ mod tests {
                 file_path: None,
                 line: None,
                 column: None,
+                confidence: 1.0,
             };
             db.insert_edge(&edge).unwrap();
         }
@@ -473,6 +502,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         };
         db.insert_edge(&edge).unwrap();
 
@@ -507,6 +537,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -518,6 +549,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -555,6 +587,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
         db.insert_edge(&Edge {
@@ -565,6 +598,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
         db.insert_edge(&Edge {
@@ -575,6 +609,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -640,6 +675,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -682,6 +718,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -717,6 +754,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
         db.insert_edge(&Edge {
@@ -727,6 +765,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
         db.insert_edge(&Edge {
@@ -737,6 +776,7 @@ mod tests {
             file_path: None,
             line: None,
             column: None,
+            confidence: 1.0,
         })
         .unwrap();
 
@@ -6,7 +6,7 @@
 //!
 //! ## Features
 //!
-//! - Multi-language support: Rust, TypeScript, JavaScript, Python, Go, Java, C, C++
+//! - Multi-language support: Rust, TypeScript, JavaScript, Python, Go, Java, C, C++, C#
 //! - Symbol extraction: functions, classes, methods, interfaces, etc.
 //! - Relationship tracking: calls, contains, imports, exports, etc.
 //! - Impact analysis: trace the effect of changes through the codebase
@@ -29,18 +29,27 @@ pub mod extraction;
 pub mod graph;
 pub mod mcp;
 pub mod types;
+pub mod watch;
 
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 use std::time::SystemTime;
 
 use anyhow::Result;
 use ignore::WalkBuilder;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 
-use db::Database;
-use extraction::Extractor;
-use types::{FileRecord, Language};
+use db::{Database, ProjectLayout};
+use extraction::{grammars, Extractor, Queries};
+use types::{ExtractionResult, FileRecord, Language, NodeKind};
+
+/// `meta` key of the fingerprint of the queries the index was extracted with
+const QUERIES_META: &str = "queries";
 
 /// Configuration for indexing
 #[derive(Debug, Clone)]
@@ -53,6 +62,11 @@ pub struct IndexConfig {
     pub exclude_dirs: Vec<String>,
     /// Whether to follow gitignore rules
     pub respect_gitignore: bool,
+    /// Number of parser threads (0 = one per available CPU)
+    pub workers: usize,
+    /// Whether to load the grammar libraries listed in the project config,
+    /// which runs their code
+    pub allow_grammars: bool,
 }
 
 impl Default for IndexConfig {
@@ -73,6 +87,7 @@ impl Default for IndexConfig {
                 "cpp".to_string(),
                 "cc".to_string(),
                 "hpp".to_string(),
+                "cs".to_string(),
             ],
             exclude_dirs: vec![
                 "node_modules".to_string(),
@@ -86,55 +101,148 @@ impl Default for IndexConfig {
                 "vendor".to_string(),
             ],
             respect_gitignore: true,
+            workers: 0,
+            allow_grammars: false,
         }
     }
 }
 
 /// Index a codebase into the database
+///
+/// Files are read, hashed and parsed by a pool of workers, each with its own
+/// `Extractor`. Results are written by the calling thread in path order, so
+/// the resulting index does not depend on the number of workers.
+///
+/// Extraction uses the built-in queries with the project's overrides from
+/// `.codemap/queries`, and the grammars listed in `.codemap/config.json`;
+/// when those change, every file is extracted again.
 pub fn index_codebase(db: &mut Database, config: &IndexConfig) -> Result<IndexStats> {
     let root = Path::new(&config.root).canonicalize()?;
     info!("Indexing codebase at {}", root.display());
+    let queries = Queries::load(&root, config.allow_grammars)?;
+    let fingerprint = queries.fingerprint();
 
-    let mut extractor = Extractor::new();
     let mut stats = IndexStats::default();
 
-    // Build the walker
-    let mut walker = WalkBuilder::new(&root);
-    walker
-        .hidden(false)
-        .git_ignore(config.respect_gitignore)
-        .git_global(config.respect_gitignore)
-        .git_exclude(config.respect_gitignore);
+    // Walk the whole tree first so that vanished files can be told apart
+    // from renamed ones
+    let walked = walk_files(&root, &root, config);
 
     // Begin transaction
     db.begin_transaction()?;
 
-    for entry in walker.build() {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(err) => {
-                warn!("Error walking directory: {}", err);
-                continue;
+    // Indexed files that are no longer on disk, or are now excluded. Files
+    // extracted with other queries are out of date whatever their content.
+    let walked_paths: HashSet<&str> = walked.iter().map(|f| f.rel_path.as_str()).collect();
+    let same_queries = db.get_meta(QUERIES_META)?.as_deref() == Some(fingerprint.as_str());
+    let mut known_hashes = HashMap::new();
+    let mut stale = Vec::new();
+    for file in db.get_all_files()? {
+        if walked_paths.contains(file.path.as_str()) {
+            if same_queries {
+                known_hashes.insert(file.path, file.content_hash);
             }
-        };
-
-        let path = entry.path();
+        } else {
+            stale.push(file);
+        }
+    }
 
-        // Skip directories
-        if !path.is_file() {
-            continue;
+    let workers = match config.workers {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(walked.len().max(1));
+    debug!("Parsing {} files with {} workers", walked.len(), workers);
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::sync_channel::<(usize, PreparedFile)>(workers * 4);
+
+    thread::scope(|scope| -> Result<()> {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let (next, walked, known_hashes) = (&next, &walked, &known_hashes);
+            let queries = queries.clone();
+            scope.spawn(move || {
+                let mut extractor = Extractor::with_queries(queries);
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(file) = walked.get(index) else {
+                        break;
+                    };
+                    let known_hash = known_hashes.get(&file.rel_path).map(String::as_str);
+                    let prepared = prepare_file(&mut extractor, file, known_hash);
+                    if tx.send((index, prepared)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // Single writer: apply results in walk order as they become available
+        let mut pending = BTreeMap::new();
+        let mut next_to_write = 0;
+        for (index, prepared) in rx {
+            pending.insert(index, prepared);
+            while let Some(prepared) = pending.remove(&next_to_write) {
+                apply_file(db, prepared, &mut stale, &mut stats)?;
+                next_to_write += 1;
+            }
         }
+        Ok(())
+    })?;
+
+    // Sweep files that are gone
+    for file in stale {
+        debug!("Removing: {}", file.path);
+        db.delete_file(&file.path)?;
+        stats.removed += 1;
+    }
+
+    // Resolve references
+    info!("Resolving references...");
+    let resolved = db.resolve_references()?;
+    stats.resolved_refs = resolved as u64;
+    stats.resolved_imports = db.resolve_imports(&ProjectLayout::load(&root))? as u64;
+    db.merge_partial_types()?;
+    stats.overrides = db.resolve_overrides()? as u64;
+    db.set_meta(QUERIES_META, &fingerprint)?;
 
-        // Check extension
+    // Commit transaction
+    db.commit()?;
+
+    info!(
+        "Indexed {} files, {} nodes, {} edges ({} refs resolved)",
+        stats.files, stats.nodes, stats.edges, stats.resolved_refs
+    );
+
+    Ok(stats)
+}
+
+/// A source file selected for indexing
+pub(crate) struct WalkedFile {
+    pub(crate) path: PathBuf,
+    pub(crate) rel_path: String,
+    pub(crate) language: Language,
+}
+
+impl WalkedFile {
+    /// Select `path` for indexing if its extension, language and location
+    /// pass the filters in `config`. Gitignore rules are not checked here.
+    pub(crate) fn select(root: &Path, path: &Path, config: &IndexConfig) -> Option<Self> {
+        // Check extension; grammars loaded from the project config add theirs
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !config.extensions.is_empty() && !config.extensions.iter().any(|e| e == ext) {
-            continue;
+        if !config.extensions.is_empty()
+            && !config.extensions.iter().any(|e| e == ext)
+            && grammars::by_extension(ext).is_none()
+        {
+            return None;
         }
 
         // Check if language is supported
         let language = Language::from_extension(ext);
         if language == Language::Unknown {
-            continue;
+            return None;
         }
 
         // Check excluded directories
@@ -142,134 +250,221 @@ pub fn index_codebase(db: &mut Database, config: &IndexConfig) -> Result<IndexSt
         if config.exclude_dirs.iter().any(|d| {
             path_str.contains(&format!("/{}/", d)) || path_str.contains(&format!("\\{}\\", d))
         }) {
-            continue;
+            return None;
         }
 
-        // Read file content
-        let content = match std::fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(err) => {
-                debug!("Failed to read {}: {}", path.display(), err);
-                stats.errors += 1;
-                continue;
-            }
-        };
-
-        // Compute content hash
-        let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
-        let content_hash = hex::encode(hasher.finalize());
-
         // Get relative path
         let rel_path = path
-            .strip_prefix(&root)
+            .strip_prefix(root)
             .unwrap_or(path)
             .display()
             .to_string();
 
-        // Check if file needs reindexing
-        if !db.needs_reindex(&rel_path, &content_hash)? {
-            debug!("Skipping unchanged file: {}", rel_path);
-            stats.skipped += 1;
+        Some(Self {
+            path: path.to_path_buf(),
+            rel_path,
+            language,
+        })
+    }
+}
+
+/// Collect the indexable files in `dir` (the project `root` or a directory
+/// below it), sorted by path relative to `root`
+pub(crate) fn walk_files(root: &Path, dir: &Path, config: &IndexConfig) -> Vec<WalkedFile> {
+    // Build the walker
+    let mut walker = WalkBuilder::new(dir);
+    walker
+        .hidden(false)
+        .git_ignore(config.respect_gitignore)
+        .git_global(config.respect_gitignore)
+        .git_exclude(config.respect_gitignore);
+
+    let mut walked = Vec::new();
+    for entry in walker.build() {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(err) => {
+                warn!("Error walking directory: {}", err);
+                continue;
+            }
+        };
+
+        // Skip directories
+        if !entry.path().is_file() {
             continue;
         }
 
-        debug!("Indexing: {}", rel_path);
+        if let Some(file) = WalkedFile::select(root, entry.path(), config) {
+            walked.push(file);
+        }
+    }
 
-        // Delete existing data for this file
-        db.delete_file(&rel_path)?;
+    walked.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    walked
+}
 
-        // Extract symbols
-        let result = extractor.extract_file(&rel_path, &content);
+/// Outcome of the read/hash/parse stage for one file; needs no database
+pub(crate) enum PreparedFile {
+    Unreadable,
+    Unchanged {
+        rel_path: String,
+    },
+    Parsed {
+        record: FileRecord,
+        result: ExtractionResult,
+    },
+}
 
-        // Store file record FIRST (nodes have FK to files)
-        let file_record = FileRecord {
-            path: rel_path.clone(),
-            content_hash,
-            language,
-            size: content.len() as u64,
-            modified_at: entry
-                .metadata()
-                .ok()
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(0),
-            indexed_at: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(0),
-            node_count: result.nodes.len() as u32,
-        };
-        db.insert_or_update_file(&file_record)?;
-
-        // Store nodes
-        let mut node_count = 0;
-        let mut id_map: std::collections::HashMap<i64, i64> = std::collections::HashMap::new();
-
-        for mut node in result.nodes {
-            let old_id = node.id;
-            node.id = 0; // Will be assigned by DB
-            let new_id = db.insert_node(&node)?;
-            id_map.insert(old_id, new_id);
-            node_count += 1;
+pub(crate) fn prepare_file(
+    extractor: &mut Extractor,
+    file: &WalkedFile,
+    known_hash: Option<&str>,
+) -> PreparedFile {
+    // Read file content
+    let content = match std::fs::read_to_string(&file.path) {
+        Ok(c) => c,
+        Err(err) => {
+            debug!("Failed to read {}: {}", file.path.display(), err);
+            return PreparedFile::Unreadable;
         }
+    };
 
-        // Store edges with mapped IDs
-        for mut edge in result.edges {
-            if let (Some(&new_source), Some(&new_target)) =
-                (id_map.get(&edge.source_id), id_map.get(&edge.target_id))
-            {
-                edge.source_id = new_source;
-                edge.target_id = new_target;
-                db.insert_edge(&edge)?;
-                stats.edges += 1;
-            }
-        }
+    // Compute content hash
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    let content_hash = hex::encode(hasher.finalize());
 
-        // Store unresolved references with mapped IDs
-        for mut uref in result.unresolved_refs {
-            if let Some(&new_source) = id_map.get(&uref.source_node_id) {
-                uref.source_node_id = new_source;
-                db.insert_unresolved_ref(&uref)?;
-            }
+    if known_hash == Some(content_hash.as_str()) {
+        return PreparedFile::Unchanged {
+            rel_path: file.rel_path.clone(),
+        };
+    }
+
+    // Extract symbols
+    let result = extractor.extract_file(&file.rel_path, &content);
+
+    let record = FileRecord {
+        path: file.rel_path.clone(),
+        content_hash,
+        language: file.language,
+        size: content.len() as u64,
+        modified_at: std::fs::metadata(&file.path)
+            .ok()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+        indexed_at: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0),
+        node_count: result.nodes.len() as u32,
+    };
+    PreparedFile::Parsed { record, result }
+}
+
+/// Write one prepared file to the database
+pub(crate) fn apply_file(
+    db: &Database,
+    prepared: PreparedFile,
+    stale: &mut Vec<FileRecord>,
+    stats: &mut IndexStats,
+) -> Result<()> {
+    let (record, result) = match prepared {
+        PreparedFile::Unreadable => {
+            stats.errors += 1;
+            return Ok(());
+        }
+        PreparedFile::Unchanged { rel_path } => {
+            debug!("Skipping unchanged file: {}", rel_path);
+            stats.skipped += 1;
+            return Ok(());
+        }
+        PreparedFile::Parsed { record, result } => (record, result),
+    };
+    let rel_path = record.path.clone();
+
+    // A new path with the content of a vanished file is a rename (`git mv`),
+    // unless the new extension changes the language it is parsed as
+    if db.get_file(&rel_path)?.is_none() {
+        if let Some(pos) = stale
+            .iter()
+            .position(|f| f.content_hash == record.content_hash && f.language == record.language)
+        {
+            let old = stale.remove(pos);
+            debug!("Renamed: {} -> {}", old.path, rel_path);
+            db.rename_file(&old.path, &rel_path)?;
+            stats.renamed += 1;
+            return Ok(());
         }
+    }
 
-        // Update node count in file record
-        let file_record = FileRecord {
-            path: rel_path.clone(),
-            content_hash: file_record.content_hash,
-            language,
-            size: content.len() as u64,
-            modified_at: file_record.modified_at,
-            indexed_at: file_record.indexed_at,
-            node_count,
-        };
-        db.insert_or_update_file(&file_record)?;
+    debug!("Indexing: {}", rel_path);
+
+    // Delete existing data for this file
+    db.delete_file(&rel_path)?;
+
+    // Store file record FIRST (nodes have FK to files)
+    db.insert_or_update_file(&record)?;
+
+    // References elsewhere may now resolve to the symbols defined here
+    let names: Vec<String> = result
+        .nodes
+        .iter()
+        .filter(|n| n.kind != NodeKind::File)
+        .map(|n| n.name.clone())
+        .collect();
+    db.invalidate_refs_named(&names)?;
+
+    // Store nodes
+    let mut id_map: HashMap<i64, i64> = HashMap::new();
+    for mut node in result.nodes {
+        let old_id = node.id;
+        node.id = 0; // Will be assigned by DB
+        let new_id = db.insert_node(&node)?;
+        id_map.insert(old_id, new_id);
+    }
 
-        stats.files += 1;
-        stats.nodes += node_count as u64;
-        stats.errors += result.errors.len() as u64;
+    // Store edges with mapped IDs
+    for mut edge in result.edges {
+        if let (Some(&new_source), Some(&new_target)) =
+            (id_map.get(&edge.source_id), id_map.get(&edge.target_id))
+        {
+            edge.source_id = new_source;
+            edge.target_id = new_target;
+            db.insert_edge(&edge)?;
+            stats.edges += 1;
+        }
     }
 
-    // Resolve references
-    info!("Resolving references...");
-    let resolved = db.resolve_references()?;
-    stats.resolved_refs = resolved as u64;
+    // Store unresolved references with mapped IDs
+    for mut uref in result.unresolved_refs {
+        if let Some(&new_source) = id_map.get(&uref.source_node_id) {
+            uref.source_node_id = new_source;
+            db.insert_unresolved_ref(&uref)?;
+        }
+    }
 
-    // Commit transaction
-    db.commit()?;
+    for error in &result.errors {
+        db.insert_diagnostic(error)?;
+    }
 
-    info!(
-        "Indexed {} files, {} nodes, {} edges ({} refs resolved)",
-        stats.files, stats.nodes, stats.edges, stats.resolved_refs
-    );
+    // Store module imports with mapped IDs
+    for mut import in result.imports {
+        if let Some(&new_source) = id_map.get(&import.source_node_id) {
+            import.source_node_id = new_source;
+            db.insert_import(&import)?;
+        }
+    }
 
-    Ok(stats)
+    stats.files += 1;
+    stats.nodes += id_map.len() as u64;
+    stats.errors += result.errors.len() as u64;
+    Ok(())
 }
 
 /// Statistics from indexing
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct IndexStats {
     pub files: u64,
     pub nodes: u64,
@@ -277,4 +472,13 @@ pub struct IndexStats {
     pub skipped: u64,
     pub errors: u64,
     pub resolved_refs: u64,
+    /// Import statements linked to the files they import
+    pub resolved_imports: u64,
+    /// Methods linked to the base methods they override; Rust trait impl
+    /// methods are counted with the refs
+    pub overrides: u64,
+    /// Files dropped from the index because they no longer exist or are excluded
+    pub removed: u64,
+    /// Files whose path changed but whose content did not
+    pub renamed: u64,
 }
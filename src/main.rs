@@ -3,115 +3,76 @@
 //! Usage:
 //!   codemap serve              Start the MCP server (stdio transport)
 //!   codemap serve --port 8080  Start the MCP server (HTTP transport)
+//!   codemap serve --watch      Start the MCP server and keep the index live
 //!   codemap index [path]       Index a codebase
+//!   codemap watch [path]       Index a codebase and reindex files as they change
 //!   codemap status [path]      Show index statistics
 //!   codemap search <query>     Search for symbols
 //!   codemap context <task>     Build context for a task
+//!   codemap callers <symbol>   ...and one subcommand per MCP tool
+//!
+//! Run `codemap help <command>` for the options of each command.
 
 mod server;
 
-use std::env;
+use std::process::ExitCode;
 
-use anyhow::Result;
+use clap::Parser;
 use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
-use codemap::cli::{context_command, index_command, search_command, status_command};
-
-fn main() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
+use codemap::cli::{index_command, query_command, status_command, watch_command, Cli, Command};
 
-    if args.len() < 2 {
-        print_usage();
-        return Ok(());
-    }
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let global = &cli.global;
 
-    match args[1].as_str() {
-        "serve" => {
-            // Check for --port flag
-            let port = args
-                .iter()
-                .position(|a| a == "--port")
-                .and_then(|i| args.get(i + 1))
-                .and_then(|p| p.parse::<u16>().ok());
-
-            if let Some(port) = port {
-                server::start_http(port)?;
-            } else {
-                server::start_stdio()?;
+    let result = match &cli.command {
+        Command::Serve { port, watch } => {
+            let db_path = global.db.as_deref();
+            match port {
+                Some(port) => {
+                    server::start_http(&global.root, db_path, *port, *watch, global.allow_grammars)
+                }
+                None => server::start_stdio(&global.root, db_path, *watch, global.allow_grammars),
             }
+            .map(|()| ExitCode::SUCCESS)
         }
-        "index" => {
+        Command::Index { path, workers } => {
             setup_logging();
-            let path = args.get(2).map(|s| s.as_str()).unwrap_or(".");
-            index_command(path)?;
-        }
-        "status" => {
-            let path = args.get(2).map(|s| s.as_str()).unwrap_or(".");
-            status_command(path)?;
+            let path = path.as_deref().unwrap_or(&global.root);
+            index_command(
+                path,
+                global.db.as_deref(),
+                *workers,
+                global.allow_grammars,
+                global.output_format(),
+            )
         }
-        "search" => {
-            if args.len() < 3 {
-                eprintln!("Usage: codemap search <query>");
-                return Ok(());
-            }
-            let path = ".";
-            let query = &args[2];
-            search_command(path, query)?;
-        }
-        "context" => {
-            if args.len() < 3 {
-                eprintln!("Usage: codemap context <task>");
-                return Ok(());
-            }
-            let path = ".";
-            let task = args[2..].join(" ");
-            context_command(path, &task)?;
-        }
-        "help" | "--help" | "-h" => {
-            print_usage();
+        Command::Watch { path } => {
+            setup_logging();
+            let path = path.as_deref().unwrap_or(&global.root);
+            watch_command(path, global.db.as_deref(), global.allow_grammars)
         }
-        "--version" | "-V" | "version" => {
-            print_version();
+        Command::Status { path } => {
+            let path = path.as_deref().unwrap_or(&global.root);
+            status_command(
+                path,
+                global.db.as_deref(),
+                global.allow_grammars,
+                global.output_format(),
+            )
         }
-        cmd => {
-            eprintln!("Unknown command: {}", cmd);
-            print_usage();
+        command => query_command(global, command),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {:#}", err);
+            ExitCode::FAILURE
         }
     }
-
-    Ok(())
-}
-
-fn print_usage() {
-    println!(
-        r#"codemap: Semantic code intelligence MCP server
-
-USAGE:
-    codemap <COMMAND> [OPTIONS]
-
-COMMANDS:
-    serve                  Start the MCP server (stdio transport)
-    serve --port <PORT>    Start the MCP server (HTTP transport)
-    index [path]           Index a codebase (default: current directory)
-    status [path]          Show index statistics
-    search <query>         Search for symbols by name
-    context <task>         Build context for a task description
-    help                   Show this help message
-
-EXAMPLES:
-    codemap index                    # Index current directory
-    codemap index ~/projects/myapp   # Index specific directory
-    codemap serve                    # Start MCP server (stdio)
-    codemap serve --port 8080        # Start MCP server (HTTP on port 8080)
-    codemap search "authenticate"    # Find symbols matching "authenticate"
-    codemap context "add user login" # Build context for implementing login
-"#
-    );
-}
-
-fn print_version() {
-    println!("codemap {}", env!("CARGO_PKG_VERSION"));
 }
 
 fn setup_logging() {
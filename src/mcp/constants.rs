@@ -15,5 +15,11 @@ pub const DEFAULT_CONTEXT_LINES: u32 = 3;
 /// Default impact analysis depth
 pub const DEFAULT_IMPACT_DEPTH: u32 = 2;
 
+/// Default maximum number of indirect callers to show in impact analysis
+pub const DEFAULT_INDIRECT_LIMIT: usize = 20;
+
 /// Maximum number of references to show per category
 pub const MAX_REFERENCES_PER_KIND: usize = 20;
+
+/// Default maximum number of dependencies to list
+pub const DEFAULT_DEPENDENCY_LIMIT: u32 = 50;
@@ -1,6 +1,6 @@
 //! Shared formatting utilities for MCP tool outputs
 
-use crate::types::Node;
+use crate::types::{Decorator, DocEntry, DocTags, Language, Node};
 
 /// Format a single node as a list item with location
 pub fn format_node_list_item(node: &Node) -> String {
@@ -35,6 +35,19 @@ pub fn format_node_simple(node: &Node) -> String {
     )
 }
 
+/// Format a node as one of several candidates for an ambiguous symbol, with
+/// what is needed to pick it
+pub fn format_candidate(node: &Node) -> String {
+    format!(
+        "- **{}** `{}` - {}:{} (id {})",
+        node.kind.as_str(),
+        node.qualified_name.as_deref().unwrap_or(&node.name),
+        node.file_path,
+        node.start_line,
+        node.id
+    )
+}
+
 /// Normalize file path (remove leading ./)
 pub fn normalize_path(path: &str) -> &str {
     path.trim_start_matches("./")
@@ -51,8 +64,57 @@ pub fn format_node(node: &Node) -> String {
     if let Some(ref sig) = node.signature {
         output.push_str(&format!("- Signature: `{}`\n", sig));
     }
-    if let Some(ref doc) = node.docstring {
-        output.push_str(&format!("- Doc: {}\n", doc));
+    if !node.decorators.is_empty() {
+        let decorators: Vec<String> = node
+            .decorators
+            .iter()
+            .map(|d| format!("`{}`", format_decorator(d)))
+            .collect();
+        output.push_str(&format!("- Decorators: {}\n", decorators.join(", ")));
+    }
+    if let Some(summary) = node.doc_summary() {
+        output.push_str(&format!("- Doc: {}\n", summary));
+    }
+    output
+}
+
+/// Format the documented parameters, return value, errors and examples of a symbol
+pub fn format_doc_tags(tags: &DocTags, language: Language) -> String {
+    let entries = |entries: &[DocEntry]| -> String {
+        entries
+            .iter()
+            .map(|e| match (e.name.is_empty(), e.description.is_empty()) {
+                (true, _) => format!("- {}\n", e.description),
+                (false, true) => format!("- `{}`\n", e.name),
+                (false, false) => format!("- `{}`: {}\n", e.name, e.description),
+            })
+            .collect()
+    };
+
+    let mut output = String::new();
+    if !tags.params.is_empty() {
+        output.push_str(&format!("\n**Parameters:**\n{}", entries(&tags.params)));
+    }
+    if let Some(ref returns) = tags.returns {
+        output.push_str(&format!("\n**Returns:** {}\n", returns));
+    }
+    if !tags.throws.is_empty() {
+        output.push_str(&format!("\n**Throws:**\n{}", entries(&tags.throws)));
+    }
+    for example in &tags.examples {
+        output.push_str(&format!(
+            "\n**Example:**\n```{}\n{}\n```\n",
+            language.as_str(),
+            example
+        ));
     }
     output
 }
+
+/// Format a decorator as `name` or `name(arguments)`
+pub fn format_decorator(decorator: &Decorator) -> String {
+    match decorator.arguments {
+        Some(ref args) => format!("{}({})", decorator.name, args),
+        None => decorator.name.clone(),
+    }
+}
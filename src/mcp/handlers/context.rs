@@ -3,18 +3,20 @@
 use crate::context::{format_context_markdown, ContextBuilder, ContextOptions};
 use crate::db::Database;
 use crate::mcp::constants::DEFAULT_CONTEXT_MAX_NODES;
+use crate::mcp::response::{ToolResponse, ToolResult};
 use crate::mcp::types::ContextRequest;
 
-pub fn handle_context(db: &Database, project_root: &str, req: &ContextRequest) -> String {
+pub fn handle_context(db: &Database, project_root: &str, req: &ContextRequest) -> ToolResult {
     let builder = ContextBuilder::new(db, project_root.to_string());
     let options = ContextOptions {
-        max_nodes: DEFAULT_CONTEXT_MAX_NODES,
+        max_nodes: req.limit.unwrap_or(DEFAULT_CONTEXT_MAX_NODES),
         include_code: true,
         ..Default::default()
     };
 
-    match builder.build_context(&req.task, &options) {
-        Ok(context) => format_context_markdown(&context),
-        Err(e) => format!("Error: {}", e),
-    }
+    let context = builder.build_context(&req.task, &options)?;
+    Ok(ToolResponse::new(
+        format_context_markdown(&context),
+        context,
+    ))
 }
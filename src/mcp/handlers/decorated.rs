@@ -0,0 +1,86 @@
+//! Handler for decorated tool
+
+use std::collections::BTreeMap;
+
+use serde_json::json;
+
+use crate::db::Database;
+use crate::mcp::format::format_decorator;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
+use crate::mcp::types::DecoratedRequest;
+
+use super::filter_nodes;
+
+pub fn handle_decorated(db: &Database, req: &DecoratedRequest) -> ToolResult {
+    let decorator = decorator_name(&req.decorator);
+    if decorator.is_empty() {
+        return Err(ToolError::invalid("Provide a decorator name"));
+    }
+
+    let nodes = db.find_decorated(decorator)?;
+    let nodes = filter_nodes(nodes, req.kind.as_deref(), req.limit)?;
+
+    let output = if nodes.is_empty() {
+        format!("No symbols decorated with '{}'", decorator)
+    } else {
+        let mut output = format!(
+            "# Decorated with '{}'\n\nFound {} symbol(s):\n\n",
+            decorator,
+            nodes.len()
+        );
+
+        let mut by_file: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        for node in &nodes {
+            by_file
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node);
+        }
+
+        for (file_path, nodes) in by_file {
+            output.push_str(&format!("## {}\n\n", file_path));
+            for node in nodes {
+                let applied: Vec<String> = node
+                    .decorators
+                    .iter()
+                    .filter(|d| matches_name(&d.name, decorator))
+                    .map(|d| format!("`{}`", format_decorator(d)))
+                    .collect();
+                output.push_str(&format!(
+                    "- {} `{}` at line {}: {}\n",
+                    node.kind.as_str(),
+                    node.qualified_name.as_deref().unwrap_or(&node.name),
+                    node.start_line,
+                    applied.join(", ")
+                ));
+            }
+            output.push('\n');
+        }
+        output
+    };
+
+    Ok(ToolResponse::new(
+        output,
+        json!({ "decorator": decorator, "symbols": nodes }),
+    ))
+}
+
+/// The bare name from `@Name`, `@name(args)` or `#[name]`
+fn decorator_name(text: &str) -> &str {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("#[")
+        .map(|t| t.trim_end_matches(']'))
+        .unwrap_or(text);
+    let text = text.trim_start_matches('@');
+    text.split('(').next().unwrap_or(text).trim()
+}
+
+/// Whether a decorator called `name` is the one asked for, in the same way
+/// as [`Database::find_decorated`]
+fn matches_name(name: &str, wanted: &str) -> bool {
+    name == wanted
+        || name
+            .strip_suffix(wanted)
+            .is_some_and(|prefix| prefix.ends_with(['.', ':']))
+}
@@ -0,0 +1,149 @@
+//! Handler for the import dependency graph tool
+
+use serde_json::{json, Map, Value};
+
+use crate::db::{Database, Dependency};
+use crate::mcp::constants::DEFAULT_DEPENDENCY_LIMIT;
+use crate::mcp::format::normalize_path;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
+use crate::mcp::types::DependenciesRequest;
+
+pub fn handle_dependencies(db: &Database, req: &DependenciesRequest) -> ToolResult {
+    let level = req.level.as_deref().unwrap_or("file");
+    let dependencies = match level {
+        "file" => db.get_file_dependencies()?,
+        "module" => db.get_module_dependencies()?,
+        other => {
+            return Err(ToolError::invalid(format!(
+                "Unknown level '{}': expected 'file' or 'module'",
+                other
+            )))
+        }
+    };
+    let direction = req.direction.as_deref().unwrap_or("both");
+    if !matches!(direction, "imports" | "imported_by" | "both") {
+        return Err(ToolError::invalid(format!(
+            "Unknown direction '{}': expected 'imports', 'imported_by' or 'both'",
+            direction
+        )));
+    }
+    let limit = req.limit.unwrap_or(DEFAULT_DEPENDENCY_LIMIT) as usize;
+
+    let Some(path) = req.path.as_deref() else {
+        return Ok(whole_graph(level, dependencies, limit));
+    };
+    let path = normalize_path(path).trim_end_matches('/');
+    let known = match level {
+        "file" => db.get_file(path)?.is_some(),
+        _ => {
+            let prefix = format!("{}/", path);
+            path == "."
+                || db
+                    .get_all_files()?
+                    .iter()
+                    .any(|f| f.path.starts_with(&prefix))
+        }
+    };
+    if !known {
+        return Err(ToolError::not_found(format!(
+            "{} '{}' is not indexed",
+            if level == "file" { "File" } else { "Directory" },
+            path
+        )));
+    }
+
+    let mut output = format!("## Dependencies of `{}`\n\n", path);
+    let mut result = Map::new();
+    result.insert("level".to_string(), json!(level));
+    result.insert("path".to_string(), json!(path));
+
+    let sections = [
+        ("imports", "Imports", "Imports nothing in the index"),
+        (
+            "imported_by",
+            "Imported by",
+            "Not imported by anything in the index",
+        ),
+    ];
+    for (key, title, empty) in sections {
+        if direction != "both" && direction != key {
+            continue;
+        }
+        let items: Vec<(&str, u32)> = dependencies
+            .iter()
+            .filter_map(|d| match key {
+                "imports" if d.from == path => Some((d.to.as_str(), d.imports)),
+                "imported_by" if d.to == path => Some((d.from.as_str(), d.imports)),
+                _ => None,
+            })
+            .take(limit)
+            .collect();
+
+        output.push_str(&format!("### {} ({})\n\n", title, items.len()));
+        if items.is_empty() {
+            output.push_str(&format!("{}\n", empty));
+        }
+        for (other, imports) in &items {
+            output.push_str(&format!("- `{}` ({})\n", other, plural(*imports)));
+        }
+        output.push('\n');
+
+        let items: Vec<Value> = items
+            .iter()
+            .map(|(other, imports)| json!({ "path": other, "imports": imports }))
+            .collect();
+        result.insert(key.to_string(), Value::Array(items));
+    }
+
+    Ok(ToolResponse::new(output, Value::Object(result)))
+}
+
+/// Every dependency in the index, up to `limit`
+fn whole_graph(level: &str, dependencies: Vec<Dependency>, limit: usize) -> ToolResponse {
+    let total = dependencies.len();
+    let dependencies: Vec<Dependency> = dependencies.into_iter().take(limit).collect();
+
+    let output = if dependencies.is_empty() {
+        "No import dependencies found between indexed files".to_string()
+    } else {
+        let mut output = format!("## {} Dependencies\n\n", capitalize(level));
+        if total > dependencies.len() {
+            output.push_str(&format!(
+                "Showing {} of {} dependencies:\n\n",
+                dependencies.len(),
+                total
+            ));
+        } else {
+            output.push_str(&format!("Found {} dependencies:\n\n", total));
+        }
+        for dep in &dependencies {
+            output.push_str(&format!(
+                "- `{}` → `{}` ({})\n",
+                dep.from,
+                dep.to,
+                plural(dep.imports)
+            ));
+        }
+        output
+    };
+
+    ToolResponse::new(
+        output,
+        json!({ "level": level, "total": total, "dependencies": dependencies }),
+    )
+}
+
+fn plural(imports: u32) -> String {
+    match imports {
+        1 => "1 import".to_string(),
+        n => format!("{} imports", n),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
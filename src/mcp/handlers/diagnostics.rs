@@ -0,0 +1,84 @@
+//! Parse diagnostics handler
+
+use std::collections::BTreeMap;
+
+use serde_json::json;
+
+use crate::db::Database;
+use crate::mcp::format::normalize_path;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
+use crate::mcp::types::DiagnosticsRequest;
+use crate::types::{ExtractionError, NodeKind};
+
+pub fn handle_diagnostics(db: &Database, req: &DiagnosticsRequest) -> ToolResult {
+    let path = req.path.as_deref().map(normalize_path);
+    if let Some(path) = path {
+        if db.get_file(path)?.is_none() {
+            return Err(ToolError::not_found(format!(
+                "File '{}' is not indexed",
+                path
+            )));
+        }
+    }
+
+    let diagnostics = db.get_diagnostics(path)?;
+    let mut by_file: BTreeMap<&str, Vec<&ExtractionError>> = BTreeMap::new();
+    for diagnostic in &diagnostics {
+        by_file
+            .entry(diagnostic.file_path.as_str())
+            .or_default()
+            .push(diagnostic);
+    }
+    let limit = req.limit.map_or(usize::MAX, |l| l as usize);
+
+    let mut files = Vec::new();
+    let mut output = match (diagnostics.len(), path) {
+        (0, Some(path)) => format!("No parse errors in `{}`\n", path),
+        (0, None) => "No parse errors in the index\n".to_string(),
+        (count, _) => format!(
+            "## Parse Errors\n\n{} syntax error(s) in {} file(s). Symbols in these files may be missing, and their relationships incomplete.\n\n",
+            count,
+            by_file.len()
+        ),
+    };
+    for (file_path, errors) in by_file.iter().take(limit) {
+        // Symbols parsed from broken source
+        let broken: Vec<_> = db
+            .get_nodes_by_file(file_path)?
+            .into_iter()
+            .filter(|n| n.has_parse_errors && n.kind != NodeKind::File)
+            .collect();
+
+        output.push_str(&format!("### {}\n\n", file_path));
+        for error in errors {
+            match (error.line, error.column) {
+                (Some(line), Some(column)) => {
+                    output.push_str(&format!("- {}:{}: {}\n", line, column, error.message))
+                }
+                _ => output.push_str(&format!("- {}\n", error.message)),
+            }
+        }
+        if !broken.is_empty() {
+            let names: Vec<String> = broken
+                .iter()
+                .map(|n| format!("`{}` (line {})", n.name, n.start_line))
+                .collect();
+            output.push_str(&format!("\nAffected symbols: {}\n", names.join(", ")));
+        }
+        output.push('\n');
+
+        files.push(json!({
+            "path": file_path,
+            "errors": errors,
+            "affected_symbols": broken,
+        }));
+    }
+    if by_file.len() > limit {
+        output.push_str(&format!("... and {} more file(s)\n", by_file.len() - limit));
+    }
+
+    Ok(ToolResponse::new(
+        output,
+        json!({ "total": diagnostics.len(), "files": files }),
+    ))
+}
@@ -1,61 +1,75 @@
 //! Handler for diff impact tool
 
+use serde_json::json;
+
 use crate::db::Database;
-use crate::mcp::format;
+use crate::mcp::format::{self, normalize_path};
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::DiffImpactRequest;
 
-pub fn handle_diff_impact(db: &Database, req: &DiffImpactRequest) -> String {
-    match db.get_diff_impact(&req.file_path, req.start_line, req.end_line) {
-        Ok(nodes) => {
-            if nodes.is_empty() {
-                format!(
-                    "No symbols affected by changes to {}:{}—{}",
-                    req.file_path, req.start_line, req.end_line
-                )
-            } else {
-                let mut output = format!(
-                    "# Impact Analysis: {}:{}—{}\n\n",
-                    req.file_path, req.start_line, req.end_line
-                );
-                output.push_str(&format!(
-                    "Potentially affected: {} symbol(s)\n\n",
-                    nodes.len()
-                ));
-
-                // Separate direct hits from indirect callers
-                let mut direct = Vec::new();
-                let mut indirect = Vec::new();
-
-                for node in nodes {
-                    if node.file_path == req.file_path
-                        && node.start_line <= req.end_line
-                        && node.end_line >= req.start_line
-                    {
-                        direct.push(node);
-                    } else {
-                        indirect.push(node);
-                    }
-                }
-
-                if !direct.is_empty() {
-                    output.push_str("## Directly Modified\n\n");
-                    for node in direct {
-                        output.push_str(&format::format_node(&node));
-                        output.push_str("\n\n");
-                    }
-                }
-
-                if !indirect.is_empty() {
-                    output.push_str("## Indirect Impact (Callers)\n\n");
-                    for node in indirect {
-                        output.push_str(&format::format_node(&node));
-                        output.push_str("\n\n");
-                    }
-                }
-
-                output
+pub fn handle_diff_impact(db: &Database, req: &DiffImpactRequest) -> ToolResult {
+    let file_path = normalize_path(&req.file_path);
+    if db.get_file(file_path)?.is_none() {
+        return Err(ToolError::not_found(format!(
+            "File '{}' is not indexed",
+            file_path
+        )));
+    }
+
+    let mut nodes = db.get_diff_impact(file_path, req.start_line, req.end_line)?;
+    if let Some(limit) = req.limit {
+        nodes.truncate(limit as usize);
+    }
+
+    // Separate direct hits from indirect callers
+    let (direct, indirect): (Vec<_>, Vec<_>) = nodes.into_iter().partition(|node| {
+        node.file_path == file_path
+            && node.start_line <= req.end_line
+            && node.end_line >= req.start_line
+    });
+
+    let output = if direct.is_empty() && indirect.is_empty() {
+        format!(
+            "No symbols affected by changes to {}:{}—{}",
+            file_path, req.start_line, req.end_line
+        )
+    } else {
+        let mut output = format!(
+            "# Impact Analysis: {}:{}—{}\n\n",
+            file_path, req.start_line, req.end_line
+        );
+        output.push_str(&format!(
+            "Potentially affected: {} symbol(s)\n\n",
+            direct.len() + indirect.len()
+        ));
+
+        if !direct.is_empty() {
+            output.push_str("## Directly Modified\n\n");
+            for node in &direct {
+                output.push_str(&format::format_node(node));
+                output.push_str("\n\n");
             }
         }
-        Err(e) => format!("Error: {}", e),
-    }
+
+        if !indirect.is_empty() {
+            output.push_str("## Indirect Impact (Callers)\n\n");
+            for node in &indirect {
+                output.push_str(&format::format_node(node));
+                output.push_str("\n\n");
+            }
+        }
+
+        output
+    };
+
+    Ok(ToolResponse::new(
+        output,
+        json!({
+            "file_path": file_path,
+            "start_line": req.start_line,
+            "end_line": req.end_line,
+            "direct": direct,
+            "indirect": indirect,
+        }),
+    ))
 }
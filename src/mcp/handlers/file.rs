@@ -1,22 +1,42 @@
 //! File listing handler
 
+use serde_json::json;
+
 use crate::db::Database;
 use crate::mcp::format::normalize_path;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::FileRequest;
 
-pub fn handle_file(db: &Database, req: &FileRequest) -> String {
+use super::filter_nodes;
+
+pub fn handle_file(db: &Database, req: &FileRequest) -> ToolResult {
     let path = normalize_path(&req.path);
 
-    let nodes = match db.get_nodes_by_file(path) {
-        Ok(n) => n,
-        Err(e) => return format!("Error: {}", e),
-    };
+    if db.get_file(path)?.is_none() {
+        return Err(ToolError::not_found(format!(
+            "File '{}' is not indexed",
+            path
+        )));
+    }
+
+    let nodes = db.get_nodes_by_file(path)?;
+    let nodes = filter_nodes(nodes, req.kind.as_deref(), req.limit)?;
 
     if nodes.is_empty() {
-        return format!("No symbols found in '{}'. File may not be indexed.", path);
+        return Ok(ToolResponse::new(
+            format!("No symbols found in '{}'", path),
+            json!({ "path": path, "symbols": [] }),
+        ));
     }
 
     let mut output = format!("## Symbols in `{}`\n\n", path);
+    let diagnostics = db.get_diagnostics(Some(path))?;
+    if !diagnostics.is_empty() {
+        output.push_str(&format!(
+            "**Warning:** {} parse error(s) in this file; symbols may be missing (see codemap-diagnostics)\n\n",
+            diagnostics.len()
+        ));
+    }
     output.push_str(&format!("Found {} symbols:\n\n", nodes.len()));
 
     // Group by kind for better readability
@@ -48,5 +68,8 @@ pub fn handle_file(db: &Database, req: &FileRequest) -> String {
         output.push('\n');
     }
 
-    output
+    Ok(ToolResponse::new(
+        output,
+        json!({ "path": path, "symbols": nodes }),
+    ))
 }
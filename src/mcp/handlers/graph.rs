@@ -1,75 +1,123 @@
 //! Graph traversal handlers (callers, callees, impact)
 
+use serde_json::{json, Value};
+
 use crate::db::Database;
 use crate::graph::Graph;
-use crate::mcp::constants::{DEFAULT_GRAPH_LIMIT, DEFAULT_IMPACT_DEPTH};
+use crate::mcp::constants::{DEFAULT_GRAPH_LIMIT, DEFAULT_IMPACT_DEPTH, DEFAULT_INDIRECT_LIMIT};
 use crate::mcp::format::format_node_simple;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::SymbolRequest;
+use crate::types::Node;
 
-pub fn handle_callers(db: &Database, req: &SymbolRequest) -> String {
-    let graph = Graph::new(db);
-    let callers = match graph.find_callers(&req.symbol, DEFAULT_GRAPH_LIMIT) {
-        Ok(c) => c,
-        Err(e) => return format!("Error: {}", e),
-    };
-
-    if callers.is_empty() {
-        return format!("No callers found for '{}'", req.symbol);
-    }
+use super::lookup_symbol;
 
-    let mut output = format!("Found {} callers of '{}':\n\n", callers.len(), req.symbol);
+pub fn handle_callers(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
+    let callers = db.get_weighted_callers(node.id, req.limit.unwrap_or(DEFAULT_GRAPH_LIMIT))?;
 
-    for caller in callers {
-        output.push_str(&format_node_simple(&caller));
-        output.push('\n');
-    }
+    let output = if callers.is_empty() {
+        format!("No callers found for '{}'", req.symbol)
+    } else {
+        let mut output = format!("Found {} callers of '{}':\n\n", callers.len(), req.symbol);
+        push_weighted(&mut output, &callers);
+        output
+    };
 
-    output
+    Ok(ToolResponse::new(
+        output,
+        json!({ "symbol": node, "callers": weighted_json(&callers)? }),
+    ))
 }
 
-pub fn handle_callees(db: &Database, req: &SymbolRequest) -> String {
-    let graph = Graph::new(db);
-    let callees = match graph.find_callees(&req.symbol, DEFAULT_GRAPH_LIMIT) {
-        Ok(c) => c,
-        Err(e) => return format!("Error: {}", e),
-    };
+pub fn handle_callees(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
+    let callees = db.get_weighted_callees(node.id, req.limit.unwrap_or(DEFAULT_GRAPH_LIMIT))?;
 
-    if callees.is_empty() {
-        return format!("No callees found for '{}'", req.symbol);
-    }
+    let output = if callees.is_empty() {
+        format!("No callees found for '{}'", req.symbol)
+    } else {
+        let mut output = format!("'{}' calls {} functions:\n\n", req.symbol, callees.len());
+        push_weighted(&mut output, &callees);
+        output
+    };
 
-    let mut output = format!("'{}' calls {} functions:\n\n", req.symbol, callees.len());
+    Ok(ToolResponse::new(
+        output,
+        json!({ "symbol": node, "callees": weighted_json(&callees)? }),
+    ))
+}
 
-    for callee in callees {
-        output.push_str(&format_node_simple(&callee));
+/// List call graph neighbours, with the confidence of calls that resolved
+/// to one of several candidates
+fn push_weighted(output: &mut String, calls: &[(Node, f64)]) {
+    for (node, confidence) in calls {
+        output.push_str(&format_node_simple(node));
+        if *confidence < 1.0 {
+            output.push_str(&format!(" (confidence {:.0}%)", confidence * 100.0));
+        }
         output.push('\n');
     }
+}
 
-    output
+/// Call graph neighbours as symbols with a `confidence` field
+fn weighted_json(calls: &[(Node, f64)]) -> Result<Vec<Value>, ToolError> {
+    calls
+        .iter()
+        .map(|(node, confidence)| {
+            let mut value = serde_json::to_value(node).map_err(ToolError::internal)?;
+            value["confidence"] = json!(confidence);
+            Ok(value)
+        })
+        .collect()
 }
 
-pub fn handle_impact(db: &Database, req: &SymbolRequest) -> String {
+pub fn handle_impact(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
+
     let graph = Graph::new(db);
-    let analysis = match graph.analyze_impact(&req.symbol, DEFAULT_IMPACT_DEPTH) {
-        Ok(a) => a,
-        Err(e) => return format!("Error: {}", e),
+    let mut analysis = graph.analyze_impact_of(node, DEFAULT_IMPACT_DEPTH)?;
+    let Some(root) = analysis.root.clone() else {
+        return Err(ToolError::not_found(format!(
+            "Symbol '{}' not found",
+            req.symbol
+        )));
     };
 
-    if analysis.root.is_none() {
-        return format!("Symbol '{}' not found", req.symbol);
-    }
-
-    let root = analysis.root.unwrap();
     let mut output = format!(
-        "## Impact Analysis for `{}`\n\n**Location:** {}:{}-{}\n\n",
+        "## Impact Analysis for `{}`\n\n**Location:** {}:{}-{}\n",
         root.name, root.file_path, root.start_line, root.end_line
     );
+    if let Some(ref sig) = root.signature {
+        output.push_str(&format!("**Signature:** `{}`\n", sig));
+    }
+    if let Some(arity) = root.arity {
+        output.push_str(&format!("**Parameters:** {}\n", arity));
+    }
+    output.push('\n');
 
     output.push_str(&format!(
         "**Total Impact:** {} symbols affected\n\n",
         analysis.total_impact
     ));
 
+    if !analysis.overrides.is_empty() {
+        output.push_str(&format!(
+            "### Overrides ({}):\n\n",
+            analysis.overrides.len()
+        ));
+        for method in &analysis.overrides {
+            output.push_str(&format!(
+                "- `{}` ({}:{}) - {}\n",
+                method.qualified_name.as_deref().unwrap_or(&method.name),
+                method.file_path,
+                method.start_line,
+                method.kind.as_str()
+            ));
+        }
+        output.push('\n');
+    }
+
     if !analysis.direct_callers.is_empty() {
         output.push_str(&format!(
             "### Direct Callers ({}):\n\n",
@@ -91,7 +139,9 @@ pub fn handle_impact(db: &Database, req: &SymbolRequest) -> String {
             "\n### Indirect Callers ({}):\n\n",
             analysis.indirect_callers.len()
         ));
-        for caller in analysis.indirect_callers.iter().take(20) {
+        let limit = req.limit.map_or(DEFAULT_INDIRECT_LIMIT, |l| l as usize);
+        analysis.indirect_callers.truncate(limit);
+        for caller in &analysis.indirect_callers {
             output.push_str(&format!(
                 "- `{}` ({}:{}) - {}\n",
                 caller.name,
@@ -102,5 +152,14 @@ pub fn handle_impact(db: &Database, req: &SymbolRequest) -> String {
         }
     }
 
-    output
+    Ok(ToolResponse::new(
+        output,
+        json!({
+            "symbol": root,
+            "overrides": analysis.overrides,
+            "direct_callers": analysis.direct_callers,
+            "indirect_callers": analysis.indirect_callers,
+            "total_impact": analysis.total_impact,
+        }),
+    ))
 }
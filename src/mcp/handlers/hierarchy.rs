@@ -1,24 +1,36 @@
 //! Handler for hierarchy tool
 
+use serde_json::json;
+
 use crate::db::Database;
 use crate::mcp::format;
+use crate::mcp::response::{ToolResponse, ToolResult};
 use crate::mcp::types::SymbolRequest;
 
-pub fn handle_hierarchy(db: &Database, req: &SymbolRequest) -> String {
-    match db.get_hierarchy(&req.symbol) {
-        Ok(nodes) => {
-            if nodes.is_empty() {
-                format!("No hierarchy found for symbol '{}'", req.symbol)
-            } else {
-                let mut output = format!("# Hierarchy for '{}'\n\n", req.symbol);
-                output.push_str(&format!("Found {} related symbols:\n\n", nodes.len()));
-                for node in nodes {
-                    output.push_str(&format::format_node(&node));
-                    output.push_str("\n\n");
-                }
-                output
-            }
-        }
-        Err(e) => format!("Error: {}", e),
+use super::lookup_symbol;
+
+pub fn handle_hierarchy(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
+
+    let mut nodes = db.get_hierarchy(node.id)?;
+    if let Some(limit) = req.limit {
+        nodes.truncate(limit as usize);
     }
+
+    let output = if nodes.is_empty() {
+        format!("No hierarchy found for symbol '{}'", req.symbol)
+    } else {
+        let mut output = format!("# Hierarchy for '{}'\n\n", req.symbol);
+        output.push_str(&format!("Found {} related symbols:\n\n", nodes.len()));
+        for node in &nodes {
+            output.push_str(&format::format_node(node));
+            output.push_str("\n\n");
+        }
+        output
+    };
+
+    Ok(ToolResponse::new(
+        output,
+        json!({ "symbol": node, "related": nodes }),
+    ))
 }
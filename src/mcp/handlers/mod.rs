@@ -1,6 +1,9 @@
 //! Handler modules for MCP tools
 
 pub mod context;
+pub mod decorated;
+pub mod dependencies;
+pub mod diagnostics;
 pub mod diff_impact;
 pub mod file;
 pub mod graph;
@@ -12,3 +15,191 @@ pub mod search;
 pub mod status;
 pub mod symbol;
 pub mod unused;
+
+use crate::db::Database;
+use crate::types::{Language, Node, NodeKind};
+
+use super::format::normalize_path;
+use super::response::ToolError;
+use super::types::{DefinitionRequest, PathRequest, SymbolRequest};
+
+/// Parse an optional symbol kind filter, e.g. "function" or "struct"
+pub(crate) fn parse_kind(kind: Option<&str>) -> Result<Option<NodeKind>, ToolError> {
+    match kind {
+        None => Ok(None),
+        Some(k) => NodeKind::parse(&k.to_lowercase())
+            .map(Some)
+            .ok_or_else(|| ToolError::invalid(format!("Unknown symbol kind '{}'", k))),
+    }
+}
+
+/// What a request says about the symbol it refers to
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SymbolQuery<'a> {
+    /// Bare or qualified name, e.g. `parse`, `Parser::parse` or `Parser.parse`
+    pub symbol: &'a str,
+    pub kind: Option<&'a str>,
+    pub file_path: Option<&'a str>,
+    pub id: Option<i64>,
+}
+
+impl SymbolRequest {
+    pub(crate) fn query(&self) -> SymbolQuery<'_> {
+        SymbolQuery {
+            symbol: &self.symbol,
+            kind: self.kind.as_deref(),
+            file_path: self.file_path.as_deref(),
+            id: self.id,
+        }
+    }
+}
+
+impl DefinitionRequest {
+    pub(crate) fn query(&self) -> SymbolQuery<'_> {
+        SymbolQuery {
+            symbol: &self.symbol,
+            kind: self.kind.as_deref(),
+            file_path: self.file_path.as_deref(),
+            id: self.id,
+        }
+    }
+}
+
+impl PathRequest {
+    /// The symbol the call paths start from
+    pub(crate) fn start_query(&self) -> SymbolQuery<'_> {
+        SymbolQuery {
+            symbol: &self.from,
+            kind: self.from_kind.as_deref(),
+            file_path: self.from_file_path.as_deref(),
+            id: self.from_id,
+        }
+    }
+
+    /// The symbol the call paths lead to
+    pub(crate) fn end_query(&self) -> SymbolQuery<'_> {
+        SymbolQuery {
+            symbol: &self.to,
+            kind: self.to_kind.as_deref(),
+            file_path: self.to_file_path.as_deref(),
+            id: self.to_id,
+        }
+    }
+}
+
+/// Find the one symbol a request refers to
+///
+/// Fails with the matching symbols as candidates when the name, qualified
+/// name, kind and file do not single out one symbol.
+pub(crate) fn lookup_symbol(db: &Database, query: &SymbolQuery) -> Result<Node, ToolError> {
+    if let Some(id) = query.id {
+        return db
+            .get_node(id)?
+            .ok_or_else(|| ToolError::not_found(format!("Symbol with id {} not found", id)));
+    }
+    if query.symbol.is_empty() {
+        return Err(ToolError::invalid("Provide a symbol name or id"));
+    }
+
+    let wanted = parse_kind(query.kind)?;
+    let file_path = query.file_path.map(normalize_path);
+
+    let mut candidates: Vec<Node> = find_by_name(db, query.symbol)?
+        .into_iter()
+        .filter(|n| wanted.is_none_or(|k| n.kind == k))
+        .filter(|n| file_path.is_none_or(|f| n.file_path == f))
+        .collect();
+    // A parameter only answers for its name when nothing else does
+    if candidates.iter().any(|n| n.kind != NodeKind::Parameter) {
+        candidates.retain(|n| n.kind != NodeKind::Parameter);
+    }
+    // Nor does a constructor when its type is among the matches
+    let types: Vec<String> = candidates
+        .iter()
+        .filter(|n| n.kind.is_type())
+        .filter_map(|n| n.qualified_name.clone())
+        .collect();
+    candidates.retain(|n| !is_constructor_of(n, &types));
+    // The parts of a C# partial type are linked to each other's members, so
+    // any part answers for the type
+    let first = candidates.first().cloned();
+    if let Some(first) = first.filter(|n| n.language == Language::CSharp && n.kind.is_type()) {
+        if candidates
+            .iter()
+            .all(|n| n.kind == first.kind && n.qualified_name == first.qualified_name)
+        {
+            candidates.truncate(1);
+        }
+    }
+
+    let mut description = format!("'{}'", query.symbol);
+    if let Some(k) = wanted {
+        description.push_str(&format!(" of kind '{}'", k.as_str()));
+    }
+    if let Some(f) = file_path {
+        description.push_str(&format!(" in '{}'", f));
+    }
+
+    match candidates.len() {
+        0 => Err(ToolError::not_found(format!(
+            "Symbol {} not found",
+            description
+        ))),
+        1 => Ok(candidates.into_iter().next().unwrap()),
+        n => Err(ToolError::ambiguous(
+            format!("Symbol {} is ambiguous: {} candidates", description, n),
+            candidates,
+        )),
+    }
+}
+
+/// Whether `node` is a constructor of one of `types`, given by qualified name:
+/// a method named after the type it is declared in
+fn is_constructor_of(node: &Node, types: &[String]) -> bool {
+    node.kind == NodeKind::Method
+        && node
+            .qualified_name
+            .as_deref()
+            .and_then(|q| q.rsplit_once("::"))
+            .is_some_and(|(owner, name)| {
+                owner.rsplit("::").next() == Some(name) && types.iter().any(|t| t == owner)
+            })
+}
+
+/// Symbols whose name is `symbol`, or whose qualified name ends with it
+fn find_by_name(db: &Database, symbol: &str) -> Result<Vec<Node>, ToolError> {
+    let nodes = db.find_nodes_by_name(symbol)?;
+    if !nodes.is_empty() {
+        return Ok(nodes);
+    }
+
+    // Qualified names are joined with `::`; accept `.` as well
+    let qualified = symbol.replace('.', "::");
+    let Some((_, name)) = qualified.rsplit_once("::") else {
+        return Ok(nodes);
+    };
+    let suffix = format!("::{}", qualified);
+    Ok(db
+        .find_nodes_by_name(name)?
+        .into_iter()
+        .filter(|n| {
+            n.qualified_name
+                .as_deref()
+                .is_some_and(|q| q == qualified || q.ends_with(&suffix))
+        })
+        .collect())
+}
+
+/// Keep the nodes of the requested kind, up to `limit`
+pub(crate) fn filter_nodes(
+    nodes: Vec<Node>,
+    kind: Option<&str>,
+    limit: Option<u32>,
+) -> Result<Vec<Node>, ToolError> {
+    let wanted = parse_kind(kind)?;
+    Ok(nodes
+        .into_iter()
+        .filter(|n| wanted.is_none_or(|k| n.kind == k))
+        .take(limit.map_or(usize::MAX, |l| l as usize))
+        .collect())
+}
@@ -1,36 +1,49 @@
 //! Handler for call path tool
 
+use serde_json::json;
+
 use crate::db::Database;
+use crate::mcp::response::{ToolResponse, ToolResult};
 use crate::mcp::types::PathRequest;
 
-pub fn handle_path(db: &Database, req: &PathRequest) -> String {
-    match db.find_call_path(&req.from, &req.to) {
-        Ok(paths) => {
-            if paths.is_empty() {
-                format!("No call path found from '{}' to '{}'", req.from, req.to)
-            } else {
-                let mut output = format!("# Call Paths from '{}' to '{}'\n\n", req.from, req.to);
-                output.push_str(&format!("Found {} path(s):\n\n", paths.len()));
-
-                for (i, path) in paths.iter().enumerate() {
-                    output.push_str(&format!("## Path {}\n\n", i + 1));
-                    for (j, node) in path.iter().enumerate() {
-                        if j > 0 {
-                            output.push_str("  ↓ calls\n");
-                        }
-                        output.push_str(&format!(
-                            "{}. {} ({}:{})\n",
-                            j + 1,
-                            node.name,
-                            node.file_path,
-                            node.start_line
-                        ));
-                    }
-                    output.push('\n');
+use super::lookup_symbol;
+
+pub fn handle_path(db: &Database, req: &PathRequest) -> ToolResult {
+    let from = lookup_symbol(db, &req.start_query())?;
+    let to = lookup_symbol(db, &req.end_query())?;
+
+    let mut paths = db.find_call_paths_between(&from, &to)?;
+    if let Some(limit) = req.limit {
+        paths.truncate(limit as usize);
+    }
+
+    let output = if paths.is_empty() {
+        format!("No call path found from '{}' to '{}'", from.name, to.name)
+    } else {
+        let mut output = format!("# Call Paths from '{}' to '{}'\n\n", from.name, to.name);
+        output.push_str(&format!("Found {} path(s):\n\n", paths.len()));
+
+        for (i, path) in paths.iter().enumerate() {
+            output.push_str(&format!("## Path {}\n\n", i + 1));
+            for (j, node) in path.iter().enumerate() {
+                if j > 0 {
+                    output.push_str("  ↓ calls\n");
                 }
-                output
+                output.push_str(&format!(
+                    "{}. {} ({}:{})\n",
+                    j + 1,
+                    node.name,
+                    node.file_path,
+                    node.start_line
+                ));
             }
+            output.push('\n');
         }
-        Err(e) => format!("Error: {}", e),
-    }
+        output
+    };
+
+    Ok(ToolResponse::new(
+        output,
+        json!({ "from": from, "to": to, "paths": paths }),
+    ))
 }
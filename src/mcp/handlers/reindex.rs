@@ -1,15 +1,31 @@
 //! Reindexing handler
 
+use serde_json::json;
+
 use crate::db::Database;
 use crate::mcp::format::normalize_path;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::ReindexRequest;
 use crate::{index_codebase, IndexConfig};
 
-pub fn handle_reindex(db: &mut Database, project_root: &str, req: &ReindexRequest) -> String {
+pub fn handle_reindex(
+    db: &mut Database,
+    project_root: &str,
+    allow_grammars: bool,
+    req: &ReindexRequest,
+) -> ToolResult {
+    let config = IndexConfig {
+        root: project_root.to_string(),
+        allow_grammars,
+        ..Default::default()
+    };
+
     // If specific files requested, delete and reindex just those
     if let Some(files) = &req.files {
         if files.is_empty() {
-            return "No files specified. Provide file paths or omit the parameter to reindex all changed files.".to_string();
+            return Err(ToolError::invalid(
+                "No files specified. Provide file paths or omit the parameter to reindex all changed files.",
+            ));
         }
 
         let mut errors = Vec::new();
@@ -25,39 +41,32 @@ pub fn handle_reindex(db: &mut Database, project_root: &str, req: &ReindexReques
         }
 
         // Now run full reindex to pick up the deleted files
-        let config = IndexConfig {
-            root: project_root.to_string(),
-            ..Default::default()
-        };
-
-        match index_codebase(db, &config) {
-            Ok(stats) => {
-                let mut output = format!(
-                    "## Reindex Complete\n\n**Files reindexed:** {}\n**Symbols found:** {}\n**Edges created:** {}\n**References resolved:** {}\n",
-                    stats.files, stats.nodes, stats.edges, stats.resolved_refs
-                );
-                if !errors.is_empty() {
-                    output.push_str(&format!("\n**Errors:** {}\n", errors.join(", ")));
-                }
-                output
-            }
-            Err(e) => format!("Reindex failed: {}", e),
+        let stats = index_codebase(db, &config)
+            .map_err(|e| ToolError::internal(format!("Reindex failed: {}", e)))?;
+
+        let mut output = format!(
+            "## Reindex Complete\n\n**Files reindexed:** {}\n**Symbols found:** {}\n**Edges created:** {}\n**References resolved:** {}\n",
+            stats.files, stats.nodes, stats.edges, stats.resolved_refs
+        );
+        if !errors.is_empty() {
+            output.push_str(&format!("\n**Errors:** {}\n", errors.join(", ")));
         }
+        Ok(ToolResponse::new(
+            output,
+            json!({ "stats": stats, "errors": errors }),
+        ))
     } else {
         // Full incremental reindex
-        let config = IndexConfig {
-            root: project_root.to_string(),
-            ..Default::default()
-        };
-
-        match index_codebase(db, &config) {
-            Ok(stats) => {
-                format!(
-                    "## Reindex Complete\n\n**Files processed:** {}\n**Files skipped (unchanged):** {}\n**Symbols found:** {}\n**Edges created:** {}\n**References resolved:** {}\n**Errors:** {}\n",
-                    stats.files, stats.skipped, stats.nodes, stats.edges, stats.resolved_refs, stats.errors
-                )
-            }
-            Err(e) => format!("Reindex failed: {}", e),
-        }
+        let stats = index_codebase(db, &config)
+            .map_err(|e| ToolError::internal(format!("Reindex failed: {}", e)))?;
+
+        let output = format!(
+            "## Reindex Complete\n\n**Files processed:** {}\n**Files skipped (unchanged):** {}\n**Files removed:** {}\n**Files renamed:** {}\n**Symbols found:** {}\n**Edges created:** {}\n**References resolved:** {}\n**Errors:** {}\n",
+            stats.files, stats.skipped, stats.removed, stats.renamed, stats.nodes, stats.edges, stats.resolved_refs, stats.errors
+        );
+        Ok(ToolResponse::new(
+            output,
+            json!({ "stats": stats, "errors": Vec::<String>::new() }),
+        ))
     }
 }
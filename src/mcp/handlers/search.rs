@@ -1,29 +1,74 @@
 //! Symbol search handler
 
-use crate::db::Database;
+use serde_json::json;
+
+use globset::Glob;
+
+use crate::db::{Database, SearchOptions};
 use crate::mcp::constants::DEFAULT_SEARCH_LIMIT;
 use crate::mcp::format::format_node_with_signature;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::SearchRequest;
+use crate::types::Language;
 
-pub fn handle_search(db: &Database, req: &SearchRequest) -> String {
-    let results = match db.search_nodes(&req.query, None, DEFAULT_SEARCH_LIMIT) {
-        Ok(r) => r,
-        Err(e) => return format!("Error: {}", e),
-    };
+use super::parse_kind;
 
-    if results.is_empty() {
-        return format!("No symbols found matching '{}'", req.query);
-    }
+pub fn handle_search(db: &Database, req: &SearchRequest) -> ToolResult {
+    let language = match req.language.as_deref() {
+        None => None,
+        Some(l) => match Language::parse(l) {
+            Language::Unknown => {
+                return Err(ToolError::invalid(format!("Unknown language '{}'", l)));
+            }
+            language => Some(language),
+        },
+    };
+    let path = match req.path.as_deref() {
+        None => None,
+        Some(p) => Some(
+            Glob::new(p)
+                .map_err(|e| ToolError::invalid(format!("Invalid path glob '{}': {}", p, e)))?
+                .compile_matcher(),
+        ),
+    };
+    let options = SearchOptions {
+        kind: parse_kind(req.kind.as_deref())?,
+        language,
+        path,
+        param_type: req.param_type.clone(),
+        returns: req.returns.clone(),
+        limit: req.limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+    };
 
-    let mut output = format!(
-        "Found {} symbols matching '{}':\n\n",
-        results.len(),
-        req.query
-    );
+    let results = db.search_symbols(&req.query, &options)?;
 
-    for node in results {
-        output.push_str(&format_node_with_signature(&node));
+    let mut described = match req.query.trim() {
+        "" => "the filters".to_string(),
+        query => format!("'{}'", query),
+    };
+    if let Some(ref param_type) = req.param_type {
+        described.push_str(&format!(", taking `{}`", param_type));
+    }
+    if let Some(ref returns) = req.returns {
+        described.push_str(&format!(", returning `{}`", returns));
     }
 
-    output
+    let output = if results.is_empty() {
+        format!("No symbols found matching {}", described)
+    } else {
+        let mut output = format!(
+            "Found {} symbols matching {}:\n\n",
+            results.len(),
+            described
+        );
+        for hit in &results {
+            output.push_str(&format_node_with_signature(&hit.node));
+        }
+        output
+    };
+
+    Ok(ToolResponse::new(
+        output,
+        json!({ "query": req.query, "results": results }),
+    ))
 }
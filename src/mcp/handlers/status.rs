@@ -1,12 +1,10 @@
 //! Status handler
 
 use crate::db::Database;
+use crate::mcp::response::{ToolResponse, ToolResult};
 
-pub fn handle_status(db: &Database) -> String {
-    let stats = match db.get_stats() {
-        Ok(s) => s,
-        Err(e) => return format!("Error: {}", e),
-    };
+pub fn handle_status(db: &Database) -> ToolResult {
+    let stats = db.get_stats()?;
 
     let mut output = String::from("## codemap Index Status\n\n");
 
@@ -17,6 +15,12 @@ pub fn handle_status(db: &Database) -> String {
         "**Database Size:** {:.2} KB\n",
         stats.db_size_bytes as f64 / 1024.0
     ));
+    if stats.parse_errors > 0 {
+        output.push_str(&format!(
+            "**Parse Errors:** {} in {} files (see codemap-diagnostics)\n",
+            stats.parse_errors, stats.files_with_errors
+        ));
+    }
 
     if !stats.languages.is_empty() {
         output.push_str("\n**Languages:**\n");
@@ -32,5 +36,24 @@ pub fn handle_status(db: &Database) -> String {
         }
     }
 
-    output
+    output.push_str("\n**Grammars:**\n");
+    for grammar in &stats.grammars {
+        let version = grammar.version.as_deref().unwrap_or("unknown version");
+        match &grammar.library {
+            Some(library) => output.push_str(&format!(
+                "- {} {} (ABI {}, .{}) from `{}`\n",
+                grammar.name,
+                version,
+                grammar.abi_version,
+                grammar.extensions.join(", ."),
+                library
+            )),
+            None => output.push_str(&format!(
+                "- {} {} (ABI {}, built in)\n",
+                grammar.name, version, grammar.abi_version
+            )),
+        }
+    }
+
+    Ok(ToolResponse::new(output, stats))
 }
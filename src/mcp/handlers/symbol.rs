@@ -3,17 +3,19 @@
 use std::fs;
 use std::path::Path;
 
+use serde_json::json;
+
 use crate::db::Database;
 use crate::mcp::constants::{DEFAULT_CONTEXT_LINES, MAX_REFERENCES_PER_KIND};
+use crate::mcp::format::format_doc_tags;
+use crate::mcp::response::{ToolError, ToolResponse, ToolResult};
 use crate::mcp::types::{DefinitionRequest, SymbolRequest};
 use crate::types::EdgeKind;
 
-pub fn handle_node(db: &Database, req: &SymbolRequest) -> String {
-    let node = match db.find_node_by_name(&req.symbol) {
-        Ok(Some(n)) => n,
-        Ok(None) => return format!("Symbol '{}' not found", req.symbol),
-        Err(e) => return format!("Error: {}", e),
-    };
+use super::lookup_symbol;
+
+pub fn handle_node(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
 
     let mut output = format!("## {}: `{}`\n\n", node.kind.as_str(), node.name);
 
@@ -33,6 +35,11 @@ pub fn handle_node(db: &Database, req: &SymbolRequest) -> String {
     if node.is_exported {
         output.push_str("**Exported:** yes\n");
     }
+    if node.has_parse_errors {
+        output.push_str(
+            "**Parse Errors:** this symbol contains syntax errors; its details may be incomplete\n",
+        );
+    }
 
     if let Some(ref sig) = node.signature {
         output.push_str(&format!("\n**Signature:**\n```\n{}\n```\n", sig));
@@ -41,35 +48,32 @@ pub fn handle_node(db: &Database, req: &SymbolRequest) -> String {
     if let Some(ref doc) = node.docstring {
         output.push_str(&format!("\n**Documentation:**\n{}\n", doc));
     }
+    if let Some(ref tags) = node.doc_tags {
+        output.push_str(&format_doc_tags(tags, node.language));
+    }
 
-    output
+    Ok(ToolResponse::new(output, json!({ "symbol": node })))
 }
 
-pub fn handle_definition(db: &Database, project_root: &str, req: &DefinitionRequest) -> String {
-    let node = match db.find_node_by_name(&req.symbol) {
-        Ok(Some(n)) => n,
-        Ok(None) => return format!("Symbol '{}' not found", req.symbol),
-        Err(e) => return format!("Error: {}", e),
-    };
+pub fn handle_definition(db: &Database, project_root: &str, req: &DefinitionRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
 
     let context_lines = req.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES) as usize;
 
     // Read the source file
     let file_path = Path::new(project_root).join(&node.file_path);
-    let content = match fs::read_to_string(&file_path) {
-        Ok(c) => c,
-        Err(e) => return format!("Error reading file {}: {}", node.file_path, e),
-    };
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| ToolError::internal(format!("reading file {}: {}", node.file_path, e)))?;
 
     let lines: Vec<&str> = content.lines().collect();
     let start = (node.start_line as usize).saturating_sub(1);
     let end = (node.end_line as usize).min(lines.len());
 
     if start >= lines.len() {
-        return format!(
-            "Error: line range {}-{} out of bounds",
+        return Err(ToolError::internal(format!(
+            "line range {}-{} out of bounds",
             node.start_line, node.end_line
-        );
+        )));
     }
 
     // Build output with context
@@ -120,24 +124,30 @@ pub fn handle_definition(db: &Database, project_root: &str, req: &DefinitionRequ
 
     output.push_str("```\n");
 
-    output
+    let ctx_start = start.saturating_sub(context_lines);
+    let ctx_end = (end + context_lines).min(lines.len());
+    Ok(ToolResponse::new(
+        output,
+        json!({
+            "symbol": node,
+            "code": lines[start..end].join("\n"),
+            "context_before": lines[ctx_start..start].join("\n"),
+            "context_after": lines[end..ctx_end].join("\n"),
+        }),
+    ))
 }
 
-pub fn handle_references(db: &Database, req: &SymbolRequest) -> String {
-    let node = match db.find_node_by_name(&req.symbol) {
-        Ok(Some(n)) => n,
-        Ok(None) => return format!("Symbol '{}' not found", req.symbol),
-        Err(e) => return format!("Error: {}", e),
-    };
+pub fn handle_references(db: &Database, req: &SymbolRequest) -> ToolResult {
+    let node = lookup_symbol(db, &req.query())?;
 
     // Get all incoming edges (references TO this symbol)
-    let edges = match db.get_incoming_edges(node.id) {
-        Ok(e) => e,
-        Err(e) => return format!("Error: {}", e),
-    };
+    let edges = db.get_incoming_edges(node.id)?;
 
     if edges.is_empty() {
-        return format!("No references found for '{}'", req.symbol);
+        return Ok(ToolResponse::new(
+            format!("No references found for '{}'", req.symbol),
+            json!({ "symbol": node, "references": [], "total": 0 }),
+        ));
     }
 
     let mut output = format!(
@@ -152,24 +162,38 @@ pub fn handle_references(db: &Database, req: &SymbolRequest) -> String {
     }
 
     let mut total = 0;
+    let mut references = Vec::new();
+    let per_kind = req.limit.map_or(MAX_REFERENCES_PER_KIND, |l| l as usize);
 
     // Process each kind
     for kind in [
         EdgeKind::Calls,
+        EdgeKind::Instantiates,
         EdgeKind::Imports,
         EdgeKind::Extends,
         EdgeKind::Implements,
+        EdgeKind::Overrides,
         EdgeKind::Contains,
         EdgeKind::References,
+        EdgeKind::TypeOf,
+        EdgeKind::Returns,
+        EdgeKind::Decorates,
         EdgeKind::Exports,
     ] {
         if let Some(edges) = by_kind.get(&kind) {
             output.push_str(&format!("### {} ({}):\n\n", kind.as_str(), edges.len()));
             total += edges.len();
 
-            for edge in edges.iter().take(MAX_REFERENCES_PER_KIND) {
+            for edge in edges.iter().take(per_kind) {
                 // Get the source node (what is referencing us)
                 if let Ok(Some(source)) = db.get_node(edge.source_id) {
+                    references.push(json!({
+                        "kind": edge.kind,
+                        "source": source,
+                        "file_path": edge.file_path,
+                        "line": edge.line,
+                        "confidence": edge.confidence,
+                    }));
                     output.push_str(&format!(
                         "- `{}` ({}) - {}",
                         source.name,
@@ -179,15 +203,15 @@ pub fn handle_references(db: &Database, req: &SymbolRequest) -> String {
                     if let Some(line) = edge.line {
                         output.push_str(&format!(":{}", line));
                     }
+                    if edge.confidence < 1.0 {
+                        output.push_str(&format!(" (confidence {:.0}%)", edge.confidence * 100.0));
+                    }
                     output.push('\n');
                 }
             }
 
-            if edges.len() > MAX_REFERENCES_PER_KIND {
-                output.push_str(&format!(
-                    "  ... and {} more\n",
-                    edges.len() - MAX_REFERENCES_PER_KIND
-                ));
+            if edges.len() > per_kind {
+                output.push_str(&format!("  ... and {} more\n", edges.len() - per_kind));
             }
             output.push('\n');
         }
@@ -195,5 +219,8 @@ pub fn handle_references(db: &Database, req: &SymbolRequest) -> String {
 
     output.push_str(&format!("**Total references:** {}\n", total));
 
-    output
+    Ok(ToolResponse::new(
+        output,
+        json!({ "symbol": node, "references": references, "total": total }),
+    ))
 }
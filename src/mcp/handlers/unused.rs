@@ -1,47 +1,52 @@
 //! Handler for unused symbols tool
 
+use serde_json::json;
+
 use crate::db::Database;
+use crate::mcp::response::{ToolResponse, ToolResult};
+use crate::mcp::types::UnusedRequest;
+
+use super::filter_nodes;
+
+pub fn handle_unused(db: &Database, req: &UnusedRequest) -> ToolResult {
+    let nodes = db.find_unused_symbols()?;
+    let nodes = filter_nodes(nodes, req.kind.as_deref(), req.limit)?;
+
+    let output = if nodes.is_empty() {
+        "No unused symbols found (all symbols are referenced or exported)".to_string()
+    } else {
+        let mut output = format!(
+            "# Unused Symbols\n\nFound {} unused symbols:\n\n",
+            nodes.len()
+        );
 
-pub fn handle_unused(db: &Database) -> String {
-    match db.find_unused_symbols() {
-        Ok(nodes) => {
-            if nodes.is_empty() {
-                "No unused symbols found (all symbols are referenced or exported)".to_string()
-            } else {
-                let mut output = format!(
-                    "# Unused Symbols\n\nFound {} unused symbols:\n\n",
-                    nodes.len()
-                );
-
-                // Group by file
-                let mut by_file: std::collections::HashMap<String, Vec<_>> =
-                    std::collections::HashMap::new();
-                for node in nodes {
-                    by_file
-                        .entry(node.file_path.clone())
-                        .or_default()
-                        .push(node);
-                }
-
-                let mut files: Vec<_> = by_file.keys().collect();
-                files.sort();
-
-                for file_path in files {
-                    let nodes = &by_file[file_path];
-                    output.push_str(&format!("## {}\n\n", file_path));
-                    for node in nodes {
-                        output.push_str(&format!(
-                            "- {} `{}` at line {}\n",
-                            node.kind.as_str(),
-                            node.name,
-                            node.start_line
-                        ));
-                    }
-                    output.push('\n');
-                }
-                output
+        // Group by file
+        let mut by_file: std::collections::HashMap<&str, Vec<_>> = std::collections::HashMap::new();
+        for node in &nodes {
+            by_file
+                .entry(node.file_path.as_str())
+                .or_default()
+                .push(node);
+        }
+
+        let mut files: Vec<_> = by_file.keys().collect();
+        files.sort();
+
+        for file_path in files {
+            let nodes = &by_file[file_path];
+            output.push_str(&format!("## {}\n\n", file_path));
+            for node in nodes {
+                output.push_str(&format!(
+                    "- {} `{}` at line {}\n",
+                    node.kind.as_str(),
+                    node.name,
+                    node.start_line
+                ));
             }
+            output.push('\n');
         }
-        Err(e) => format!("Error: {}", e),
-    }
+        output
+    };
+
+    Ok(ToolResponse::new(output, json!({ "symbols": nodes })))
 }
@@ -17,22 +17,29 @@
 //! - codemap-unused: Find unused/dead code
 //! - codemap-implementations: Find implementations of interfaces/traits
 //! - codemap-diff-impact: Analyze impact of code changes
+//! - codemap-dependencies: File and module import dependencies
+//! - codemap-decorated: Find symbols carrying a decorator, annotation or attribute
+//! - codemap-diagnostics: Parse errors and the symbols they affect
 
 mod constants;
-mod format;
-mod handlers;
+pub(crate) mod format;
+pub(crate) mod handlers;
+pub mod response;
 mod types;
 
 use std::sync::{Arc, Mutex};
 
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
-    model::{ServerCapabilities, ServerInfo},
-    tool, tool_handler, tool_router, ServerHandler,
+    model::{CallToolResult, ServerCapabilities, ServerInfo},
+    tool, tool_handler, tool_router, ErrorData, ServerHandler,
 };
 
 use crate::db::Database;
 
+use response::{call_tool_result, ToolError};
+
+pub use response::ResponseFormat;
 pub use types::*;
 
 /// MCP server handler for codemap
@@ -41,6 +48,7 @@ pub struct CodeMapHandler {
     tool_router: ToolRouter<Self>,
     db: Arc<Mutex<Database>>,
     project_root: String,
+    allow_grammars: bool,
 }
 
 #[tool_router]
@@ -50,6 +58,7 @@ impl CodeMapHandler {
             tool_router: Self::tool_router(),
             db: Arc::new(Mutex::new(db)),
             project_root,
+            allow_grammars: false,
         }
     }
 
@@ -59,21 +68,30 @@ impl CodeMapHandler {
             tool_router: Self::tool_router(),
             db,
             project_root,
+            allow_grammars: false,
         }
     }
 
+    /// Let reindexing load the grammar libraries of the project config
+    pub fn allow_grammars(mut self, allow: bool) -> Self {
+        self.allow_grammars = allow;
+        self
+    }
+
     /// Build focused context for a specific task
     #[tool(
         name = "codemap-context",
         description = "Build focused code context for a specific task. Returns entry points, related symbols, and code snippets."
     )]
-    fn codemap_context(&self, Parameters(req): Parameters<ContextRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_context(
+        &self,
+        Parameters(req): Parameters<ContextRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::context::handle_context(&db, &self.project_root, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::context::handle_context(&db, &self.project_root, &req)
+        Ok(call_tool_result("codemap-context", result, req.format))
     }
 
     /// Quick symbol search by name
@@ -81,13 +99,15 @@ impl CodeMapHandler {
         name = "codemap-search",
         description = "Quick symbol search by name. Returns locations only (no code)."
     )]
-    fn codemap_search(&self, Parameters(req): Parameters<SearchRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_search(
+        &self,
+        Parameters(req): Parameters<SearchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::search::handle_search(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::search::handle_search(&db, &req)
+        Ok(call_tool_result("codemap-search", result, req.format))
     }
 
     /// Find all callers of a symbol
@@ -95,13 +115,15 @@ impl CodeMapHandler {
         name = "codemap-callers",
         description = "Find all functions/methods that call a specific symbol."
     )]
-    fn codemap_callers(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_callers(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::graph::handle_callers(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::graph::handle_callers(&db, &req)
+        Ok(call_tool_result("codemap-callers", result, req.format))
     }
 
     /// Find all callees of a symbol
@@ -109,13 +131,15 @@ impl CodeMapHandler {
         name = "codemap-callees",
         description = "Find all functions/methods that a specific symbol calls."
     )]
-    fn codemap_callees(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_callees(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::graph::handle_callees(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::graph::handle_callees(&db, &req)
+        Ok(call_tool_result("codemap-callees", result, req.format))
     }
 
     /// Analyze the impact of changing a symbol
@@ -123,13 +147,15 @@ impl CodeMapHandler {
         name = "codemap-impact",
         description = "Analyze the impact radius of changing a symbol."
     )]
-    fn codemap_impact(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_impact(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::graph::handle_impact(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::graph::handle_impact(&db, &req)
+        Ok(call_tool_result("codemap-impact", result, req.format))
     }
 
     /// Get the full source code definition of a symbol
@@ -137,13 +163,15 @@ impl CodeMapHandler {
         name = "codemap-definition",
         description = "Get the full source code of a symbol. Returns the complete definition with surrounding context lines."
     )]
-    fn codemap_definition(&self, Parameters(req): Parameters<DefinitionRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_definition(
+        &self,
+        Parameters(req): Parameters<DefinitionRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::symbol::handle_definition(&db, &self.project_root, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::symbol::handle_definition(&db, &self.project_root, &req)
+        Ok(call_tool_result("codemap-definition", result, req.format))
     }
 
     /// List all symbols in a specific file
@@ -151,13 +179,15 @@ impl CodeMapHandler {
         name = "codemap-file",
         description = "List all symbols defined in a specific file. Returns functions, classes, methods, etc."
     )]
-    fn codemap_file(&self, Parameters(req): Parameters<FileRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_file(
+        &self,
+        Parameters(req): Parameters<FileRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::file::handle_file(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::file::handle_file(&db, &req)
+        Ok(call_tool_result("codemap-file", result, req.format))
     }
 
     /// Find all references to a symbol
@@ -165,13 +195,15 @@ impl CodeMapHandler {
         name = "codemap-references",
         description = "Find all references to a symbol including calls, imports, type usages, and other relationships."
     )]
-    fn codemap_references(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_references(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::symbol::handle_references(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::symbol::handle_references(&db, &req)
+        Ok(call_tool_result("codemap-references", result, req.format))
     }
 
     /// Trigger incremental reindexing
@@ -179,13 +211,20 @@ impl CodeMapHandler {
         name = "codemap-reindex",
         description = "Trigger incremental reindexing of the codebase. Only changed files are re-parsed."
     )]
-    fn codemap_reindex(&self, Parameters(req): Parameters<ReindexRequest>) -> String {
-        let mut db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_reindex(
+        &self,
+        Parameters(req): Parameters<ReindexRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(mut db) => handlers::reindex::handle_reindex(
+                &mut db,
+                &self.project_root,
+                self.allow_grammars,
+                &req,
+            ),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::reindex::handle_reindex(&mut db, &self.project_root, &req)
+        Ok(call_tool_result("codemap-reindex", result, req.format))
     }
 
     /// Get detailed information about a symbol
@@ -193,13 +232,15 @@ impl CodeMapHandler {
         name = "codemap-node",
         description = "Get detailed information about a specific code symbol."
     )]
-    fn codemap_node(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_node(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::symbol::handle_node(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::symbol::handle_node(&db, &req)
+        Ok(call_tool_result("codemap-node", result, req.format))
     }
 
     /// Get index statistics
@@ -207,13 +248,15 @@ impl CodeMapHandler {
         name = "codemap-status",
         description = "Get the status of the codemap index. Shows statistics about indexed files, symbols, and relationships."
     )]
-    fn codemap_status(&self) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_status(
+        &self,
+        Parameters(req): Parameters<StatusRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::status::handle_status(&db),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::status::handle_status(&db)
+        Ok(call_tool_result("codemap-status", result, req.format))
     }
 
     /// Get class/module hierarchy
@@ -221,13 +264,15 @@ impl CodeMapHandler {
         name = "codemap-hierarchy",
         description = "Get the hierarchy of a symbol showing parent/child contains relationships (e.g., class contains methods)."
     )]
-    fn codemap_hierarchy(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_hierarchy(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::hierarchy::handle_hierarchy(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::hierarchy::handle_hierarchy(&db, &req)
+        Ok(call_tool_result("codemap-hierarchy", result, req.format))
     }
 
     /// Find call path between two symbols
@@ -235,13 +280,15 @@ impl CodeMapHandler {
         name = "codemap-path",
         description = "Find call paths from one symbol to another. Shows how function A reaches function B through intermediate calls."
     )]
-    fn codemap_path(&self, Parameters(req): Parameters<PathRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_path(
+        &self,
+        Parameters(req): Parameters<PathRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::path::handle_path(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::path::handle_path(&db, &req)
+        Ok(call_tool_result("codemap-path", result, req.format))
     }
 
     /// Find unused/dead code
@@ -249,13 +296,63 @@ impl CodeMapHandler {
         name = "codemap-unused",
         description = "Find unused symbols (functions, methods, classes) with no incoming references. Helps identify dead code."
     )]
-    fn codemap_unused(&self) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_unused(
+        &self,
+        Parameters(req): Parameters<UnusedRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::unused::handle_unused(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
+        Ok(call_tool_result("codemap-unused", result, req.format))
+    }
 
-        handlers::unused::handle_unused(&db)
+    /// Show the import dependency graph
+    #[tool(
+        name = "codemap-dependencies",
+        description = "Show which files (or directories, with level 'module') import which, resolved from import statements. Give a path to see what it imports and what imports it."
+    )]
+    fn codemap_dependencies(
+        &self,
+        Parameters(req): Parameters<DependenciesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::dependencies::handle_dependencies(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
+        };
+        Ok(call_tool_result("codemap-dependencies", result, req.format))
+    }
+
+    /// Find symbols carrying a decorator
+    #[tool(
+        name = "codemap-decorated",
+        description = "Find all symbols carrying a decorator, annotation or attribute, e.g. every @Transactional method or #[tokio::main] function. Matches the full name or its last segment."
+    )]
+    fn codemap_decorated(
+        &self,
+        Parameters(req): Parameters<DecoratedRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::decorated::handle_decorated(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
+        };
+        Ok(call_tool_result("codemap-decorated", result, req.format))
+    }
+
+    /// List parse errors
+    #[tool(
+        name = "codemap-diagnostics",
+        description = "List syntax errors found while indexing, by file, with the symbols parsed from broken code. Check this when a symbol or relationship seems to be missing."
+    )]
+    fn codemap_diagnostics(
+        &self,
+        Parameters(req): Parameters<DiagnosticsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::diagnostics::handle_diagnostics(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
+        };
+        Ok(call_tool_result("codemap-diagnostics", result, req.format))
     }
 
     /// Find implementations of an interface/trait
@@ -263,13 +360,19 @@ impl CodeMapHandler {
         name = "codemap-implementations",
         description = "Find all classes/structs that implement an interface or extend a trait/class."
     )]
-    fn codemap_implementations(&self, Parameters(req): Parameters<SymbolRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_implementations(
+        &self,
+        Parameters(req): Parameters<SymbolRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::implementations::handle_implementations(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::implementations::handle_implementations(&db, &req)
+        Ok(call_tool_result(
+            "codemap-implementations",
+            result,
+            req.format,
+        ))
     }
 
     /// Analyze impact of code changes
@@ -277,13 +380,15 @@ impl CodeMapHandler {
         name = "codemap-diff-impact",
         description = "Analyze the impact of changing a specific region of code. Shows directly modified symbols and their callers."
     )]
-    fn codemap_diff_impact(&self, Parameters(req): Parameters<DiffImpactRequest>) -> String {
-        let db = match self.db.lock() {
-            Ok(db) => db,
-            Err(e) => return format!("Error: {}", e),
+    fn codemap_diff_impact(
+        &self,
+        Parameters(req): Parameters<DiffImpactRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match self.db.lock() {
+            Ok(db) => handlers::diff_impact::handle_diff_impact(&db, &req),
+            Err(e) => Err(ToolError::internal(e)),
         };
-
-        handlers::diff_impact::handle_diff_impact(&db, &req)
+        Ok(call_tool_result("codemap-diff-impact", result, req.format))
     }
 }
 
@@ -299,7 +404,10 @@ impl ServerHandler for CodeMapHandler {
                 codemap-references for all usages of a symbol, codemap-hierarchy for class/module structure, \
                 codemap-path to find call paths between functions, codemap-unused to find dead code, \
                 codemap-implementations to find interface/trait implementations, \
-                codemap-diff-impact to analyze change impact, and codemap-reindex to refresh after edits."
+                codemap-diff-impact to analyze change impact, codemap-dependencies for the file and module \
+                import graph, codemap-decorated to find symbols by decorator or annotation, \
+                codemap-diagnostics for parse errors that make the graph of a file unreliable, \
+                and codemap-reindex to refresh after edits."
                     .into(),
             ),
             capabilities: ServerCapabilities::builder().enable_tools().build(),
@@ -307,3 +415,244 @@ impl ServerHandler for CodeMapHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &[(&str, &str)] = &[
+        ("lib.rs", "fn caller() { callee(); }\nfn callee() {}\n"),
+        (
+            "parser.py",
+            "class Parser:\n    def parse(self):\n        pass\n",
+        ),
+        ("util.py", "def parse():\n    pass\n"),
+    ];
+
+    fn handler(files: &[(&str, &str)]) -> (tempfile::TempDir, CodeMapHandler) {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, code) in files {
+            std::fs::write(dir.path().join(path), code).unwrap();
+        }
+        let mut db = Database::in_memory().unwrap();
+        let config = crate::IndexConfig {
+            root: dir.path().display().to_string(),
+            ..Default::default()
+        };
+        crate::index_codebase(&mut db, &config).unwrap();
+        let root = config.root.clone();
+        (dir, CodeMapHandler::new(db, root))
+    }
+
+    fn symbol(name: &str, format: ResponseFormat) -> Parameters<SymbolRequest> {
+        Parameters(SymbolRequest {
+            symbol: name.to_string(),
+            kind: None,
+            file_path: None,
+            id: None,
+            limit: None,
+            format,
+        })
+    }
+
+    fn text(result: &CallToolResult) -> String {
+        result.content[0].as_text().unwrap().text.clone()
+    }
+
+    #[test]
+    fn test_tools_return_structured_content() {
+        let (_dir, handler) = handler(PROJECT);
+        let result = handler
+            .codemap_callers(symbol("callee", ResponseFormat::Markdown))
+            .unwrap();
+        assert!(text(&result).contains("caller"));
+        let json = result.structured_content.unwrap();
+        assert_eq!(json["tool"], "codemap-callers");
+        assert_eq!(json["result"]["symbol"]["name"], "callee");
+        assert_eq!(json["result"]["callers"][0]["name"], "caller");
+    }
+
+    #[test]
+    fn test_json_format_and_errors() {
+        let (_dir, handler) = handler(PROJECT);
+        let result = handler
+            .codemap_node(symbol("missing", ResponseFormat::Json))
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let json: serde_json::Value = serde_json::from_str(&text(&result)).unwrap();
+        assert_eq!(json["status"], "error");
+        assert_eq!(json["error"]["code"], "not_found");
+
+        let result = handler
+            .codemap_status(Parameters(StatusRequest::default()))
+            .unwrap();
+        assert_eq!(
+            result.structured_content.unwrap()["result"]["total_files"],
+            3
+        );
+    }
+
+    /// The JSON body of a `codemap-node` call, successful or not
+    fn node(handler: &CodeMapHandler, request: Parameters<SymbolRequest>) -> serde_json::Value {
+        let result = handler.codemap_node(request).unwrap();
+        serde_json::from_str(&text(&result)).unwrap()
+    }
+
+    #[test]
+    fn test_lookup_narrows_by_qualified_name_kind_and_file() {
+        let (_dir, handler) = handler(PROJECT);
+
+        // Both `parse` symbols are listed when nothing tells them apart
+        let json = node(&handler, symbol("parse", ResponseFormat::Json));
+        assert_eq!(json["error"]["code"], "ambiguous");
+        let mut files: Vec<&str> = json["error"]["candidates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["file_path"].as_str().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["parser.py", "util.py"]);
+
+        let json = node(&handler, symbol("Parser.parse", ResponseFormat::Json));
+        assert_eq!(json["result"]["symbol"]["file_path"], "parser.py");
+
+        let mut request = symbol("parse", ResponseFormat::Json);
+        request.0.file_path = Some("./parser.py".to_string());
+        let json = node(&handler, request);
+        assert_eq!(json["result"]["symbol"]["qualified_name"], "Parser::parse");
+
+        let mut request = symbol("parse", ResponseFormat::Json);
+        request.0.kind = Some("class".to_string());
+        let json = node(&handler, request);
+        assert_eq!(json["error"]["code"], "not_found");
+    }
+
+    #[test]
+    fn test_lookup_prefers_types_over_their_constructors() {
+        let (_dir, handler) = handler(&[
+            (
+                "Invoice.java",
+                "class Invoice {\n    public Invoice() {}\n    void send() {}\n}\n",
+            ),
+            (
+                "a.cs",
+                "namespace Shop;\npublic partial class Order {\n    public Order() {}\n}\n",
+            ),
+            (
+                "b.cs",
+                "namespace Shop;\npublic partial class Order {\n    public void Pay() {}\n}\n",
+            ),
+            (
+                "cart.cpp",
+                "class Cart {\npublic:\n    Cart();\n};\nCart::Cart() {}\n",
+            ),
+        ]);
+        let kind_in = |name: &str, file_path: Option<&str>| {
+            let mut request = symbol(name, ResponseFormat::Json);
+            request.0.file_path = file_path.map(str::to_string);
+            let json = node(&handler, request);
+            json["result"]["symbol"]["kind"].clone()
+        };
+
+        assert_eq!(kind_in("Invoice", None), "class");
+        assert_eq!(kind_in("Cart", None), "class");
+        assert_eq!(kind_in("Order", Some("a.cs")), "class");
+        // The parts of the partial type still answer as one
+        assert_eq!(kind_in("Order", None), "class");
+
+        // The constructor is reachable by kind or qualified name
+        let mut request = symbol("Invoice", ResponseFormat::Json);
+        request.0.kind = Some("method".to_string());
+        let json = node(&handler, request);
+        assert_eq!(
+            json["result"]["symbol"]["qualified_name"],
+            "Invoice::Invoice"
+        );
+        assert_eq!(kind_in("Shop.Order.Order", None), "method");
+    }
+
+    #[test]
+    fn test_type_tools_use_the_selected_symbol() {
+        let (_dir, handler) = handler(&[
+            (
+                "a.py",
+                "class Base:\n    def run(self):\n        pass\n\nclass Child(Base):\n    pass\n",
+            ),
+            (
+                "b.py",
+                "class Base:\n    def stop(self):\n        pass\n\nclass Other(Base):\n    pass\n",
+            ),
+        ]);
+
+        let mut request = symbol("Base", ResponseFormat::Json);
+        request.0.file_path = Some("a.py".to_string());
+        let json = handler
+            .codemap_implementations(request)
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let implementations = json["result"]["implementations"].as_array().unwrap();
+        assert_eq!(implementations.len(), 1);
+        assert_eq!(implementations[0]["name"], "Child");
+
+        let mut request = symbol("Base", ResponseFormat::Json);
+        request.0.file_path = Some("b.py".to_string());
+        let json = handler
+            .codemap_hierarchy(request)
+            .unwrap()
+            .structured_content
+            .unwrap();
+        let related: Vec<&str> = json["result"]["related"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["name"].as_str().unwrap())
+            .collect();
+        assert!(related.contains(&"stop"));
+        assert!(!related.contains(&"run"));
+    }
+
+    #[test]
+    fn test_path_selects_each_end() {
+        let (_dir, handler) = handler(&[
+            ("a.py", "def main():\n    save()\n\ndef save():\n    pass\n"),
+            ("b.py", "def save():\n    pass\n"),
+        ]);
+        let main_id = handler
+            .db
+            .lock()
+            .unwrap()
+            .find_node_by_name("main")
+            .unwrap()
+            .unwrap()
+            .id;
+
+        let path = |to_file_path: Option<&str>| PathRequest {
+            from: String::new(),
+            from_kind: None,
+            from_file_path: None,
+            from_id: Some(main_id),
+            to: "save".to_string(),
+            to_kind: Some("function".to_string()),
+            to_file_path: to_file_path.map(str::to_string),
+            to_id: None,
+            limit: None,
+            format: ResponseFormat::Json,
+        };
+        let paths = |to_file_path| {
+            let json = handler
+                .codemap_path(Parameters(path(to_file_path)))
+                .unwrap()
+                .structured_content
+                .unwrap();
+            json["result"]["paths"].as_array().unwrap().len()
+        };
+        assert_eq!(paths(Some("a.py")), 1);
+        assert_eq!(paths(Some("b.py")), 0);
+
+        // Without a file, the target name is ambiguous
+        let result = handler.codemap_path(Parameters(path(None))).unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+}
@@ -0,0 +1,207 @@
+//! Tool responses: a markdown report plus the same result as versioned JSON
+//!
+//! Every tool produces a [`ToolResult`]. It is rendered as markdown for
+//! people and as a JSON envelope for programs:
+//!
+//! ```json
+//! { "schema_version": 1, "tool": "codemap-callers", "status": "ok", "result": { ... } }
+//! { "schema_version": 1, "tool": "codemap-callers", "status": "error",
+//!   "error": { "code": "not_found", "message": "Symbol 'foo' not found" } }
+//! ```
+//!
+//! Fields may be added to `result` within a schema version; renaming or
+//! removing one requires bumping [`SCHEMA_VERSION`].
+
+use std::fmt::Display;
+
+use rmcp::model::{CallToolResult, Content};
+use rmcp::schemars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::mcp::format::format_candidate;
+use crate::types::Node;
+
+/// Version of the JSON result schemas
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Output format requested for a tool's text content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+/// Why a tool produced no result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The symbol or file is not in the index
+    NotFound,
+    /// The request itself is invalid, e.g. an unknown symbol kind
+    InvalidRequest,
+    /// Several symbols match the request; the candidates are listed
+    Ambiguous,
+    /// The index could not be read or updated
+    Internal,
+}
+
+/// A failed tool call
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Symbols that matched an ambiguous request
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<Node>,
+}
+
+impl ToolError {
+    fn new(code: ErrorCode, message: String) -> Self {
+        Self {
+            code,
+            message,
+            candidates: Vec::new(),
+        }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message.into())
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidRequest, message.into())
+    }
+
+    pub fn ambiguous(message: impl Into<String>, candidates: Vec<Node>) -> Self {
+        Self {
+            candidates,
+            ..Self::new(ErrorCode::Ambiguous, message.into())
+        }
+    }
+
+    pub fn internal(err: impl Display) -> Self {
+        Self::new(ErrorCode::Internal, format!("Error: {}", err))
+    }
+
+    /// The message, followed by the candidates of an ambiguous request
+    pub fn to_markdown(&self) -> String {
+        if self.candidates.is_empty() {
+            return self.message.clone();
+        }
+        let mut output = format!("{}\n\n", self.message);
+        for node in &self.candidates {
+            output.push_str(&format_candidate(node));
+            output.push('\n');
+        }
+        output.push_str("\nNarrow it down with a qualified name, file path, kind or id.\n");
+        output
+    }
+}
+
+impl From<anyhow::Error> for ToolError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::internal(err)
+    }
+}
+
+/// A successful tool call
+#[derive(Debug, Clone)]
+pub struct ToolResponse {
+    /// Report for people
+    pub markdown: String,
+    /// The same result for programs
+    pub result: Value,
+}
+
+impl ToolResponse {
+    pub fn new(markdown: String, result: impl Serialize) -> Self {
+        let result = serde_json::to_value(result)
+            .unwrap_or_else(|e| json!({ "serialization_error": e.to_string() }));
+        Self { markdown, result }
+    }
+}
+
+pub type ToolResult = Result<ToolResponse, ToolError>;
+
+/// Wrap a tool result in the versioned JSON envelope
+pub fn envelope(tool: &str, result: &ToolResult) -> Value {
+    match result {
+        Ok(response) => json!({
+            "schema_version": SCHEMA_VERSION,
+            "tool": tool,
+            "status": "ok",
+            "result": response.result,
+        }),
+        Err(error) => json!({
+            "schema_version": SCHEMA_VERSION,
+            "tool": tool,
+            "status": "error",
+            "error": error,
+        }),
+    }
+}
+
+/// Render a tool result as text in the requested format
+pub fn render(tool: &str, result: &ToolResult, format: ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => match result {
+            Ok(response) => response.markdown.clone(),
+            Err(error) => error.to_markdown(),
+        },
+        ResponseFormat::Json => {
+            serde_json::to_string_pretty(&envelope(tool, result)).unwrap_or_default()
+        }
+    }
+}
+
+/// Build the MCP result: text in the requested format plus the JSON envelope
+/// as structured content
+pub fn call_tool_result(tool: &str, result: ToolResult, format: ResponseFormat) -> CallToolResult {
+    let text = render(tool, &result, format);
+    let mut call_result = if result.is_ok() {
+        CallToolResult::success(vec![Content::text(text)])
+    } else {
+        CallToolResult::error(vec![Content::text(text)])
+    };
+    call_result.structured_content = Some(envelope(tool, &result));
+    call_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_is_versioned() {
+        let ok: ToolResult = Ok(ToolResponse::new("# Report".into(), json!({ "count": 1 })));
+        let value = envelope("codemap-status", &ok);
+        assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        assert_eq!(value["tool"], "codemap-status");
+        assert_eq!(value["status"], "ok");
+        assert_eq!(value["result"]["count"], 1);
+
+        let err: ToolResult = Err(ToolError::not_found("Symbol 'x' not found"));
+        let value = envelope("codemap-node", &err);
+        assert_eq!(value["status"], "error");
+        assert_eq!(value["error"]["code"], "not_found");
+        assert_eq!(
+            render("codemap-node", &err, ResponseFormat::Markdown),
+            "Symbol 'x' not found"
+        );
+    }
+
+    #[test]
+    fn test_call_tool_result_carries_structured_content() {
+        let ok: ToolResult = Ok(ToolResponse::new("# Report".into(), json!({})));
+        let result = call_tool_result("codemap-status", ok, ResponseFormat::Markdown);
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(result.structured_content.unwrap()["status"], "ok");
+
+        let err: ToolResult = Err(ToolError::invalid("Unknown symbol kind 'x'"));
+        let result = call_tool_result("codemap-search", err, ResponseFormat::Json);
+        assert_eq!(result.is_error, Some(true));
+    }
+}
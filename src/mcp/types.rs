@@ -3,25 +3,76 @@
 use rmcp::schemars;
 use serde::Deserialize;
 
+use super::response::ResponseFormat;
+
 /// Request for context tool
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ContextRequest {
     #[schemars(description = "Description of the task, bug, or feature to explore")]
     pub task: String,
+    #[schemars(description = "Maximum number of symbols to include (default: 20)")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for search tool
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct SearchRequest {
-    #[schemars(description = "Symbol name or partial name to search for")]
+    #[serde(default)]
+    #[schemars(
+        description = "Symbol name, partial name or words from its signature or docs; 'userrepo' finds 'UserRepository' and small typos are tolerated. May be empty when filtering by param_type or returns"
+    )]
     pub query: String,
+    #[schemars(
+        description = "Optional: only return symbols of this kind (e.g., 'function', 'struct')"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Optional: only return symbols in this language (e.g., 'rust')")]
+    pub language: Option<String>,
+    #[schemars(
+        description = "Optional: only return symbols in files matching this glob (e.g., 'src/**/*.rs')"
+    )]
+    pub path: Option<String>,
+    #[schemars(
+        description = "Optional: only return functions and methods taking a parameter of this type, or a type containing it (e.g., '&mut Connection'); '_' matches any type"
+    )]
+    pub param_type: Option<String>,
+    #[schemars(
+        description = "Optional: only return functions and methods whose return type is or contains this type (e.g., 'Result<_, AuthError>'); '_' matches any type"
+    )]
+    pub returns: Option<String>,
+    #[schemars(description = "Maximum number of results (default: 10)")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for symbol-based tools (callers, callees, impact, node)
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct SymbolRequest {
-    #[schemars(description = "Function/method/class name")]
+    #[serde(default)]
+    #[schemars(
+        description = "Function/method/class name, optionally qualified (e.g., 'Parser::parse' or 'Parser.parse')"
+    )]
     pub symbol: String,
+    #[schemars(
+        description = "Optional: kind of the symbol (e.g., 'function', 'struct') when several share the name"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Optional: file that defines the symbol, relative to project root")]
+    pub file_path: Option<String>,
+    #[schemars(
+        description = "Optional: id of the symbol, as listed by other tools; takes precedence over the name"
+    )]
+    pub id: Option<i64>,
+    #[schemars(description = "Maximum number of results to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for file-based tools
@@ -29,15 +80,40 @@ pub struct SymbolRequest {
 pub struct FileRequest {
     #[schemars(description = "File path relative to project root (e.g., 'src/main.rs')")]
     pub path: String,
+    #[schemars(
+        description = "Optional: only list symbols of this kind (e.g., 'function', 'struct')"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Maximum number of symbols to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for definition tool with context options
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct DefinitionRequest {
-    #[schemars(description = "Function/method/class name")]
+    #[serde(default)]
+    #[schemars(
+        description = "Function/method/class name, optionally qualified (e.g., 'Parser::parse' or 'Parser.parse')"
+    )]
     pub symbol: String,
+    #[schemars(
+        description = "Optional: kind of the symbol (e.g., 'function', 'struct') when several share the name"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Optional: file that defines the symbol, relative to project root")]
+    pub file_path: Option<String>,
+    #[schemars(
+        description = "Optional: id of the symbol, as listed by other tools; takes precedence over the name"
+    )]
+    pub id: Option<i64>,
     #[schemars(description = "Number of context lines before/after (default: 3)")]
     pub context_lines: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for reindex tool
@@ -47,15 +123,45 @@ pub struct ReindexRequest {
         description = "Optional: specific files to reindex. If empty, reindexes all changed files."
     )]
     pub files: Option<Vec<String>>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for call path tool
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct PathRequest {
-    #[schemars(description = "Starting symbol name")]
+    #[serde(default)]
+    #[schemars(description = "Starting symbol name, optionally qualified (e.g., 'Server::run')")]
     pub from: String,
-    #[schemars(description = "Target symbol name")]
+    #[schemars(
+        description = "Optional: kind of the starting symbol (e.g., 'function') when several share the name"
+    )]
+    pub from_kind: Option<String>,
+    #[schemars(
+        description = "Optional: file that defines the starting symbol, relative to project root"
+    )]
+    pub from_file_path: Option<String>,
+    #[schemars(
+        description = "Optional: id of the starting symbol, as listed by other tools; takes precedence over the name"
+    )]
+    pub from_id: Option<i64>,
+    #[serde(default)]
+    #[schemars(description = "Target symbol name, optionally qualified")]
     pub to: String,
+    #[schemars(description = "Optional: kind of the target symbol when several share the name")]
+    pub to_kind: Option<String>,
+    #[schemars(
+        description = "Optional: file that defines the target symbol, relative to project root"
+    )]
+    pub to_file_path: Option<String>,
+    #[schemars(description = "Optional: id of the target symbol; takes precedence over the name")]
+    pub to_id: Option<i64>,
+    #[schemars(description = "Maximum number of paths to list (at most 5 are searched)")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
 
 /// Request for diff impact tool
@@ -67,4 +173,81 @@ pub struct DiffImpactRequest {
     pub start_line: u32,
     #[schemars(description = "End line of the change (1-indexed)")]
     pub end_line: u32,
+    #[schemars(description = "Maximum number of affected symbols to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
+}
+
+/// Request for unused symbols tool
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct UnusedRequest {
+    #[schemars(
+        description = "Optional: only list symbols of this kind (e.g., 'function', 'struct')"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Maximum number of symbols to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
+}
+
+/// Request for decorated tool
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct DecoratedRequest {
+    #[schemars(
+        description = "Decorator, annotation or attribute name, with or without '@' (e.g., 'Transactional', 'app.route', 'tokio::main')"
+    )]
+    pub decorator: String,
+    #[schemars(
+        description = "Optional: only list symbols of this kind (e.g., 'function', 'method')"
+    )]
+    pub kind: Option<String>,
+    #[schemars(description = "Maximum number of symbols to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
+}
+
+/// Request for diagnostics tool
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct DiagnosticsRequest {
+    #[schemars(
+        description = "Optional: file to show the parse errors of, relative to project root. Omit for every file."
+    )]
+    pub path: Option<String>,
+    #[schemars(description = "Maximum number of files to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
+}
+
+/// Request for dependencies tool
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct DependenciesRequest {
+    #[schemars(
+        description = "Optional: file (or directory, with level 'module') to show the dependencies of. Omit for the whole graph."
+    )]
+    pub path: Option<String>,
+    #[schemars(description = "Granularity: 'file' (default) or 'module' (directories)")]
+    pub level: Option<String>,
+    #[schemars(description = "With a path: 'imports', 'imported_by' or 'both' (default)")]
+    pub direction: Option<String>,
+    #[schemars(description = "Maximum number of dependencies to list")]
+    pub limit: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
+}
+
+/// Request for status tool
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct StatusRequest {
+    #[serde(default)]
+    #[schemars(description = "Output format: 'markdown' (default) or 'json'")]
+    pub format: ResponseFormat,
 }
@@ -10,11 +10,12 @@ use rmcp::{
     },
     ServiceExt,
 };
+use std::path::Path;
 use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
-use codemap::cli::initialize_server_database;
+use codemap::cli::{initialize_server_database, start_watching};
 use codemap::mcp::CodeMapHandler;
 
 fn setup_debug_logging() {
@@ -28,14 +29,26 @@ fn setup_debug_logging() {
 
 /// Start MCP server with stdio transport
 #[tokio::main]
-pub async fn start_stdio() -> Result<()> {
+pub async fn start_stdio(
+    root: &str,
+    db_path: Option<&Path>,
+    watch: bool,
+    allow_grammars: bool,
+) -> Result<()> {
     setup_debug_logging();
     info!("Starting codemap MCP server (stdio)");
 
-    let (project_root, db) = initialize_server_database()?;
+    let (project_root, db) = initialize_server_database(root, db_path, allow_grammars)?;
     info!("Project root: {}", project_root);
 
-    let handler = CodeMapHandler::new(db, project_root);
+    let db = Arc::new(std::sync::Mutex::new(db));
+    let _watcher = if watch {
+        Some(start_watching(&project_root, db.clone(), allow_grammars)?)
+    } else {
+        None
+    };
+
+    let handler = CodeMapHandler::new_shared(db, project_root).allow_grammars(allow_grammars);
     let service = handler.serve(stdio()).await?;
 
     info!("MCP server running on stdio");
@@ -46,20 +59,34 @@ pub async fn start_stdio() -> Result<()> {
 
 /// Start MCP server with HTTP transport
 #[tokio::main]
-pub async fn start_http(port: u16) -> Result<()> {
+pub async fn start_http(
+    root: &str,
+    db_path: Option<&Path>,
+    port: u16,
+    watch: bool,
+    allow_grammars: bool,
+) -> Result<()> {
     setup_debug_logging();
     info!("Starting codemap MCP server (HTTP on port {})", port);
 
-    let (project_root, db) = initialize_server_database()?;
+    let (project_root, db) = initialize_server_database(root, db_path, allow_grammars)?;
     info!("Project root: {}", project_root);
 
     // Wrap database in Arc for sharing across HTTP sessions
     let db = Arc::new(std::sync::Mutex::new(db));
+    let _watcher = if watch {
+        Some(start_watching(&project_root, db.clone(), allow_grammars)?)
+    } else {
+        None
+    };
     let cancellation_token = tokio_util::sync::CancellationToken::new();
 
     // Create HTTP service - each session gets a handler with shared database
     let service = StreamableHttpService::new(
-        move || Ok(CodeMapHandler::new_shared(db.clone(), project_root.clone())),
+        move || {
+            Ok(CodeMapHandler::new_shared(db.clone(), project_root.clone())
+                .allow_grammars(allow_grammars))
+        },
         LocalSessionManager::default().into(),
         StreamableHttpServerConfig {
             cancellation_token: cancellation_token.child_token(),
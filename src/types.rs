@@ -7,6 +7,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::extraction::grammars;
+
 /// Represents the kind of code symbol
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -90,6 +92,28 @@ impl NodeKind {
             _ => None,
         }
     }
+
+    /// Whether this kind declares a type (valid target of extends/implements)
+    pub fn is_type(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::Class
+                | NodeKind::Struct
+                | NodeKind::Interface
+                | NodeKind::Trait
+                | NodeKind::Protocol
+                | NodeKind::Enum
+                | NodeKind::TypeAlias
+        )
+    }
+
+    /// Whether this kind is a member declared inside another symbol
+    pub fn is_member(&self) -> bool {
+        matches!(
+            self,
+            NodeKind::Field | NodeKind::Property | NodeKind::EnumMember | NodeKind::Parameter
+        )
+    }
 }
 
 /// Represents the kind of relationship between nodes
@@ -161,8 +185,7 @@ impl EdgeKind {
 }
 
 /// Supported programming languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     Rust,
     TypeScript,
@@ -179,11 +202,16 @@ pub enum Language {
     Ruby,
     Swift,
     Kotlin,
+    /// A language whose grammar is loaded at runtime, by grammar name
+    Plugin(&'static str),
     Unknown,
 }
 
 impl Language {
     pub fn from_extension(ext: &str) -> Self {
+        if let Some(grammar) = grammars::by_extension(ext) {
+            return grammar.language();
+        }
         match ext.to_lowercase().as_str() {
             "rs" => Language::Rust,
             "ts" => Language::TypeScript,
@@ -205,7 +233,15 @@ impl Language {
     }
 
     pub fn parse(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
+        let s = s.to_lowercase();
+        Self::named(&s)
+            .or_else(|| grammars::by_name(&s).map(|g| g.language()))
+            .unwrap_or(Language::Unknown)
+    }
+
+    /// The language with this lowercase name, other than runtime grammars
+    pub fn named(s: &str) -> Option<Self> {
+        Some(match s {
             "rust" => Language::Rust,
             "typescript" => Language::TypeScript,
             "javascript" => Language::JavaScript,
@@ -221,8 +257,8 @@ impl Language {
             "ruby" => Language::Ruby,
             "swift" => Language::Swift,
             "kotlin" => Language::Kotlin,
-            _ => Language::Unknown,
-        }
+            _ => return None,
+        })
     }
 
     pub fn as_str(&self) -> &'static str {
@@ -242,11 +278,24 @@ impl Language {
             Language::Ruby => "ruby",
             Language::Swift => "swift",
             Language::Kotlin => "kotlin",
+            Language::Plugin(name) => name,
             Language::Unknown => "unknown",
         }
     }
 }
 
+impl Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Language::parse(&String::deserialize(deserializer)?))
+    }
+}
+
 /// A location in source code
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
@@ -303,16 +352,121 @@ pub struct Node {
     pub start_column: u32,
     pub end_column: u32,
     pub signature: Option<String>,
+    /// Parameters, return type, type parameters, receiver and declared
+    /// errors of a function or method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_parts: Option<SignatureParts>,
+    /// Number of declared parameters of a function or method, not counting
+    /// a `self`/`this` receiver
+    #[serde(default)]
+    pub arity: Option<u32>,
     pub visibility: Visibility,
     pub docstring: Option<String>,
+    /// Parameters, return value, errors and examples described by the docstring
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_tags: Option<DocTags>,
     pub is_async: bool,
     pub is_static: bool,
     pub is_exported: bool,
+    /// The symbol's source contains syntax errors, or the symbol was recovered
+    /// from one, so its relationships may be incomplete
+    #[serde(default)]
+    pub has_parse_errors: bool,
     pub language: Language,
+    /// Decorators, annotations and attributes applied to the symbol, in source order
+    #[serde(default)]
+    pub decorators: Vec<Decorator>,
+}
+
+impl Node {
+    /// First paragraph of the documentation, on one line
+    pub fn doc_summary(&self) -> Option<String> {
+        let doc = self.docstring.as_deref()?;
+        let paragraph = doc.split("\n\n").next().unwrap_or(doc);
+        let summary: Vec<&str> = paragraph.split_whitespace().collect();
+        (!summary.is_empty()).then(|| summary.join(" "))
+    }
+}
+
+/// A decorator (`@app.route("/")`), annotation (`@Transactional`) or
+/// attribute (`#[derive(Debug)]`) applied to a symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Decorator {
+    /// Name as written, without `@` or `#[...]`, e.g. `app.route` or `tokio::main`
+    pub name: String,
+    /// Argument list as written, without the surrounding parentheses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
+/// A function or method signature broken into its parts, with types as written
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignatureParts {
+    /// Parameters in order, without the receiver
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<Param>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<String>,
+    /// Generic type parameters, lifetimes and template parameters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_params: Vec<TypeParam>,
+    /// How a method receives its object when declared: `&mut self`,
+    /// `*Server` for Go, `self`/`cls` for Python, `this: Window` for TypeScript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<String>,
+    /// Exception types of a `throws` clause
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throws: Vec<String>,
+}
+
+/// A declared parameter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Param {
+    /// Empty for unnamed parameters such as C's `int` in `f(int)`
+    pub name: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Rest and splat parameters: `...args`, `*args`, `**kwargs`, `String...`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub variadic: bool,
+}
+
+/// A generic type parameter and the bounds it must satisfy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeParam {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bounds: Vec<String>,
+}
+
+/// Structured parts of a symbol's documentation: `@param` tags, Rust
+/// `# Arguments` sections, Python `Args:` sections and their equivalents
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocTags {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<DocEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub returns: Option<String>,
+    /// Errors, exceptions and panics, named by their type when the docs do so
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub throws: Vec<DocEntry>,
+    /// Example code, without its markdown fences
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<String>,
+}
+
+/// A documented parameter or error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocEntry {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    pub description: String,
 }
 
 /// Represents a relationship/edge between code symbols
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub id: i64,
     pub source_id: i64,
@@ -322,6 +476,29 @@ pub struct Edge {
     pub file_path: Option<String>,
     pub line: Option<u32>,
     pub column: Option<u32>,
+    /// How certain the resolver is that the target is correct (0.0-1.0).
+    /// Ambiguous references produce one edge per candidate, sharing the weight.
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+}
+
+fn default_confidence() -> f64 {
+    1.0
+}
+
+impl Default for Edge {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            source_id: 0,
+            target_id: 0,
+            kind: EdgeKind::default(),
+            file_path: None,
+            line: None,
+            column: None,
+            confidence: default_confidence(),
+        }
+    }
 }
 
 /// Metadata about an indexed file
@@ -345,6 +522,27 @@ pub struct UnresolvedReference {
     pub file_path: String,
     pub line: u32,
     pub column: u32,
+    /// Name of the source symbol when it is only known by name, e.g. `Type`
+    /// in `impl Trait for Type`. `source_node_id` is then the enclosing node.
+    #[serde(default)]
+    pub source_name: Option<String>,
+    /// Qualifier of a call such as `Foo` in `Foo::new()` or `util` in `util.parse()`.
+    /// `self`/`this` are replaced by the enclosing type; for imports it is the module path.
+    #[serde(default)]
+    pub receiver: Option<String>,
+}
+
+/// A module named by an import statement, resolved to files after indexing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleImport {
+    /// The import node, or the module node of a Rust `mod foo;`
+    pub source_node_id: i64,
+    /// Module path as written: `crate::db::Node`, `./util`, `..models.User`,
+    /// `github.com/x/y`, `com.shop.*`, `<stdio.h>`
+    pub module: String,
+    pub file_path: String,
+    pub line: u32,
+    pub language: Language,
 }
 
 /// Result of extracting symbols from a file
@@ -353,15 +551,19 @@ pub struct ExtractionResult {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
     pub unresolved_refs: Vec<UnresolvedReference>,
+    pub imports: Vec<ModuleImport>,
     pub errors: Vec<ExtractionError>,
 }
 
-/// Error during extraction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Error during extraction: a file that could not be parsed, or a syntax
+/// error within one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExtractionError {
     pub message: String,
     pub file_path: String,
+    /// 1-based line, for syntax errors
     pub line: Option<u32>,
+    /// 1-based column, for syntax errors
     pub column: Option<u32>,
 }
 
@@ -402,6 +604,29 @@ pub struct IndexStats {
     pub db_size_bytes: u64,
     pub languages: Vec<(Language, u64)>,
     pub node_kinds: Vec<(NodeKind, u64)>,
+    /// Syntax errors recorded while parsing
+    #[serde(default)]
+    pub parse_errors: u64,
+    /// Files with at least one syntax error
+    #[serde(default)]
+    pub files_with_errors: u64,
+    /// Grammars available for parsing
+    #[serde(default)]
+    pub grammars: Vec<GrammarInfo>,
+}
+
+/// A tree-sitter grammar, compiled in or loaded at runtime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrammarInfo {
+    pub name: String,
+    /// Version declared by the grammar, if it declares one
+    pub version: Option<String>,
+    /// Tree-sitter ABI the grammar was generated for
+    pub abi_version: usize,
+    /// Shared library of a grammar loaded at runtime
+    pub library: Option<String>,
+    /// File extensions of a grammar loaded at runtime
+    pub extensions: Vec<String>,
 }
 
 /// Context built for a task
@@ -479,6 +704,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_node_kind_is_type() {
+        assert!(NodeKind::Class.is_type());
+        assert!(NodeKind::Trait.is_type());
+        assert!(NodeKind::TypeAlias.is_type());
+        assert!(!NodeKind::Function.is_type());
+        assert!(!NodeKind::Method.is_type());
+        assert!(!NodeKind::File.is_type());
+    }
+
     // EdgeKind tests
     #[test]
     fn test_edge_kind_as_str() {
@@ -609,12 +844,17 @@ mod tests {
             start_column: 0,
             end_column: 1,
             signature: Some("fn test_fn() -> bool".to_string()),
+            signature_parts: None,
+            arity: None,
             visibility: Visibility::Public,
             docstring: Some("A test function".to_string()),
+            doc_tags: None,
             is_async: false,
             is_static: false,
             is_exported: true,
+            has_parse_errors: false,
             language: Language::Rust,
+            decorators: Vec::new(),
         };
 
         assert_eq!(node.name, "test_fn");
@@ -633,6 +873,7 @@ mod tests {
             file_path: Some("src/lib.rs".to_string()),
             line: Some(15),
             column: Some(4),
+            confidence: 1.0,
         };
 
         assert_eq!(edge.source_id, 10);
@@ -0,0 +1,614 @@
+//! File-system watch mode
+//!
+//! Subscribes to change notifications under the project root and reindexes
+//! only the files that were touched. Notifications are debounced so that
+//! bursts such as branch switches are applied as a single batch, and the
+//! database lock is only held while the parsed results are written.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use tracing::{debug, info, warn};
+
+use crate::db::{Database, ProjectLayout, LAYOUT_FILES};
+use crate::extraction::{grammars, Extractor, Queries, QUERIES_DIR};
+use crate::{
+    apply_file, prepare_file, walk_files, IndexConfig, IndexStats, PreparedFile, WalkedFile,
+};
+
+/// How long the tree must be quiet before a batch of changes is applied
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Directory of codemap's own files: the default database, queries and
+/// grammar config. Nothing in it is indexed.
+const CODEMAP_DIR: &str = ".codemap";
+
+/// Suffixes SQLite gives the files it keeps next to a database
+const DATABASE_SIDECARS: &[&str] = &["-wal", "-shm", "-journal"];
+
+/// A running watch; dropping it stops watching
+pub struct Watcher {
+    _debouncer: Debouncer<RecommendedWatcher>,
+    thread: JoinHandle<()>,
+}
+
+impl Watcher {
+    /// Block the calling thread for as long as the watch runs
+    pub fn wait(self) {
+        let Self { _debouncer, thread } = self;
+        thread.join().ok();
+    }
+}
+
+/// Watch `config.root` and keep `db` in sync with it until the returned
+/// `Watcher` is dropped
+///
+/// Only changes made after this call are picked up; run `index_codebase`
+/// first to catch up with anything that changed while nobody was watching.
+pub fn watch(db: Arc<Mutex<Database>>, config: IndexConfig, debounce: Duration) -> Result<Watcher> {
+    let root = Path::new(&config.root).canonicalize()?;
+    let (tx, rx) = mpsc::channel::<DebounceEventResult>();
+
+    let mut debouncer = new_debouncer(debounce, tx)?;
+    debouncer.watcher().watch(&root, RecursiveMode::Recursive)?;
+    info!("Watching {} for changes", root.display());
+
+    let db_path = lock(&db)?.path();
+    let mut tree = WatchedTree::new(root, config, db_path.as_deref())?;
+    let thread = thread::spawn(move || {
+        for events in rx {
+            let events = match events {
+                Ok(events) => events,
+                Err(err) => {
+                    warn!("Watch error: {}", err);
+                    continue;
+                }
+            };
+            let paths: Vec<PathBuf> = events.into_iter().map(|e| e.path).collect();
+            match tree.sync(&db, &paths) {
+                Ok(stats) if stats.files + stats.removed + stats.renamed > 0 => info!(
+                    "Reindexed {} files ({} removed, {} renamed), {} refs resolved",
+                    stats.files, stats.removed, stats.renamed, stats.resolved_refs
+                ),
+                Ok(_) => {}
+                Err(err) => warn!("Failed to apply changes: {}", err),
+            }
+        }
+    });
+
+    Ok(Watcher {
+        _debouncer: debouncer,
+        thread,
+    })
+}
+
+/// The project tree as seen by the indexer, with the filters `index_codebase`
+/// applies when it walks it
+struct WatchedTree {
+    root: PathBuf,
+    config: IndexConfig,
+    ignores: IgnoreRules,
+    queries: Arc<Queries>,
+    /// The database and its sidecar files, whose writes must not be
+    /// mistaken for changes to the tree
+    db_files: Vec<PathBuf>,
+}
+
+impl WatchedTree {
+    fn new(root: PathBuf, config: IndexConfig, db_path: Option<&Path>) -> Result<Self> {
+        let ignores = IgnoreRules::load(&root, &config);
+        let queries = Queries::load(&root, config.allow_grammars)?;
+        let mut db_files = Vec::new();
+        if let Some(path) = db_path.and_then(|p| p.canonicalize().ok()) {
+            for suffix in DATABASE_SIDECARS {
+                let mut sidecar = path.clone().into_os_string();
+                sidecar.push(suffix);
+                db_files.push(PathBuf::from(sidecar));
+            }
+            db_files.push(path);
+        }
+        Ok(Self {
+            root,
+            config,
+            ignores,
+            queries,
+            db_files,
+        })
+    }
+
+    /// Bring the index up to date with the given changed paths
+    fn sync(&mut self, db: &Mutex<Database>, paths: &[PathBuf]) -> Result<IndexStats> {
+        let mut stats = IndexStats::default();
+
+        // Edited ignore files change what belongs in the index from now on
+        if paths
+            .iter()
+            .any(|p| p.file_name().is_some_and(|n| n == ".gitignore"))
+        {
+            self.ignores = IgnoreRules::load(&self.root, &self.config);
+        }
+        // Edited queries and grammars apply to the files changed from now
+        // on; files already indexed are extracted again by the next
+        // `codemap index`
+        let queries_dir = self.root.join(QUERIES_DIR);
+        let config_file = self.root.join(grammars::CONFIG_FILE);
+        if paths
+            .iter()
+            .any(|p| p.starts_with(&queries_dir) || *p == config_file)
+        {
+            match Queries::load(&self.root, self.config.allow_grammars) {
+                Ok(queries) => {
+                    info!("Reloaded extraction queries; run `codemap index` to apply them to unchanged files");
+                    self.queries = queries;
+                }
+                Err(err) => warn!("Keeping the previous extraction queries: {:#}", err),
+            }
+        }
+
+        // Import resolution follows the manifests in the project root
+        let layout_changed = paths
+            .iter()
+            .any(|p| LAYOUT_FILES.iter().any(|f| *p == self.root.join(f)));
+
+        // Split the touched paths into files to parse and paths to drop.
+        // The database and the rest of codemap's own files are never indexed,
+        // and writing the index must not trigger another sync.
+        let codemap_dir = self.root.join(CODEMAP_DIR);
+        let mut present: Vec<WalkedFile> = Vec::new();
+        let mut gone: BTreeSet<String> = BTreeSet::new();
+        for path in paths.iter().collect::<BTreeSet<_>>() {
+            if path.starts_with(&codemap_dir) || self.db_files.contains(path) {
+                continue;
+            }
+            let Ok(rel) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            if rel.as_os_str().is_empty() {
+                continue;
+            }
+            let rel_path = rel.display().to_string();
+
+            if path.is_dir() {
+                // Directories created or moved into the tree only report themselves
+                if !self.is_excluded(path, true) {
+                    present.extend(
+                        walk_files(&self.root, path, &self.config)
+                            .into_iter()
+                            .filter(|f| !self.is_excluded(&f.path, false)),
+                    );
+                }
+            } else if path.is_file() && !self.is_excluded(path, false) {
+                match WalkedFile::select(&self.root, path, &self.config) {
+                    Some(file) => present.push(file),
+                    None => {
+                        gone.insert(rel_path);
+                    }
+                }
+            } else {
+                gone.insert(rel_path);
+            }
+        }
+        present.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+        present.dedup_by(|a, b| a.rel_path == b.rel_path);
+
+        if present.is_empty() && gone.is_empty() && !layout_changed {
+            return Ok(stats);
+        }
+
+        // Look up what is already indexed, then parse without the lock
+        let (known_hashes, mut stale) = {
+            let db = lock(db)?;
+            let mut known = HashMap::new();
+            for file in &present {
+                if let Some(record) = db.get_file(&file.rel_path)? {
+                    known.insert(record.path, record.content_hash);
+                }
+            }
+
+            // Indexed files under the vanished paths; a removed directory
+            // only reports itself
+            let mut stale = Vec::new();
+            if !gone.is_empty() {
+                for file in db.get_all_files()? {
+                    let vanished = gone.iter().any(|g| {
+                        file.path == *g
+                            || file
+                                .path
+                                .strip_prefix(g.as_str())
+                                .is_some_and(|rest| rest.starts_with(['/', '\\']))
+                    });
+                    if vanished && !present.iter().any(|f| f.rel_path == file.path) {
+                        stale.push(file);
+                    }
+                }
+            }
+            (known, stale)
+        };
+
+        // Paths that were never indexed and still are not, such as a README
+        if present.is_empty() && stale.is_empty() && !layout_changed {
+            return Ok(stats);
+        }
+
+        let mut extractor = Extractor::with_queries(self.queries.clone());
+        let prepared: Vec<_> = present
+            .iter()
+            .map(|file| {
+                let known_hash = known_hashes.get(&file.rel_path).map(String::as_str);
+                prepare_file(&mut extractor, file, known_hash)
+            })
+            .collect();
+
+        let mut db = lock(db)?;
+        db.begin_transaction()?;
+        let result = (|| -> Result<()> {
+            // Files extracted again, and the files linked to them beforehand
+            let mut extracted = Vec::new();
+            let mut added = false;
+            for prepared in &prepared {
+                if let PreparedFile::Parsed { record, .. } = prepared {
+                    added |= db.get_file(&record.path)?.is_none();
+                    extracted.push(record.path.clone());
+                }
+            }
+            let dependents = db.get_dependent_files(&extracted)?;
+
+            for prepared in prepared {
+                apply_file(&db, prepared, &mut stale, &mut stats)?;
+            }
+            for file in stale {
+                debug!("Removing: {}", file.path);
+                db.delete_file(&file.path)?;
+                stats.removed += 1;
+            }
+            // Files touched without changing leave the graph as it was
+            if stats.files + stats.removed + stats.renamed == 0 && !layout_changed {
+                return Ok(());
+            }
+            stats.resolved_refs = db.resolve_references()? as u64;
+
+            // Links that span files only need redoing around the changed
+            // files, unless files appeared, vanished or moved: any import
+            // may lead somewhere else then
+            let layout = ProjectLayout::load(&self.root);
+            let scope: Vec<String> = extracted.into_iter().chain(dependents).collect();
+            let rebuild = added || stats.removed + stats.renamed > 0 || layout_changed;
+            stats.resolved_imports = match rebuild {
+                true => db.resolve_imports(&layout)?,
+                false => db.resolve_imports_in(&layout, &scope)?,
+            } as u64;
+            match rebuild {
+                true => db.merge_partial_types()?,
+                false => db.merge_partial_types_in(&scope)?,
+            };
+            stats.overrides = match rebuild {
+                true => db.resolve_overrides()?,
+                false => db.resolve_overrides_in(&scope)?,
+            } as u64;
+            Ok(())
+        })();
+        match result {
+            Ok(()) => db.commit()?,
+            Err(err) => {
+                db.rollback()?;
+                return Err(err);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Whether `path` is left out of the index by `exclude_dirs` or gitignore
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let rel = path.strip_prefix(&self.root).unwrap_or(path);
+        let in_excluded_dir = rel.components().any(|c| {
+            self.config
+                .exclude_dirs
+                .iter()
+                .any(|d| c.as_os_str() == d.as_str())
+        });
+        in_excluded_dir || self.ignores.is_ignored(path, is_dir)
+    }
+}
+
+fn lock(db: &Mutex<Database>) -> Result<std::sync::MutexGuard<'_, Database>> {
+    db.lock()
+        .map_err(|e| anyhow!("Database lock poisoned: {}", e))
+}
+
+/// Gitignore rules for single paths, matching what the index walk honours
+struct IgnoreRules {
+    /// Matchers in order of precedence: nested `.gitignore` files deepest
+    /// first, then `.git/info/exclude`, then the global excludes file
+    matchers: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    fn load(root: &Path, config: &IndexConfig) -> Self {
+        // Like the walker, only honour gitignore rules inside a git repository
+        let in_repo = root.ancestors().any(|dir| dir.join(".git").exists());
+        if !config.respect_gitignore || !in_repo {
+            return Self {
+                matchers: Vec::new(),
+            };
+        }
+
+        let mut ignore_files: Vec<PathBuf> = WalkBuilder::new(root)
+            .hidden(false)
+            .git_global(true)
+            .git_exclude(true)
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name() == ".gitignore")
+            .map(|entry| entry.into_path())
+            .collect();
+        ignore_files.sort_by_key(|path| std::cmp::Reverse(path.components().count()));
+
+        let mut matchers: Vec<Gitignore> = ignore_files
+            .iter()
+            .map(|path| Gitignore::new(path).0)
+            .collect();
+
+        let mut exclude = GitignoreBuilder::new(root);
+        exclude.add(root.join(".git").join("info").join("exclude"));
+        if let Ok(matcher) = exclude.build() {
+            matchers.push(matcher);
+        }
+        matchers.push(GitignoreBuilder::new(root).build_global().0);
+
+        Self { matchers }
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        for matcher in &self.matchers {
+            if !path.starts_with(matcher.path()) {
+                continue;
+            }
+            match matcher.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Mutex<Database>, WatchedTree) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        std::fs::write(root.join("lib.rs"), "fn lib() { helper(); }\n").unwrap();
+
+        let mut db = Database::in_memory().unwrap();
+        let config = IndexConfig {
+            root: root.display().to_string(),
+            ..Default::default()
+        };
+        crate::index_codebase(&mut db, &config).unwrap();
+        let tree = WatchedTree::new(root, config, None).unwrap();
+        (dir, Mutex::new(db), tree)
+    }
+
+    #[test]
+    fn test_sync_indexes_touched_files_only() {
+        let (_dir, db, mut tree) = setup();
+        let helper = tree.root.join("helper.rs");
+        std::fs::write(&helper, "pub fn helper() {}\n").unwrap();
+
+        let stats = tree.sync(&db, &[helper, tree.root.join("lib.rs")]).unwrap();
+        assert_eq!(stats.files, 1);
+        assert_eq!(stats.skipped, 1);
+
+        let db = db.lock().unwrap();
+        let helper = db.find_node_by_name("helper").unwrap().unwrap();
+        assert_eq!(db.get_callers(helper.id, 10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_sync_skips_ignored_and_excluded_paths() {
+        let (_dir, db, mut tree) = setup();
+        for rel in ["generated/out.rs", "target/debug/build.rs"] {
+            let path = tree.root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "fn skipped() {}\n").unwrap();
+        }
+
+        let stats = tree
+            .sync(
+                &db,
+                &[
+                    tree.root.join("generated"),
+                    tree.root.join("target/debug/build.rs"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(stats.files, 0);
+        assert!(db
+            .lock()
+            .unwrap()
+            .find_node_by_name("skipped")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_sync_removes_deleted_directories() {
+        let (_dir, db, mut tree) = setup();
+        let nested = tree.root.join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        std::fs::write(nested.join("a.rs"), "fn a() {}\n").unwrap();
+        tree.sync(&db, std::slice::from_ref(&nested)).unwrap();
+        assert!(db
+            .lock()
+            .unwrap()
+            .get_file("nested/a.rs")
+            .unwrap()
+            .is_some());
+
+        std::fs::remove_dir_all(&nested).unwrap();
+        let stats = tree.sync(&db, &[nested]).unwrap();
+        assert_eq!(stats.removed, 1);
+        assert!(db
+            .lock()
+            .unwrap()
+            .get_file("nested/a.rs")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_sync_ignores_database_writes_and_unindexed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("lib.rs"), "use crate::util::helper;\n").unwrap();
+        std::fs::write(root.join("util.rs"), "pub fn helper() {}\n").unwrap();
+        std::fs::create_dir(root.join(CODEMAP_DIR)).unwrap();
+        let db_path = root.join(CODEMAP_DIR).join("index.db");
+
+        let mut db = Database::open(&db_path).unwrap();
+        let config = IndexConfig {
+            root: root.display().to_string(),
+            ..Default::default()
+        };
+        crate::index_codebase(&mut db, &config).unwrap();
+        let mut tree = WatchedTree::new(root.clone(), config, db.path().as_deref()).unwrap();
+        let db = Mutex::new(db);
+
+        // A source edit runs the cross-file passes
+        std::fs::write(root.join("util.rs"), "pub fn helper() { }\n").unwrap();
+        let stats = tree.sync(&db, &[root.join("util.rs")]).unwrap();
+        assert_eq!(stats.files, 1);
+        assert_eq!(stats.resolved_imports, 1);
+
+        // The index's own writes, and files that were never indexed, do not
+        std::fs::write(root.join("README.md"), "# readme\n").unwrap();
+        let mut wal = db_path.clone().into_os_string();
+        wal.push("-wal");
+        let stats = tree
+            .sync(
+                &db,
+                &[
+                    db_path.clone(),
+                    PathBuf::from(wal),
+                    root.join(CODEMAP_DIR).join("queries"),
+                    root.join("README.md"),
+                    root.join("lib.rs"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(stats.files + stats.removed + stats.renamed, 0);
+        assert_eq!(stats.skipped, 1);
+        assert_eq!(stats.resolved_imports, 0);
+        assert!(db.lock().unwrap().get_file("util.rs").unwrap().is_some());
+    }
+
+    /// Every edge, by the files and names of its ends, to compare indexes
+    /// whose node ids differ
+    fn edge_snapshot(db: &Database) -> Vec<String> {
+        let describe = |id: i64| {
+            let node = db.get_node(id).unwrap().unwrap();
+            let name = node.qualified_name.unwrap_or(node.name);
+            format!("{}#{}", node.file_path, name)
+        };
+        let mut edges = Vec::new();
+        for file in db.get_all_files().unwrap() {
+            for node in db.get_nodes_by_file(&file.path).unwrap() {
+                for edge in db.get_outgoing_edges(node.id).unwrap() {
+                    edges.push(format!(
+                        "{} -{}-> {} @{:?}:{:?} ({:.3})",
+                        describe(edge.source_id),
+                        edge.kind.as_str(),
+                        describe(edge.target_id),
+                        edge.line,
+                        edge.column,
+                        edge.confidence
+                    ));
+                }
+            }
+        }
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_sync_relinks_like_a_clean_index() {
+        let (_dir, db, mut tree) = setup();
+        let files = [
+            (
+                "lib.rs",
+                "mod util;\nuse crate::util::helper;\nfn lib() { helper(); }\n",
+            ),
+            ("util.rs", "pub fn helper() {}\n"),
+            ("Base.java", "class Base { void run() {} }\n"),
+            ("Child.java", "class Child extends Base { void run() {} }\n"),
+            (
+                "order.cs",
+                "namespace Shop { public partial class Order { public void Pay() {} } }\n",
+            ),
+            (
+                "order_shipping.cs",
+                "namespace Shop { public partial class Order : IEntity { public void Ship() {} } }\n",
+            ),
+        ];
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(path, code)| {
+                std::fs::write(tree.root.join(path), code).unwrap();
+                tree.root.join(path)
+            })
+            .collect();
+        tree.sync(&db, &paths).unwrap();
+
+        // Edit the files others link to
+        std::fs::write(
+            tree.root.join("util.rs"),
+            "pub fn helper() { other(); }\nfn other() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tree.root.join("order_shipping.cs"),
+            "namespace Shop { public partial class Order : IEntity { public void Ship(int days) {} } }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tree.root.join("Base.java"),
+            "class Base { void run() { stop(); } void stop() {} }\n",
+        )
+        .unwrap();
+        let changed = [
+            tree.root.join("util.rs"),
+            tree.root.join("order_shipping.cs"),
+            tree.root.join("Base.java"),
+        ];
+        let stats = tree.sync(&db, &changed).unwrap();
+        assert_eq!(stats.files, 3);
+
+        let mut clean = Database::in_memory().unwrap();
+        crate::index_codebase(&mut clean, &tree.config).unwrap();
+        let expected = edge_snapshot(&clean);
+        assert!(expected
+            .iter()
+            .any(|e| e.starts_with("lib.rs#crate::util::helper -imports-> util.rs")));
+        assert!(expected
+            .iter()
+            .any(|e| e.starts_with("order.cs#Shop::Order -contains-> order_shipping.cs")));
+        assert!(expected
+            .iter()
+            .any(|e| e.starts_with("Child.java#Child::run -overrides-> Base.java#Base::run")));
+        assert_eq!(edge_snapshot(&db.lock().unwrap()), expected);
+    }
+}
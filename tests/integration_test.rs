@@ -6,7 +6,25 @@ use codemap::db::Database;
 use codemap::extraction::Extractor;
 use codemap::graph::Graph;
 use codemap::types::{EdgeKind, FileRecord, Language, NodeKind};
-use tempfile::tempdir;
+use tempfile::{tempdir, TempDir};
+
+/// Helper to write a project to a temporary directory and index it
+fn index_project(files: &[(&str, &str)]) -> (TempDir, Database, codemap::IndexConfig) {
+    let dir = tempdir().unwrap();
+    for (path, code) in files {
+        let file = dir.path().join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, code).unwrap();
+    }
+
+    let config = codemap::IndexConfig {
+        root: dir.path().display().to_string(),
+        ..Default::default()
+    };
+    let mut db = Database::in_memory().unwrap();
+    codemap::index_codebase(&mut db, &config).unwrap();
+    (dir, db, config)
+}
 
 /// Helper to set up a test database with indexed code
 fn setup_indexed_db(code: &str, filename: &str) -> Database {
@@ -233,6 +251,40 @@ def main():
     assert_eq!(main.unwrap().kind, NodeKind::Function);
 }
 
+#[test]
+fn test_end_to_end_implementations() {
+    let code = r#"
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+trait Solid: Shape {}
+
+struct Circle;
+struct Square;
+
+impl Shape for Circle {
+    fn area(&self) -> f64 { 3.14 }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 { 1.0 }
+}
+"#;
+
+    let db = setup_indexed_db(code, "shapes.rs");
+    let shape = db.find_node_by_name("Shape").unwrap().unwrap();
+
+    let mut names: Vec<_> = db
+        .find_implementations(shape.id)
+        .unwrap()
+        .into_iter()
+        .map(|n| n.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Circle", "Solid", "Square"]);
+}
+
 #[test]
 fn test_database_persistence() {
     let dir = tempdir().unwrap();
@@ -487,6 +539,7 @@ fn test_large_codebase_simulation() {
         file_path: Some("a.rs".to_string()),
         line: None,
         column: None,
+        confidence: 1.0,
     };
     db.insert_edge(&edge_ab).unwrap();
 
@@ -498,6 +551,7 @@ fn test_large_codebase_simulation() {
         file_path: Some("b.rs".to_string()),
         line: None,
         column: None,
+        confidence: 1.0,
     };
     db.insert_edge(&edge_bc).unwrap();
 
@@ -509,6 +563,7 @@ fn test_large_codebase_simulation() {
         file_path: Some("c.rs".to_string()),
         line: None,
         column: None,
+        confidence: 1.0,
     };
     db.insert_edge(&edge_ca).unwrap();
 
@@ -619,6 +674,527 @@ fn test_cross_file_references() {
     assert!(!callers.is_empty());
 }
 
+#[test]
+fn test_resolution_prefers_nearest_definition() {
+    let (_dir, db, _) = index_project(&[
+        ("api/handler.rs", "fn serve() { build(); }"),
+        ("api/build.rs", "fn build() {}"),
+        ("worker/build.rs", "fn build() {}"),
+    ]);
+
+    let serve = db.find_node_by_name("serve").unwrap().unwrap();
+    let callees = db.get_outgoing_edges(serve.id).unwrap();
+    let calls: Vec<_> = callees
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Calls)
+        .collect();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].confidence, 0.9);
+
+    let target = db.get_node(calls[0].target_id).unwrap().unwrap();
+    assert!(target.file_path.ends_with("api/build.rs"));
+}
+
+/// Every edge in the index, keyed by file and qualified name rather than row ids
+fn edge_snapshot(db: &Database, files: &[&str]) -> Vec<String> {
+    let describe = |id: i64| {
+        let node = db.get_node(id).unwrap().unwrap();
+        format!(
+            "{}#{}",
+            node.file_path,
+            node.qualified_name.unwrap_or(node.name)
+        )
+    };
+    let mut edges = Vec::new();
+    for file in files {
+        for node in db.get_nodes_by_file(file).unwrap() {
+            for edge in db.get_outgoing_edges(node.id).unwrap() {
+                edges.push(format!(
+                    "{} -{}-> {} @{:?}:{:?} ({:.3})",
+                    describe(edge.source_id),
+                    edge.kind.as_str(),
+                    describe(edge.target_id),
+                    edge.line,
+                    edge.column,
+                    edge.confidence
+                ));
+            }
+        }
+    }
+    edges.sort();
+    edges
+}
+
+#[test]
+fn test_incremental_reindex_matches_clean_index() {
+    let (dir, mut incremental, config) = index_project(&[
+        ("util.rs", "pub fn helper() { inner(); }\nfn inner() {}\n"),
+        ("main.rs", "fn main() { helper(); added(); }\n"),
+        (
+            "app/run.rs",
+            "fn run() { helper(); }\nimpl Shape for Circle {}\n",
+        ),
+        ("shapes.rs", "trait Shape {}\nstruct Circle;\n"),
+    ]);
+    let files = ["util.rs", "main.rs", "app/run.rs", "shapes.rs"];
+    let write = |path: &str, code: &str| std::fs::write(dir.path().join(path), code).unwrap();
+
+    // Shift `helper`, define the previously missing `added`, and touch the
+    // file holding the types of a cross-file `impl`
+    write(
+        "util.rs",
+        "fn added() {}\n\npub fn helper() { inner(); }\nfn inner() {}\n",
+    );
+    write("shapes.rs", "struct Circle;\ntrait Shape {}\n");
+    let stats = codemap::index_codebase(&mut incremental, &config).unwrap();
+    assert_eq!(stats.files, 2);
+
+    let mut clean = Database::in_memory().unwrap();
+    codemap::index_codebase(&mut clean, &config).unwrap();
+
+    let expected = edge_snapshot(&clean, &files);
+    assert!(expected
+        .iter()
+        .any(|e| e.contains("main.rs#main -calls-> util.rs#helper")));
+    assert!(expected
+        .iter()
+        .any(|e| e.contains("main.rs#main -calls-> util.rs#added")));
+    assert!(expected
+        .iter()
+        .any(|e| e.contains("app/run.rs#run -calls-> util.rs#helper")));
+    assert!(expected
+        .iter()
+        .any(|e| e.contains("shapes.rs#Circle -implements-> shapes.rs#Shape")));
+    assert_eq!(edge_snapshot(&incremental, &files), expected);
+}
+
+#[test]
+fn test_reindex_prunes_deleted_and_excluded_files() {
+    let (dir, mut db, mut config) = index_project(&[
+        ("keep.rs", "fn keep() { gone(); }"),
+        ("gone.rs", "fn gone() {}"),
+        ("gen/out.rs", "fn generated() {}"),
+    ]);
+    assert!(db.find_node_by_name("generated").unwrap().is_some());
+
+    std::fs::remove_file(dir.path().join("gone.rs")).unwrap();
+    config.exclude_dirs.push("gen".to_string());
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+
+    assert_eq!(stats.removed, 2);
+    assert!(db.get_file("gone.rs").unwrap().is_none());
+    assert!(db.find_node_by_name("gone").unwrap().is_none());
+    assert!(db.find_node_by_name("generated").unwrap().is_none());
+    let keep = db.find_node_by_name("keep").unwrap().unwrap();
+    assert!(db.get_outgoing_edges(keep.id).unwrap().is_empty());
+}
+
+#[test]
+fn test_reindex_detects_renames() {
+    let (dir, mut db, config) = index_project(&[
+        ("main.rs", "fn main() { helper(); }"),
+        ("helpers.rs", "fn helper() {}"),
+    ]);
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("lib")).unwrap();
+    let before = db.find_node_by_name("helper").unwrap().unwrap();
+
+    std::fs::rename(root.join("helpers.rs"), root.join("lib/helpers.rs")).unwrap();
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.renamed, 1);
+    assert_eq!(stats.removed, 0);
+    assert_eq!(stats.files, 0);
+
+    // Same node, new location, still called from main
+    let after = db.find_node_by_name("helper").unwrap().unwrap();
+    assert_eq!(after.id, before.id);
+    assert_eq!(after.file_path, "lib/helpers.rs");
+    assert!(db.get_file("helpers.rs").unwrap().is_none());
+    let file_node = db.get_nodes_by_file("lib/helpers.rs").unwrap();
+    assert!(
+        file_node
+            .iter()
+            .any(|n| n.kind == NodeKind::File
+                && n.qualified_name.as_deref() == Some("lib/helpers.rs"))
+    );
+    let callers = db.get_callers(after.id, 10).unwrap();
+    assert_eq!(callers.len(), 1);
+    assert_eq!(callers[0].name, "main");
+}
+
+#[test]
+fn test_reindex_extracts_moves_that_change_language() {
+    let (dir, mut db, config) = index_project(&[
+        ("shape.h", "struct Shape { int sides; };\n"),
+        ("lib.rs", "mod net;\nfn main() {}\n"),
+        ("client.rs", "pub fn connect() {}\n"),
+    ]);
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("net")).unwrap();
+    let connect = db.find_node_by_name("connect").unwrap().unwrap();
+
+    // A header that becomes C++ is extracted again; a Rust file moved into
+    // another module is a rename
+    std::fs::rename(root.join("shape.h"), root.join("shape.hpp")).unwrap();
+    std::fs::rename(root.join("client.rs"), root.join("net/client.rs")).unwrap();
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.renamed, 1);
+    assert_eq!(stats.removed, 1);
+    assert_eq!(stats.files, 1);
+
+    let header = db.get_file("shape.hpp").unwrap().unwrap();
+    assert_eq!(header.language, Language::Cpp);
+    let shape = db.find_node_by_name("Shape").unwrap().unwrap();
+    assert_eq!(shape.language, Language::Cpp);
+    assert!(db.get_file("client.rs").unwrap().is_none());
+    let moved = db.find_node_by_name("connect").unwrap().unwrap();
+    assert_eq!(moved.id, connect.id);
+    assert_eq!(moved.file_path, "net/client.rs");
+}
+
+#[test]
+fn test_import_dependencies() {
+    let (dir, mut db, config) = index_project(&[
+        ("app/__init__.py", ""),
+        ("app/models.py", "class User: pass"),
+        (
+            "app/api/views.py",
+            "import os\nfrom app.models import User\nfrom . import forms",
+        ),
+        ("app/api/forms.py", "from ..models import User"),
+    ]);
+
+    // Imports are resolved again on every run, changed files or not
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.resolved_imports, 3);
+
+    let pairs = |deps: Vec<codemap::db::Dependency>| -> Vec<(String, String)> {
+        deps.into_iter().map(|d| (d.from, d.to)).collect()
+    };
+    let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+    assert_eq!(
+        pairs(db.get_file_dependencies().unwrap()),
+        [
+            pair("app/api/forms.py", "app/models.py"),
+            pair("app/api/views.py", "app/api/forms.py"),
+            pair("app/api/views.py", "app/models.py"),
+        ]
+    );
+    assert_eq!(
+        pairs(db.get_module_dependencies().unwrap()),
+        [pair("app/api", "app")]
+    );
+
+    // Without the module, `app.models.User` can only come from the package
+    std::fs::remove_file(dir.path().join("app/models.py")).unwrap();
+    codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(
+        pairs(db.get_file_dependencies().unwrap()),
+        [
+            pair("app/api/forms.py", "app/__init__.py"),
+            pair("app/api/views.py", "app/__init__.py"),
+            pair("app/api/views.py", "app/api/forms.py"),
+        ]
+    );
+}
+
+#[test]
+fn test_decorated_symbols() {
+    let (_dir, db, _) = index_project(&[
+        ("auth.py", "def login_required(f):\n    return f\n"),
+        (
+            "views.py",
+            "from auth import login_required\n\n\
+             @login_required\n\
+             def profile(): pass\n\n\
+             @app.route('/users', methods=['GET'])\n\
+             @login_required\n\
+             def users(): pass\n",
+        ),
+    ]);
+
+    // Decorators round-trip through the database
+    let users = &db.find_nodes_by_name("users").unwrap()[0];
+    let applied: Vec<(&str, Option<&str>)> = users
+        .decorators
+        .iter()
+        .map(|d| (d.name.as_str(), d.arguments.as_deref()))
+        .collect();
+    assert_eq!(
+        applied,
+        [
+            ("app.route", Some("'/users', methods=['GET']")),
+            ("login_required", None)
+        ]
+    );
+
+    // Both uses link to the in-repo decorator
+    let decorator = &db.find_nodes_by_name("login_required").unwrap()[0];
+    let mut decorated: Vec<String> = db
+        .get_incoming_edges(decorator.id)
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.kind == EdgeKind::Decorates)
+        .map(|e| db.get_node(e.source_id).unwrap().unwrap().name)
+        .collect();
+    decorated.sort();
+    assert_eq!(decorated, ["profile", "users"]);
+
+    let names = |decorator: &str| -> Vec<String> {
+        db.find_decorated(decorator)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.name)
+            .collect()
+    };
+    assert_eq!(names("login_required"), ["profile", "users"]);
+    assert_eq!(names("app.route"), ["users"]);
+    assert_eq!(names("route"), ["users"]);
+    assert!(names("oute").is_empty());
+}
+
+#[test]
+fn test_overrides() {
+    let (_dir, db, _) = index_project(&[
+        (
+            "handlers.py",
+            "class BaseHandler:\n    def handle(self, request): pass\n    def close(self): pass\n\n\
+             class JsonHandler(BaseHandler):\n    def handle(self, request): pass\n    def close(self, force): pass\n\n\
+             class StrictJsonHandler(JsonHandler):\n    def handle(self, request): pass\n\n\
+             def serve(h: JsonHandler):\n    h.handle(None)\n",
+        ),
+        (
+            "Shape.java",
+            "interface Shape { double area(); }\n\
+             abstract class Base implements Shape { void draw() {} }\n\
+             class Circle extends Base {\n    @Override public double area() { return 1; }\n    @Override void draw() {}\n}\n",
+        ),
+        (
+            "widget.ts",
+            "class Widget { render(): void {} constructor(a: number) {} }\n\
+             class Button extends Widget { render(): void {} constructor(a: number) { super(a); } }\n",
+        ),
+        (
+            "stream.hpp",
+            "class Stream { public: virtual int read(char* buf, int n) = 0; };\n\
+             class File : public Stream { public: int read(char* buf, int n) override; };\n",
+        ),
+        (
+            "shape.rs",
+            "pub trait Shape { fn area(&self) -> f64; fn name(&self) -> String { String::new() } }\n\
+             pub struct Square;\n\
+             impl Shape for Square { fn area(&self) -> f64 { 1.0 } }\n\
+             impl std::fmt::Display for Square {\n    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n}\n",
+        ),
+    ]);
+
+    let overrides = |path: &str| -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        for node in db.get_nodes_by_file(path).unwrap() {
+            for edge in db.get_outgoing_edges(node.id).unwrap() {
+                if edge.kind == EdgeKind::Overrides {
+                    let target = db.get_node(edge.target_id).unwrap().unwrap();
+                    pairs.push((
+                        node.qualified_name.clone().unwrap(),
+                        target.qualified_name.unwrap(),
+                    ));
+                }
+            }
+        }
+        pairs
+    };
+    let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+    // Only the nearest definition with the same arity is overridden
+    assert_eq!(
+        overrides("handlers.py"),
+        [
+            pair("JsonHandler::handle", "BaseHandler::handle"),
+            pair("StrictJsonHandler::handle", "JsonHandler::handle"),
+        ]
+    );
+    assert_eq!(
+        overrides("Shape.java"),
+        [
+            pair("Circle::area", "Shape::area"),
+            pair("Circle::draw", "Base::draw"),
+        ]
+    );
+    assert_eq!(
+        overrides("widget.ts"),
+        [pair("Button::render", "Widget::render")]
+    );
+    assert_eq!(
+        overrides("stream.hpp"),
+        [pair("File::read", "Stream::read")]
+    );
+    // Display is not in the project
+    assert_eq!(overrides("shape.rs"), [pair("Square::area", "Shape::area")]);
+
+    // Changing the base method reaches every override and their callers
+    let base = db
+        .find_nodes_by_name("handle")
+        .unwrap()
+        .into_iter()
+        .find(|n| n.qualified_name.as_deref() == Some("BaseHandler::handle"))
+        .unwrap();
+    let impact = Graph::new(&db).analyze_impact_of(base, 3).unwrap();
+    let mut overriding: Vec<_> = impact
+        .overrides
+        .iter()
+        .map(|n| n.qualified_name.clone().unwrap())
+        .collect();
+    overriding.sort();
+    assert_eq!(
+        overriding,
+        ["JsonHandler::handle", "StrictJsonHandler::handle"]
+    );
+    let callers: Vec<_> = impact.direct_callers.iter().map(|n| &n.name).collect();
+    assert_eq!(callers, ["serve"]);
+}
+
+#[test]
+fn test_csharp_partial_types() {
+    let (dir, mut db, config) = index_project(&[
+        (
+            "Entity.cs",
+            "namespace Shop.Models;\n\
+             public abstract class Entity { public virtual string Describe() => \"\"; }\n\
+             public interface IAuditable { void Audit(); }\n",
+        ),
+        (
+            "Order.cs",
+            "namespace Shop.Models;\n\
+             public partial class Order : Entity, IAuditable\n{\n\
+                 public decimal Total { get; set; }\n\
+                 public void Submit() { Validate(); }\n\
+                 public void Audit() { }\n}\n",
+        ),
+        (
+            "Order.Validation.cs",
+            "namespace Shop.Models\n{\n\
+                 partial class Order\n    {\n\
+                     private void Validate() { }\n\
+                     public override string Describe() => \"order\";\n    }\n}\n",
+        ),
+    ]);
+
+    // Each part answers for the members and base types of the whole type
+    let edges = |db: &Database, path: &str, kind: EdgeKind| -> Vec<String> {
+        let part = db
+            .get_nodes_by_file(path)
+            .unwrap()
+            .into_iter()
+            .find(|n| n.name == "Order")
+            .unwrap();
+        let mut names: Vec<String> = db
+            .get_outgoing_edges(part.id)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| db.get_node(e.target_id).unwrap().unwrap().name)
+            .collect();
+        names.sort();
+        names
+    };
+    for path in ["Order.cs", "Order.Validation.cs"] {
+        assert_eq!(
+            edges(&db, path, EdgeKind::Contains),
+            ["Audit", "Describe", "Submit", "Total", "Validate"],
+            "{}",
+            path
+        );
+        assert_eq!(edges(&db, path, EdgeKind::Extends), ["Entity"], "{}", path);
+        assert_eq!(
+            edges(&db, path, EdgeKind::Implements),
+            ["IAuditable"],
+            "{}",
+            path
+        );
+    }
+
+    // Overrides are found through the base type listed by the other part
+    let overridden = |db: &Database, name: &str| -> Vec<String> {
+        let mut targets = Vec::new();
+        for node in db.find_nodes_by_name(name).unwrap() {
+            for edge in db.get_outgoing_edges(node.id).unwrap() {
+                if edge.kind == EdgeKind::Overrides {
+                    let target = db.get_node(edge.target_id).unwrap().unwrap();
+                    targets.push(target.qualified_name.unwrap());
+                }
+            }
+        }
+        targets
+    };
+    assert_eq!(
+        overridden(&db, "Describe"),
+        ["Shop::Models::Entity::Describe"]
+    );
+    assert_eq!(
+        overridden(&db, "Audit"),
+        ["Shop::Models::IAuditable::Audit"]
+    );
+
+    // Links to members that are gone do not survive a reindex
+    std::fs::write(
+        dir.path().join("Order.Validation.cs"),
+        "namespace Shop.Models;\npartial class Order { }\n",
+    )
+    .unwrap();
+    codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(
+        edges(&db, "Order.cs", EdgeKind::Contains),
+        ["Audit", "Submit", "Total"]
+    );
+    assert_eq!(
+        edges(&db, "Order.Validation.cs", EdgeKind::Contains),
+        ["Audit", "Submit", "Total"]
+    );
+}
+
+#[test]
+fn test_parallel_indexing_is_deterministic() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    let mut files = Vec::new();
+    for i in 0..40 {
+        let path = format!("mod{}/file{}.rs", i % 4, i);
+        let file = root.join(&path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(
+            file,
+            format!(
+                "pub fn func_{i}() {{ shared(); func_{}(); }}\npub fn shared() {{}}\n",
+                (i + 1) % 40
+            ),
+        )
+        .unwrap();
+        files.push(path);
+    }
+    let files: Vec<&str> = files.iter().map(String::as_str).collect();
+
+    let index = |workers: usize| {
+        let mut db = Database::in_memory().unwrap();
+        let config = codemap::IndexConfig {
+            root: root.display().to_string(),
+            workers,
+            ..Default::default()
+        };
+        let stats = codemap::index_codebase(&mut db, &config).unwrap();
+        assert_eq!(stats.files, 40);
+        let ids: Vec<_> = files
+            .iter()
+            .flat_map(|f| db.get_nodes_by_file(f).unwrap())
+            .map(|n| (n.id, n.qualified_name))
+            .collect();
+        (ids, edge_snapshot(&db, &files))
+    };
+
+    let single = index(1);
+    assert_eq!(index(4), single);
+    assert_eq!(index(0), single);
+}
+
 #[test]
 fn test_search_performance() {
     let db = Database::in_memory().unwrap();
@@ -667,3 +1243,422 @@ fn test_search_performance() {
         duration
     );
 }
+
+#[test]
+fn test_watch_reindexes_changed_files() {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    let (dir, db, config) = index_project(&[("main.rs", "fn main() { helper(); }")]);
+    let db = Arc::new(Mutex::new(db));
+    let _watcher = codemap::watch::watch(db.clone(), config, Duration::from_millis(100)).unwrap();
+
+    std::fs::write(dir.path().join("helper.rs"), "fn helper() {}").unwrap();
+
+    // Wait for the debounced batch to land
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let helper = loop {
+        if let Some(node) = db.lock().unwrap().find_node_by_name("helper").unwrap() {
+            break node;
+        }
+        assert!(Instant::now() < deadline, "watcher did not index helper.rs");
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    let callers = db.lock().unwrap().get_callers(helper.id, 10).unwrap();
+    assert_eq!(callers.len(), 1);
+    assert_eq!(callers[0].name, "main");
+}
+
+#[test]
+fn test_field_references() {
+    let (_dir, db, _) = index_project(&[
+        (
+            "Account.java",
+            "class Account {\n    private long balance;\n    void deposit(long amount) { this.balance += amount; }\n    long peek() { return balance; }\n}\n",
+        ),
+        (
+            "Ledger.java",
+            "class Ledger {\n    private long balance;\n    void audit(Account account) { long b = account.balance; }\n    void reset() { this.balance = 0; }\n}\n",
+        ),
+    ]);
+
+    let readers = |path: &str| -> Vec<String> {
+        let field = db
+            .get_nodes_by_file(path)
+            .unwrap()
+            .into_iter()
+            .find(|n| n.kind == NodeKind::Field && n.name == "balance")
+            .unwrap();
+        let mut names: Vec<String> = db
+            .get_incoming_edges(field.id)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.kind == EdgeKind::References)
+            .map(|e| db.get_node(e.source_id).unwrap().unwrap().name)
+            .collect();
+        names.sort();
+        names
+    };
+
+    // Reads through `account` bind by receiver type, `this` by enclosing class
+    assert_eq!(readers("Account.java"), ["audit", "deposit"]);
+    assert_eq!(readers("Ledger.java"), ["reset"]);
+
+    // Fields are contained by their class
+    let account = db.get_nodes_by_file("Account.java").unwrap();
+    let class = account.iter().find(|n| n.name == "Account").unwrap();
+    let children: Vec<String> = db
+        .get_outgoing_edges(class.id)
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.kind == EdgeKind::Contains)
+        .map(|e| db.get_node(e.target_id).unwrap().unwrap().name)
+        .collect();
+    assert_eq!(children, ["balance", "deposit", "peek"]);
+}
+
+#[test]
+fn test_methods_attach_to_their_type() {
+    let (_dir, db, _) = index_project(&[
+        ("cart.rs", "pub struct Cart { items: Vec<u32> }\n"),
+        (
+            "cart_ops.rs",
+            "impl Cart {\n    pub fn add(&mut self, item: u32) {}\n}\n\
+             impl Default for Cart {\n    fn default() -> Self { Cart { items: Vec::new() } }\n}\n",
+        ),
+        (
+            "server.go",
+            "package main\ntype Server struct { port int }\n",
+        ),
+        (
+            "handlers.go",
+            "package main\nfunc (s *Server) Run() { s.Stop() }\nfunc (s *Server) Stop() {}\n",
+        ),
+        (
+            "stream.hpp",
+            "class Stream {\npublic:\n    int read(char* buf);\n};\n",
+        ),
+        (
+            "stream.cpp",
+            "#include \"stream.hpp\"\nint Stream::read(char* buf) { return 0; }\n",
+        ),
+    ]);
+
+    // What each type contains, across impl blocks and files
+    let methods = |type_name: &str| -> Vec<(String, String)> {
+        let owner = db
+            .find_nodes_by_name(type_name)
+            .unwrap()
+            .into_iter()
+            .find(|n| matches!(n.kind, NodeKind::Struct | NodeKind::Class))
+            .unwrap();
+        let mut methods: Vec<(String, String)> = db
+            .get_hierarchy(owner.id)
+            .unwrap()
+            .into_iter()
+            .filter(|n| n.kind == NodeKind::Method)
+            .map(|n| (n.qualified_name.unwrap(), n.file_path))
+            .collect();
+        methods.sort();
+        methods
+    };
+    let method = |qualified: &str, path: &str| (qualified.to_string(), path.to_string());
+
+    assert_eq!(
+        methods("Cart"),
+        [
+            method("Cart::add", "cart_ops.rs"),
+            method("Cart::default", "cart_ops.rs")
+        ]
+    );
+    assert_eq!(
+        methods("Server"),
+        [
+            method("Server::Run", "handlers.go"),
+            method("Server::Stop", "handlers.go")
+        ]
+    );
+    assert_eq!(
+        methods("Stream"),
+        [
+            method("Stream::read", "stream.cpp"),
+            method("Stream::read", "stream.hpp")
+        ]
+    );
+}
+
+#[test]
+fn test_arrow_function_callers() {
+    let code = r#"
+const validate = (form) => form.ok;
+export const handleSubmit = async (form) => {
+    if (validate(form)) { await send(form); }
+};
+function sendAll(forms) {
+    forms.forEach((form) => { validate(form); });
+}
+"#;
+    let db = setup_indexed_db(code, "form.tsx");
+    let graph = Graph::new(&db);
+
+    let mut callers: Vec<String> = graph
+        .find_callers("validate", 10)
+        .unwrap()
+        .into_iter()
+        .map(|n| n.name)
+        .collect();
+    callers.sort();
+    assert_eq!(callers, ["handleSubmit", "sendAll"]);
+
+    let submit = db.find_nodes_by_name("handleSubmit").unwrap();
+    assert_eq!(submit.len(), 1);
+    assert!(submit[0].is_exported);
+}
+
+#[test]
+fn test_documentation_is_stored() {
+    let (_dir, db, _) = index_project(&[(
+        "pricing.py",
+        "def discount(total, rate):\n    \"\"\"Apply a discount.\n\n    Args:\n        total: Price before the discount.\n        rate: Fraction taken off.\n\n    Returns:\n        The discounted price.\n    \"\"\"\n    return total * (1 - rate)\n",
+    )]);
+
+    let node = db.find_node_by_name("discount").unwrap().unwrap();
+    assert_eq!(node.doc_summary().as_deref(), Some("Apply a discount."));
+    let tags = node.doc_tags.unwrap();
+    let params: Vec<&str> = tags.params.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(params, ["total", "rate"]);
+    assert_eq!(tags.returns.as_deref(), Some("The discounted price."));
+}
+
+#[test]
+fn test_search_by_signature() {
+    let (_dir, db, _) = index_project(&[(
+        "auth.rs",
+        "pub fn login(conn: &mut Connection, user: &str) -> Result<Session, AuthError> { todo!() }\n\
+         pub fn logout(conn: &Connection) -> Result<(), AuthError> { todo!() }\n\
+         pub fn refresh(pool: Vec<&mut Connection>) -> Option<Session> { None }\n\
+         pub fn verify(token: &str) -> Result<Claims, DecodeError> { todo!() }\n",
+    )]);
+
+    let search = |query: &str, param_type: Option<&str>, returns: Option<&str>| -> Vec<String> {
+        let options = codemap::db::SearchOptions {
+            param_type: param_type.map(String::from),
+            returns: returns.map(String::from),
+            limit: 10,
+            ..Default::default()
+        };
+        let mut names: Vec<String> = db
+            .search_symbols(query, &options)
+            .unwrap()
+            .into_iter()
+            .map(|hit| hit.node.name)
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        search("", Some("&mut Connection"), None),
+        ["login", "refresh"]
+    );
+    assert_eq!(
+        search("", Some("Connection"), None),
+        ["login", "logout", "refresh"]
+    );
+    assert_eq!(
+        search("", None, Some("Result<_, AuthError>")),
+        ["login", "logout"]
+    );
+    assert_eq!(search("log", Some("&mut Connection"), None), ["login"]);
+    assert!(search("", None, None).is_empty());
+
+    let login = db.find_node_by_name("login").unwrap().unwrap();
+    let parts = login.signature_parts.unwrap();
+    assert_eq!(parts.params[1].type_name.as_deref(), Some("&str"));
+    assert_eq!(login.arity, Some(2));
+}
+
+#[test]
+fn test_parse_errors_are_recorded() {
+    let (dir, mut db, config) = index_project(&[
+        (
+            "app.js",
+            "function ok() { return 1; }\n\nfunction broken() {\n  if (x { return 2; }\n}\n",
+        ),
+        ("clean.js", "function clean() {}\n"),
+    ]);
+
+    let diagnostics = db.get_diagnostics(None).unwrap();
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics.iter().all(|d| d.file_path == "app.js"));
+    assert_eq!(diagnostics[0].line, Some(4));
+    assert!(db.get_diagnostics(Some("clean.js")).unwrap().is_empty());
+
+    let stats = db.get_stats().unwrap();
+    assert_eq!(stats.parse_errors, diagnostics.len() as u64);
+    assert_eq!(stats.files_with_errors, 1);
+
+    let broken = db.find_node_by_name("broken").unwrap().unwrap();
+    assert!(broken.has_parse_errors);
+    let ok = db.find_node_by_name("ok").unwrap().unwrap();
+    assert!(!ok.has_parse_errors);
+
+    // Fixing the file clears its diagnostics
+    std::fs::write(
+        dir.path().join("app.js"),
+        "function ok() { return 1; }\n\nfunction broken() {\n  if (x) { return 2; }\n}\n",
+    )
+    .unwrap();
+    codemap::index_codebase(&mut db, &config).unwrap();
+    assert!(db.get_diagnostics(None).unwrap().is_empty());
+    assert_eq!(db.get_stats().unwrap().parse_errors, 0);
+}
+
+#[test]
+fn test_project_queries_extend_extraction() {
+    let (dir, mut db, config) = index_project(&[(
+        "routes.rs",
+        "handler!(get_user);\n\nfn serve() {\n    dispatch(get_user);\n}\n",
+    )]);
+    assert!(db.find_node_by_name("get_user").unwrap().is_none());
+
+    // Symbols declared by a macro, from a query that extends the built-in one
+    let queries = dir.path().join(".codemap/queries/rust");
+    std::fs::create_dir_all(&queries).unwrap();
+    std::fs::write(
+        queries.join("handlers.scm"),
+        "; extends\n\
+         (macro_invocation\n  macro: (identifier) @_macro\n  (token_tree (identifier) @name)\n  (#eq? @_macro \"handler\")) @definition.function\n",
+    )
+    .unwrap();
+
+    // Unchanged files are extracted again with the new queries
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.skipped, 0);
+    let handler = db.find_node_by_name("get_user").unwrap().unwrap();
+    assert_eq!(handler.kind, NodeKind::Function);
+    assert_eq!(handler.start_line, 1);
+    // Built-in patterns still apply
+    assert!(db.find_node_by_name("serve").unwrap().is_some());
+
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.skipped, 1);
+
+    // Broken queries fail the index with the file at fault
+    std::fs::write(
+        queries.join("broken.scm"),
+        "(no_such_node) @definition.function\n",
+    )
+    .unwrap();
+    let err = codemap::index_codebase(&mut db, &config).unwrap_err();
+    assert!(format!("{:#}", err).contains("broken.scm"));
+}
+
+#[test]
+fn test_project_grammars_are_reported() {
+    let (dir, mut db, config) = index_project(&[("lib.rs", "fn main() {}\n")]);
+
+    // Status lists the compiled-in grammars with their ABI
+    let stats = db.get_stats().unwrap();
+    let rust = stats.grammars.iter().find(|g| g.name == "rust").unwrap();
+    assert!(rust.abi_version >= 14);
+    assert!(rust.library.is_none());
+    assert_eq!(
+        stats
+            .grammars
+            .iter()
+            .filter(|g| g.name == "typescript")
+            .count(),
+        1
+    );
+
+    // Grammar libraries are not opened without the user's consent
+    std::fs::create_dir_all(dir.path().join(".codemap")).unwrap();
+    std::fs::write(
+        dir.path().join(".codemap/config.json"),
+        r#"{"grammars": [{"name": "lua", "library": "grammars/lua.so", "extensions": ["lua"]}]}"#,
+    )
+    .unwrap();
+    codemap::index_codebase(&mut db, &config).unwrap();
+
+    // A grammar library that cannot be loaded fails the index
+    let config = codemap::IndexConfig {
+        allow_grammars: true,
+        ..config
+    };
+    let err = codemap::index_codebase(&mut db, &config).unwrap_err();
+    assert!(format!("{:#}", err).contains("grammars/lua.so"));
+}
+
+/// Indexes a project with a grammar built from source at test time. Run it
+/// with `--ignored` and `CODEMAP_TEST_GRAMMAR_DIR` set to a checkout of
+/// tree-sitter-c (the directory holding `src/parser.c`); it needs a C
+/// compiler, `$CC` or `cc`.
+#[test]
+#[ignore = "needs CODEMAP_TEST_GRAMMAR_DIR and a C compiler"]
+fn test_project_grammar_indexes_files() {
+    let grammar_dir =
+        std::env::var_os("CODEMAP_TEST_GRAMMAR_DIR").expect("CODEMAP_TEST_GRAMMAR_DIR is not set");
+    let src = std::path::Path::new(&grammar_dir).join("src");
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+
+    // The C grammar, loaded under a name and extension of its own
+    std::fs::create_dir_all(root.join("grammars")).unwrap();
+    let library = root.join("grammars/minic.so");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = std::process::Command::new(cc)
+        .args(["-shared", "-fPIC", "-O0", "-I"])
+        .arg(&src)
+        .arg(src.join("parser.c"))
+        .arg("-o")
+        .arg(&library)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to build {}", library.display());
+
+    let queries = root.join(".codemap/queries/minic");
+    std::fs::create_dir_all(&queries).unwrap();
+    std::fs::write(
+        root.join(".codemap/config.json"),
+        r#"{"grammars": [{"name": "minic", "library": "grammars/minic.so", "extensions": ["mc"], "symbol": "tree_sitter_c"}]}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        queries.join("symbols.scm"),
+        "(function_definition\n  declarator: (function_declarator declarator: (identifier) @name)) @definition.function\n\
+         (call_expression function: (identifier) @name) @reference.call\n",
+    )
+    .unwrap();
+    std::fs::write(
+        root.join("main.mc"),
+        "int helper(void) { return 1; }\n\nint main(void) { return helper(); }\n",
+    )
+    .unwrap();
+
+    let config = codemap::IndexConfig {
+        root: root.display().to_string(),
+        allow_grammars: true,
+        ..Default::default()
+    };
+    let mut db = Database::in_memory().unwrap();
+    let stats = codemap::index_codebase(&mut db, &config).unwrap();
+    assert_eq!(stats.errors, 0);
+
+    let main = db.find_node_by_name("main").unwrap().unwrap();
+    let helper = db.find_node_by_name("helper").unwrap().unwrap();
+    assert_eq!(main.kind, NodeKind::Function);
+    assert_eq!(main.language.as_str(), "minic");
+    assert_eq!((helper.start_line, main.start_line), (1, 3));
+
+    let callees = db.get_callees(main.id, 10).unwrap();
+    assert_eq!(
+        callees.iter().map(|n| n.id).collect::<Vec<_>>(),
+        [helper.id]
+    );
+
+    let stats = db.get_stats().unwrap();
+    let minic = stats.grammars.iter().find(|g| g.name == "minic").unwrap();
+    assert_eq!(minic.extensions, ["mc"]);
+}